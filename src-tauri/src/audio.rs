@@ -0,0 +1,192 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::settings::VadSettings;
+
+mod capture;
+mod decode;
+mod opus;
+mod vad;
+mod wav;
+
+pub use capture::{AutoStop, Recorder, RecordingResult, RecordingStatus};
+pub use decode::decode_to_whisper_wav;
+pub use vad::{trim_silence, SilenceTrim};
+pub use wav::{is_whisper_wav, wav_duration};
+
+/// Sample rate whisper.cpp expects
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// File extensions accepted for transcription, decoded by `decode_to_whisper_wav`
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "wav", "mp3", "m4a", "mp4", "aac", "flac", "ogg", "oga", "opus", "webm", "mkv", "caf", "aif",
+    "aiff",
+];
+
+/// An audio input device as reported by the OS audio host
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDevice {
+    /// Stable identifier, stored as `selectedMicrophone` in settings
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Payload of the `audio-level` event, emitted while recording
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevel {
+    /// Root mean square of the last interval, 0.0 – 1.0
+    pub rms: f32,
+    /// Peak absolute sample of the last interval, 0.0 – 1.0
+    pub peak: f32,
+    pub elapsed_ms: u64,
+}
+
+/// Audio ready to hand to whisper, see `prepare_for_whisper`
+pub struct PreparedAudio {
+    /// 16 kHz mono WAV to transcribe
+    pub path: PathBuf,
+    /// Length of the audio in seconds, before silence trimming
+    pub duration: f64,
+    pub trimmed_silence: SilenceTrim,
+    /// Intermediate files, removed when this is dropped
+    _temp_files: Vec<TempAudioFile>,
+}
+
+/// Decode `input` to a whisper WAV if it isn't one and cut leading and
+/// trailing silence per `vad`. Blocking; run it off the async runtime.
+pub fn prepare_for_whisper(input: &Path, vad: &VadSettings) -> Result<PreparedAudio, AppError> {
+    let mut path = input.to_path_buf();
+    let mut temp_files = Vec::new();
+
+    // Whisper only reads 16 kHz mono WAV reliably; decode anything else
+    // (m4a, mp3, ogg, flac, webm, ...) first
+    if !is_whisper_wav(input) {
+        let decoded = TempAudioFile::new("decoded");
+        decode_to_whisper_wav(input, decoded.path())?;
+        path = decoded.path().to_path_buf();
+        temp_files.push(decoded);
+    }
+
+    let duration = wav_duration(&path).unwrap_or(0.0);
+
+    // Leading and trailing silence only costs whisper time, so cut it off
+    let mut trimmed_silence = SilenceTrim::default();
+    if vad.trim_silence {
+        let trimmed = TempAudioFile::new("trimmed");
+        match trim_silence(&path, trimmed.path(), vad) {
+            Ok(Some(trim)) => {
+                path = trimmed.path().to_path_buf();
+                trimmed_silence = trim;
+                temp_files.push(trimmed);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Silence trimming failed, using the original audio: {}", e),
+        }
+    }
+
+    Ok(PreparedAudio {
+        path,
+        duration,
+        trimmed_silence,
+        _temp_files: temp_files,
+    })
+}
+
+/// A WAV file in the system temp directory that is deleted when dropped
+pub struct TempAudioFile {
+    path: PathBuf,
+}
+
+impl TempAudioFile {
+    pub fn new(label: &str) -> Self {
+        Self {
+            path: std::env::temp_dir().join(format!(
+                "voice-intelligence-{}-{}.wav",
+                label,
+                uuid::Uuid::new_v4()
+            )),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempAudioFile {
+    fn drop(&mut self) {
+        if self.path.exists() {
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                log::warn!(
+                    "Failed to remove temporary audio {}: {}",
+                    self.path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn device_id(device: &cpal::Device) -> Option<String> {
+    device.id().ok().map(|id| id.to_string())
+}
+
+fn device_name(device: &cpal::Device) -> String {
+    device
+        .description()
+        .map(|d| d.name().to_string())
+        .unwrap_or_else(|_| "Unknown device".to_string())
+}
+
+/// List the input devices of the default audio host
+pub fn list_input_devices() -> Result<Vec<InputDevice>, AppError> {
+    let host = cpal::default_host();
+    let default_id = host.default_input_device().as_ref().and_then(device_id);
+
+    let devices = host.input_devices().map_err(|e| AppError::AudioDevice {
+        device: None,
+        reason: format!("Failed to enumerate input devices: {}", e),
+    })?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let id = device_id(&device)?;
+            Some(InputDevice {
+                is_default: default_id.as_deref() == Some(id.as_str()),
+                name: device_name(&device),
+                id,
+            })
+        })
+        .collect())
+}
+
+/// Find an input device by id (or name, for settings saved by older versions),
+/// falling back to the system default
+fn find_input_device(wanted: Option<&str>) -> Result<cpal::Device, AppError> {
+    let host = cpal::default_host();
+
+    if let Some(wanted) = wanted.filter(|w| !w.is_empty()) {
+        if let Ok(mut devices) = host.input_devices() {
+            if let Some(device) = devices
+                .find(|d| device_id(d).as_deref() == Some(wanted) || device_name(d) == wanted)
+            {
+                return Ok(device);
+            }
+        }
+        log::warn!(
+            "Input device '{}' not found, using the default device",
+            wanted
+        );
+    }
+
+    host.default_input_device()
+        .ok_or_else(|| AppError::AudioDevice {
+            device: wanted.map(String::from),
+            reason: "No input device available".to_string(),
+        })
+}
@@ -0,0 +1,410 @@
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::vad;
+use super::wav::WhisperWavWriter;
+use super::{device_name, find_input_device, AudioLevel};
+use crate::error::AppError;
+
+/// How often `audio-level` events are emitted
+const LEVEL_EVENT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A finished recording
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingResult {
+    /// 16 kHz mono WAV, ready for `transcribe_audio`
+    pub path: String,
+    pub duration_secs: f64,
+    pub device: String,
+}
+
+/// Payload of the `recording-state-changed` event and `get_recording_status`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub recording: bool,
+    pub device: Option<String>,
+    pub elapsed_ms: u64,
+    /// Set once a recording has been stopped and written
+    pub path: Option<String>,
+}
+
+/// Stop a recording by itself once the speaker has gone quiet
+#[derive(Debug, Clone, Copy)]
+pub struct AutoStop {
+    /// Audio quieter than this (dBFS) counts as silence
+    pub threshold_db: f32,
+    pub after: Duration,
+}
+
+enum StopSignal {
+    Finish,
+    Cancel,
+}
+
+struct ActiveRecording {
+    stop_tx: mpsc::Sender<StopSignal>,
+    thread: JoinHandle<Result<RecordingResult, AppError>>,
+    device: String,
+    started: Instant,
+}
+
+/// Native microphone capture held in Tauri managed state. Capture runs on its
+/// own thread so it keeps going while the window is hidden.
+pub struct Recorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(None),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<ActiveRecording>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn status(&self) -> RecordingStatus {
+        match self.lock().as_ref() {
+            // The capture thread exits early when auto-stop kicks in
+            Some(active) => RecordingStatus {
+                recording: !active.thread.is_finished(),
+                device: Some(active.device.clone()),
+                elapsed_ms: active.started.elapsed().as_millis() as u64,
+                path: None,
+            },
+            None => RecordingStatus {
+                recording: false,
+                device: None,
+                elapsed_ms: 0,
+                path: None,
+            },
+        }
+    }
+
+    /// Start capturing from `device_id` (or the default input) into `path`.
+    /// Returns once the device is open and audio is flowing.
+    pub fn start(
+        &self,
+        app: AppHandle,
+        device_id: Option<String>,
+        path: PathBuf,
+        auto_stop: Option<AutoStop>,
+    ) -> Result<RecordingStatus, AppError> {
+        let mut active = self.lock();
+        if active.is_some() {
+            return Err(AppError::RecordingInProgress);
+        }
+
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("audio-capture".to_string())
+            .spawn(move || capture(app, device_id, path, auto_stop, stop_rx, ready_tx))
+            .map_err(|e| AppError::Audio(format!("Failed to start capture thread: {}", e)))?;
+
+        let device = match ready_rx.recv() {
+            Ok(Ok(device)) => device,
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                return Err(e);
+            }
+            Err(_) => {
+                let _ = thread.join();
+                return Err(AppError::Audio(
+                    "Capture thread exited unexpectedly".to_string(),
+                ));
+            }
+        };
+
+        log::info!("Recording started on '{}'", device);
+        *active = Some(ActiveRecording {
+            stop_tx,
+            thread,
+            device: device.clone(),
+            started: Instant::now(),
+        });
+
+        Ok(RecordingStatus {
+            recording: true,
+            device: Some(device),
+            elapsed_ms: 0,
+            path: None,
+        })
+    }
+
+    /// Stop capturing and wait for the WAV file to be written. Also collects
+    /// the result of a recording that stopped itself.
+    pub fn stop(&self) -> Result<RecordingResult, AppError> {
+        let active = self.lock().take().ok_or(AppError::NotRecording)?;
+        let _ = active.stop_tx.send(StopSignal::Finish);
+        let result = active
+            .thread
+            .join()
+            .map_err(|_| AppError::Audio("Capture thread panicked".to_string()))??;
+        log::info!(
+            "Recording stopped: {:.1}s written to {}",
+            result.duration_secs,
+            result.path
+        );
+        Ok(result)
+    }
+
+    /// Stop capturing and discard the recording
+    pub fn cancel(&self) -> Result<(), AppError> {
+        let active = self.lock().take().ok_or(AppError::NotRecording)?;
+        let _ = active.stop_tx.send(StopSignal::Cancel);
+        match active.thread.join() {
+            Ok(Err(AppError::Cancelled)) | Ok(Ok(_)) => {
+                log::info!("Recording cancelled");
+                Ok(())
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(AppError::Audio("Capture thread panicked".to_string())),
+        }
+    }
+}
+
+/// Body of the capture thread. Owns the cpal stream, which is not `Send` on
+/// every platform, and reports the opened device through `ready_tx`.
+fn capture(
+    app: AppHandle,
+    device_id: Option<String>,
+    path: PathBuf,
+    auto_stop: Option<AutoStop>,
+    stop_rx: mpsc::Receiver<StopSignal>,
+    ready_tx: mpsc::Sender<Result<String, AppError>>,
+) -> Result<RecordingResult, AppError> {
+    let (samples_rx, stream, sample_rate, device, failed) = match open_stream(device_id.as_deref())
+    {
+        Ok(opened) => opened,
+        Err(e) => {
+            let _ = ready_tx.send(Err(e));
+            return Err(AppError::Audio("Failed to open input device".to_string()));
+        }
+    };
+    let mut writer = match WhisperWavWriter::create(&path, sample_rate) {
+        Ok(writer) => writer,
+        Err(e) => {
+            let _ = ready_tx.send(Err(e));
+            return Err(AppError::Audio(
+                "Failed to create recording file".to_string(),
+            ));
+        }
+    };
+    let _ = ready_tx.send(Ok(device.clone()));
+
+    let started = Instant::now();
+    let mut meter = LevelMeter::default();
+    let mut last_level_event = Instant::now();
+    let mut silence = auto_stop.map(|auto_stop| {
+        vad::SilenceTimer::new(auto_stop.threshold_db, auto_stop.after, sample_rate)
+    });
+    let mut auto_stopped = false;
+
+    let cancelled = loop {
+        match stop_rx.try_recv() {
+            Ok(StopSignal::Finish) | Err(mpsc::TryRecvError::Disconnected) => break false,
+            Ok(StopSignal::Cancel) => break true,
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+        if failed.load(Ordering::Relaxed) {
+            log::warn!("Input stream failed, finishing recording early");
+            break false;
+        }
+
+        match samples_rx.recv_timeout(LEVEL_EVENT_INTERVAL) {
+            Ok(samples) => {
+                meter.update(&samples);
+                writer.push(&samples)?;
+                if let (Some(timer), Some(auto_stop)) = (silence.as_mut(), auto_stop) {
+                    if timer.push(&samples) {
+                        log::info!("No speech for {:?}, stopping recording", auto_stop.after);
+                        auto_stopped = true;
+                        break false;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break false,
+        }
+
+        if last_level_event.elapsed() >= LEVEL_EVENT_INTERVAL {
+            let _ = app.emit("audio-level", meter.take(started.elapsed()));
+            last_level_event = Instant::now();
+        }
+    };
+
+    // Stop the device, then write whatever it delivered before stopping
+    drop(stream);
+    if cancelled {
+        drop(writer);
+        let _ = std::fs::remove_file(&path);
+        let _ = app.emit(
+            "recording-state-changed",
+            RecordingStatus {
+                recording: false,
+                device: Some(device),
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                path: None,
+            },
+        );
+        return Err(AppError::Cancelled);
+    }
+    for samples in samples_rx.try_iter() {
+        writer.push(&samples)?;
+    }
+    let duration_secs = writer.finish()?;
+
+    let result = RecordingResult {
+        path: path.to_string_lossy().to_string(),
+        duration_secs,
+        device,
+    };
+    let status = RecordingStatus {
+        recording: false,
+        device: Some(result.device.clone()),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        path: Some(result.path.clone()),
+    };
+    let _ = app.emit("recording-state-changed", status.clone());
+    if auto_stopped {
+        // The frontend collects the result with `stop_recording`
+        let _ = app.emit("recording-auto-stopped", status);
+    }
+    Ok(result)
+}
+
+type OpenedStream = (
+    mpsc::Receiver<Vec<f32>>,
+    cpal::Stream,
+    u32,
+    String,
+    Arc<AtomicBool>,
+);
+
+/// Open the input device and start a stream delivering mono `f32` chunks
+fn open_stream(device_id: Option<&str>) -> Result<OpenedStream, AppError> {
+    let device = find_input_device(device_id)?;
+    let name = device_name(&device);
+    let device_error = |reason: String| AppError::AudioDevice {
+        device: Some(name.clone()),
+        reason,
+    };
+
+    let supported = device
+        .default_input_config()
+        .map_err(|e| device_error(format!("No usable input format: {}", e)))?;
+    let config = supported.config();
+    let channels = usize::from(config.channels);
+
+    let (samples_tx, samples_rx) = mpsc::channel();
+    let failed = Arc::new(AtomicBool::new(false));
+
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => {
+            build_stream::<f32>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::I16 => {
+            build_stream::<i16>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::U16 => {
+            build_stream::<u16>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::I32 => {
+            build_stream::<i32>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::I8 => {
+            build_stream::<i8>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::U8 => {
+            build_stream::<u8>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        SampleFormat::F64 => {
+            build_stream::<f64>(&device, &config, channels, samples_tx, failed.clone())
+        }
+        other => return Err(device_error(format!("Unsupported sample format {}", other))),
+    }
+    .map_err(|e| device_error(format!("Failed to open input stream: {}", e)))?;
+
+    stream
+        .play()
+        .map_err(|e| device_error(format!("Failed to start input stream: {}", e)))?;
+
+    Ok((samples_rx, stream, config.sample_rate, name, failed))
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    channels: usize,
+    samples_tx: mpsc::Sender<Vec<f32>>,
+    failed: Arc<AtomicBool>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            // Downmix to mono by averaging the channels of each frame
+            let mono = data
+                .chunks(channels)
+                .map(|frame| {
+                    frame.iter().map(|s| s.to_sample::<f32>()).sum::<f32>() / frame.len() as f32
+                })
+                .collect();
+            let _ = samples_tx.send(mono);
+        },
+        move |e| {
+            log::warn!("Input stream error: {}", e);
+            failed.store(true, Ordering::Relaxed);
+        },
+        None,
+    )
+}
+
+/// RMS and peak over the samples seen since the last event
+#[derive(Default)]
+struct LevelMeter {
+    sum_squares: f64,
+    count: usize,
+    peak: f32,
+}
+
+impl LevelMeter {
+    fn update(&mut self, samples: &[f32]) {
+        for sample in samples {
+            self.sum_squares += f64::from(sample * sample);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.count += samples.len();
+    }
+
+    fn take(&mut self, elapsed: Duration) -> AudioLevel {
+        let rms = if self.count == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.count as f64).sqrt() as f32
+        };
+        let level = AudioLevel {
+            rms: rms.min(1.0),
+            peak: self.peak.min(1.0),
+            elapsed_ms: elapsed.as_millis() as u64,
+        };
+        *self = Self::default();
+        level
+    }
+}
@@ -0,0 +1,245 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::OnceLock;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecRegistry, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::opus::OpusDecoder;
+use super::wav::WhisperWavWriter;
+use super::WHISPER_SAMPLE_RATE;
+use crate::error::AppError;
+
+/// symphonia's decoders plus Opus, which it can demux but not decode
+fn codecs() -> &'static CodecRegistry {
+    static CODECS: OnceLock<CodecRegistry> = OnceLock::new();
+    CODECS.get_or_init(|| {
+        let mut registry = CodecRegistry::new();
+        symphonia::default::register_enabled_codecs(&mut registry);
+        registry.register_all::<OpusDecoder>();
+        registry
+    })
+}
+
+/// Decode any audio file symphonia understands (WAV, FLAC, MP3, AAC/M4A,
+/// ALAC, Ogg Vorbis/Opus, WebM/MKV, CAF, AIFF) into a 16 kHz mono WAV at
+/// `output`. Returns the decoded duration in seconds.
+pub fn decode_to_whisper_wav(input: &Path, output: &Path) -> Result<f64, AppError> {
+    let unsupported = |reason: String| AppError::UnsupportedAudio {
+        path: input.display().to_string(),
+        reason,
+    };
+
+    let file = File::open(input)
+        .map_err(|e| AppError::io(format!("Failed to open {}", input.display()), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = input.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| unsupported(format!("Unrecognized audio container: {}", e)))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| unsupported("The file contains no audio track".to_string()))?;
+    let track_id = track.id;
+
+    let mut decoder = codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| match e {
+            SymphoniaError::Unsupported(_) => unsupported(format!(
+                "No decoder for this audio codec ({})",
+                track.codec_params.codec
+            )),
+            e => unsupported(format!("Failed to open audio decoder: {}", e)),
+        })?;
+
+    // Created on the first decoded packet, once the real sample rate is known
+    let mut writer: Option<WhisperWavWriter> = None;
+    let mut samples: Option<SampleBuffer<f32>> = None;
+    let mut mono = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            // Chained Ogg streams and similar; what was decoded so far is kept
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(AppError::Audio(format!("Failed to read audio: {}", e))),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only loses a few milliseconds
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable audio packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(AppError::Audio(format!("Failed to decode audio: {}", e))),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let buffer =
+            samples.get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+        if buffer.capacity() < decoded.capacity() * channels {
+            *buffer = SampleBuffer::new(decoded.capacity() as u64, spec);
+        }
+        buffer.copy_interleaved_ref(decoded);
+
+        mono.clear();
+        mono.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+
+        let writer = match writer.as_mut() {
+            Some(writer) => writer,
+            None => writer.insert(WhisperWavWriter::create(output, spec.rate)?),
+        };
+        writer.push(&mono)?;
+    }
+
+    let writer = writer.ok_or_else(|| unsupported("The audio track is empty".to_string()))?;
+    let duration = writer.finish()?;
+    log::info!(
+        "Decoded {} to {:.1}s of {} Hz mono audio",
+        input.display(),
+        duration,
+        WHISPER_SAMPLE_RATE
+    );
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/audio")
+            .join(name)
+    }
+
+    fn temp_path(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "decode-test-{}.{}",
+            uuid::Uuid::new_v4(),
+            extension
+        ))
+    }
+
+    /// Decode `input` and return the duration and samples of the output WAV
+    fn decode(input: &Path) -> (f64, Vec<i16>) {
+        let output = temp_path("wav");
+        let duration = decode_to_whisper_wav(input, &output).unwrap();
+
+        let reader = hound::WavReader::open(&output).unwrap();
+        assert!(super::super::wav::is_whisper_spec(&reader.spec()));
+        let samples = reader
+            .into_samples::<i16>()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        std::fs::remove_file(&output).unwrap();
+        (duration, samples)
+    }
+
+    fn peak(samples: &[i16]) -> u16 {
+        samples.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn decodes_stereo_44khz_wav() {
+        let input = temp_path("wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+        for i in 0..44_100 {
+            let t = i as f32 / 44_100.0;
+            // Tone on the left channel only, so the downmix halves it
+            writer
+                .write_sample((16_000.0 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()) as i16)
+                .unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (duration, samples) = decode(&input);
+        std::fs::remove_file(&input).unwrap();
+
+        assert_eq!(duration, 1.0);
+        assert_eq!(samples.len(), 16_000);
+        assert!(
+            (7_000..9_000).contains(&peak(&samples)),
+            "peak {}",
+            peak(&samples)
+        );
+    }
+
+    #[test]
+    fn decodes_flac() {
+        let (duration, samples) = decode(&fixture("tone-22khz-mono.flac"));
+
+        assert_eq!(duration, 0.5);
+        assert_eq!(samples.len(), 8_000);
+        assert!(peak(&samples) > 14_000, "peak {}", peak(&samples));
+    }
+
+    #[test]
+    fn decodes_mp3() {
+        let (duration, samples) = decode(&fixture("silence-32khz-mono.mp3"));
+
+        // 25 frames of 1152 samples at 32 kHz
+        assert_eq!(duration, 0.9);
+        assert_eq!(samples.len(), 14_400);
+    }
+
+    #[test]
+    fn decodes_ogg_opus() {
+        let (duration, samples) = decode(&fixture("tone-stereo.opus"));
+
+        // 25 packets of 960 samples at 48 kHz, minus the 312 sample pre-skip
+        assert_eq!(samples.len(), (25 * 960 - 312) / 3);
+        assert_eq!(duration, samples.len() as f64 / 16_000.0);
+        assert!(peak(&samples) > 5_000, "peak {}", peak(&samples));
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_audio() {
+        let input = temp_path("mp3");
+        std::fs::write(&input, b"not audio at all").unwrap();
+
+        let result = decode_to_whisper_wav(&input, &temp_path("wav"));
+        std::fs::remove_file(&input).unwrap();
+
+        assert!(matches!(result, Err(AppError::UnsupportedAudio { .. })));
+    }
+}
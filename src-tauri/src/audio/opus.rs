@@ -0,0 +1,250 @@
+use audiopus::coder::{Decoder as OpusCoder, GenericCtl};
+use audiopus::packet::Packet as OpusPacket;
+use audiopus::MutSignals;
+use std::sync::Mutex;
+use symphonia::core::audio::{
+    AsAudioBufferRef, AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec,
+};
+use symphonia::core::codecs::{
+    CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult, CODEC_TYPE_OPUS,
+};
+use symphonia::core::errors::{decode_error, unsupported_error, Result};
+use symphonia::core::formats::Packet;
+use symphonia::core::support_codec;
+
+/// Opus always decodes to 48 kHz
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// Longest Opus packet: 120 ms at 48 kHz
+const MAX_PACKET_FRAMES: usize = 5760;
+
+/// Opus decoder for symphonia, which demuxes Ogg and WebM/Matroska Opus
+/// streams but has no decoder for them. Mono and stereo streams only.
+pub struct OpusDecoder {
+    params: CodecParameters,
+    // libopus decoders may move between threads but not be shared
+    decoder: Mutex<OpusCoder>,
+    channels: usize,
+    /// Interleaved output of the last packet
+    interleaved: Vec<f32>,
+    buffer: AudioBuffer<f32>,
+    /// Encoder delay ("pre-skip") still to be dropped from the output
+    skip: usize,
+}
+
+/// The fields of an `OpusHead` identification header that decoding needs
+struct OpusHead {
+    channels: usize,
+    pre_skip: usize,
+    mapping_family: u8,
+}
+
+impl OpusHead {
+    /// Parse the header Ogg and Matroska carry as the track's extra data
+    fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 19 || &data[..8] != b"OpusHead" {
+            return None;
+        }
+        Some(Self {
+            channels: usize::from(data[9]),
+            pre_skip: usize::from(u16::from_le_bytes([data[10], data[11]])),
+            mapping_family: data[18],
+        })
+    }
+}
+
+impl Decoder for OpusDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self> {
+        let head = params.extra_data.as_deref().and_then(OpusHead::parse);
+        if head.as_ref().is_some_and(|head| head.mapping_family != 0) {
+            return unsupported_error("opus: only mono and stereo streams are supported");
+        }
+
+        let channels = head
+            .as_ref()
+            .map(|head| head.channels)
+            .or_else(|| params.channels.map(|c| c.count()))
+            .unwrap_or(0);
+        let (opus_channels, layout) = match channels {
+            1 => (audiopus::Channels::Mono, Channels::FRONT_LEFT),
+            2 => (
+                audiopus::Channels::Stereo,
+                Channels::FRONT_LEFT | Channels::FRONT_RIGHT,
+            ),
+            _ => return unsupported_error("opus: only mono and stereo streams are supported"),
+        };
+
+        let decoder = OpusCoder::new(audiopus::SampleRate::Hz48000, opus_channels)
+            .or_else(|_| decode_error("opus: failed to create decoder"))?;
+        let skip = head
+            .map(|head| head.pre_skip)
+            .or_else(|| params.delay.map(|delay| delay as usize))
+            .unwrap_or(0);
+
+        Ok(Self {
+            params: params.clone(),
+            decoder: Mutex::new(decoder),
+            channels,
+            interleaved: vec![0.0; MAX_PACKET_FRAMES * channels],
+            buffer: AudioBuffer::new(
+                MAX_PACKET_FRAMES as u64,
+                SignalSpec::new(OPUS_SAMPLE_RATE, layout),
+            ),
+            skip,
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[support_codec!(CODEC_TYPE_OPUS, "opus", "Opus")]
+    }
+
+    fn reset(&mut self) {
+        let decoder = self.decoder.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = decoder.reset_state() {
+            log::warn!("Failed to reset Opus decoder: {}", e);
+        }
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        let input = OpusPacket::try_from(packet.buf())
+            .or_else(|_| decode_error("opus: empty or oversized packet"))?;
+        let output = MutSignals::try_from(&mut self.interleaved[..])
+            .or_else(|_| decode_error("opus: output buffer too large"))?;
+        let decoder = self.decoder.get_mut().unwrap_or_else(|e| e.into_inner());
+        let frames = decoder
+            .decode_float(Some(input), output, false)
+            .or_else(|_| decode_error("opus: invalid packet"))?;
+
+        self.buffer.clear();
+        self.buffer.render_reserved(Some(frames));
+        for channel in 0..self.channels {
+            let samples = self.interleaved[..frames * self.channels]
+                .iter()
+                .skip(channel)
+                .step_by(self.channels);
+            for (out, sample) in self.buffer.chan_mut(channel).iter_mut().zip(samples) {
+                *out = *sample;
+            }
+        }
+
+        let skipped = self.skip.min(frames);
+        self.buffer.shift(skipped);
+        self.skip -= skipped;
+
+        Ok(self.buffer.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buffer.as_audio_buffer_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use audiopus::coder::Encoder;
+    use audiopus::Application;
+
+    /// 20 ms of audio per packet
+    const PACKET_FRAMES: usize = 960;
+
+    fn opus_head(channels: u8, pre_skip: u16) -> Box<[u8]> {
+        let mut head = b"OpusHead".to_vec();
+        head.push(1);
+        head.push(channels);
+        head.extend_from_slice(&pre_skip.to_le_bytes());
+        head.extend_from_slice(&OPUS_SAMPLE_RATE.to_le_bytes());
+        head.extend_from_slice(&[0, 0, 0]);
+        head.into_boxed_slice()
+    }
+
+    fn params(head: Box<[u8]>) -> CodecParameters {
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_OPUS).with_extra_data(head);
+        params
+    }
+
+    /// Packets of a 440 Hz tone
+    fn encode_tone(channels: audiopus::Channels, packets: usize) -> Vec<Vec<u8>> {
+        let encoder =
+            Encoder::new(audiopus::SampleRate::Hz48000, channels, Application::Audio).unwrap();
+        let count = channels as usize;
+        (0..packets)
+            .map(|p| {
+                let input: Vec<f32> = (0..PACKET_FRAMES * count)
+                    .map(|i| {
+                        let t = (p * PACKET_FRAMES + i / count) as f32 / OPUS_SAMPLE_RATE as f32;
+                        0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+                    })
+                    .collect();
+                let mut output = vec![0u8; 4000];
+                let len = encoder.encode_float(&input, &mut output).unwrap();
+                output.truncate(len);
+                output
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_stereo_packets_and_drops_pre_skip() {
+        let mut decoder =
+            OpusDecoder::try_new(&params(opus_head(2, 312)), &DecoderOptions::default()).unwrap();
+
+        let mut frames = 0;
+        let mut peak = 0.0f32;
+        for data in encode_tone(audiopus::Channels::Stereo, 10) {
+            let decoded = decoder
+                .decode(&Packet::new_from_slice(0, 0, 0, &data))
+                .unwrap();
+            assert_eq!(decoded.spec().rate, OPUS_SAMPLE_RATE);
+            assert_eq!(decoded.spec().channels.count(), 2);
+            frames += decoded.frames();
+            if let AudioBufferRef::F32(buffer) = decoded {
+                peak = buffer
+                    .chan(1)
+                    .iter()
+                    .fold(peak, |peak, s| peak.max(s.abs()));
+            }
+        }
+
+        assert_eq!(frames, 10 * PACKET_FRAMES - 312);
+        assert!(peak > 0.25, "peak {}", peak);
+    }
+
+    #[test]
+    fn decodes_mono_packets() {
+        let mut decoder =
+            OpusDecoder::try_new(&params(opus_head(1, 0)), &DecoderOptions::default()).unwrap();
+
+        for data in encode_tone(audiopus::Channels::Mono, 3) {
+            let decoded = decoder
+                .decode(&Packet::new_from_slice(0, 0, 0, &data))
+                .unwrap();
+            assert_eq!(decoded.frames(), PACKET_FRAMES);
+            assert_eq!(decoded.spec().channels.count(), 1);
+        }
+    }
+
+    #[test]
+    fn rejects_surround_and_bad_packets() {
+        let mut surround = opus_head(6, 0).to_vec();
+        surround[18] = 1;
+        assert!(
+            OpusDecoder::try_new(&params(surround.into()), &DecoderOptions::default()).is_err()
+        );
+
+        let mut decoder =
+            OpusDecoder::try_new(&params(opus_head(1, 0)), &DecoderOptions::default()).unwrap();
+        assert!(decoder
+            .decode(&Packet::new_from_slice(0, 0, 0, &[]))
+            .is_err());
+    }
+}
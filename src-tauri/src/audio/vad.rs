@@ -0,0 +1,300 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use super::wav::is_whisper_spec;
+use super::WHISPER_SAMPLE_RATE;
+use crate::error::AppError;
+use crate::settings::VadSettings;
+
+/// Length of the frames the energy detector looks at
+const FRAME_MS: usize = 30;
+
+/// Consecutive loud frames needed to count as speech, so clicks and pops
+/// don't keep silence from being trimmed
+const MIN_SPEECH_FRAMES: usize = 3;
+
+/// Silence removed from a recording before transcription
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceTrim {
+    pub leading_secs: f64,
+    pub trailing_secs: f64,
+}
+
+/// Level of `samples` in dBFS, `-inf` for digital silence
+pub fn rms_db(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let mean_square = samples.iter().map(|s| f64::from(s * s)).sum::<f64>() / samples.len() as f64;
+    (10.0 * mean_square.log10()) as f32
+}
+
+/// Whether a chunk of audio is loud enough to be speech
+pub fn is_voiced(samples: &[f32], threshold_db: f32) -> bool {
+    rms_db(samples) >= threshold_db
+}
+
+/// Tells when live audio has been silent for long enough to stop recording.
+/// Silence is measured by the length of the audio received, not wall time.
+pub struct SilenceTimer {
+    threshold_db: f32,
+    limit_samples: u64,
+    silent_samples: u64,
+}
+
+impl SilenceTimer {
+    pub fn new(threshold_db: f32, after: Duration, sample_rate: u32) -> Self {
+        Self {
+            threshold_db,
+            limit_samples: (after.as_secs_f64() * f64::from(sample_rate)) as u64,
+            silent_samples: 0,
+        }
+    }
+
+    /// Feed the next chunk of mono audio. Returns true once the silence since
+    /// the last speech has reached the limit.
+    pub fn push(&mut self, samples: &[f32]) -> bool {
+        if is_voiced(samples, self.threshold_db) {
+            self.silent_samples = 0;
+        } else {
+            self.silent_samples += samples.len() as u64;
+        }
+        self.silent_samples >= self.limit_samples
+    }
+}
+
+/// Sample range from the first to the last speech in 16 kHz mono audio,
+/// widened by `padding_ms` on both sides. `None` when no speech was found.
+fn voiced_range(samples: &[f32], threshold_db: f32, padding_ms: u32) -> Option<Range<usize>> {
+    let frame_len = WHISPER_SAMPLE_RATE as usize * FRAME_MS / 1000;
+    let voiced: Vec<bool> = samples
+        .chunks(frame_len)
+        .map(|frame| is_voiced(frame, threshold_db))
+        .collect();
+
+    // Start of the first and end of the last run of MIN_SPEECH_FRAMES loud frames
+    let runs: Vec<usize> = voiced
+        .windows(MIN_SPEECH_FRAMES)
+        .enumerate()
+        .filter(|(_, window)| window.iter().all(|v| *v))
+        .map(|(i, _)| i)
+        .collect();
+    let first = *runs.first()?;
+    let last = *runs.last()? + MIN_SPEECH_FRAMES;
+
+    let padding = WHISPER_SAMPLE_RATE as usize * padding_ms as usize / 1000;
+    let start = (first * frame_len).saturating_sub(padding);
+    let end = (last * frame_len + padding).min(samples.len());
+    Some(start..end)
+}
+
+/// Write `input` to `output` without its leading and trailing silence.
+///
+/// Only 16 kHz mono 16-bit WAV files are trimmed. Returns `None`, leaving
+/// `output` unwritten, when the file has another format, contains no speech
+/// at all or has nothing worth trimming.
+pub fn trim_silence(
+    input: &Path,
+    output: &Path,
+    settings: &VadSettings,
+) -> Result<Option<SilenceTrim>, AppError> {
+    let mut reader = hound::WavReader::open(input)
+        .map_err(|e| AppError::Audio(format!("Failed to read {}: {}", input.display(), e)))?;
+    let spec = reader.spec();
+    if !is_whisper_spec(&spec) {
+        log::info!("Skipping silence trimming for {:?} audio", spec);
+        return Ok(None);
+    }
+
+    let raw = reader
+        .samples::<i16>()
+        .collect::<Result<Vec<i16>, _>>()
+        .map_err(|e| AppError::Audio(format!("Failed to read {}: {}", input.display(), e)))?;
+    let samples: Vec<f32> = raw
+        .iter()
+        .map(|s| f32::from(*s) / f32::from(i16::MAX))
+        .collect();
+
+    let Some(range) = voiced_range(&samples, settings.threshold_db, settings.padding_ms) else {
+        log::info!(
+            "No speech above {} dBFS found, not trimming",
+            settings.threshold_db
+        );
+        return Ok(None);
+    };
+
+    let rate = f64::from(WHISPER_SAMPLE_RATE);
+    let trim = SilenceTrim {
+        leading_secs: range.start as f64 / rate,
+        trailing_secs: (samples.len() - range.end) as f64 / rate,
+    };
+    // Not worth rewriting the file for less than one frame
+    if trim.leading_secs + trim.trailing_secs < FRAME_MS as f64 / 1000.0 {
+        return Ok(None);
+    }
+
+    let mut writer = hound::WavWriter::create(output, spec)
+        .map_err(|e| AppError::io(format!("Failed to create {}", output.display()), e))?;
+    for sample in &raw[range] {
+        writer
+            .write_sample(*sample)
+            .map_err(|e| AppError::io("Failed to write WAV samples", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| AppError::io("Failed to finalize WAV file", e))?;
+
+    log::info!(
+        "Trimmed {:.2}s of leading and {:.2}s of trailing silence",
+        trim.leading_secs,
+        trim.trailing_secs
+    );
+    Ok(Some(trim))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: usize = WHISPER_SAMPLE_RATE as usize;
+
+    fn silence(secs: f64) -> Vec<f32> {
+        vec![0.0; (secs * RATE as f64) as usize]
+    }
+
+    /// 440 Hz sine; an amplitude of 0.5 is about -9 dBFS
+    fn tone(secs: f64, amplitude: f32) -> Vec<f32> {
+        (0..(secs * RATE as f64) as usize)
+            .map(|i| amplitude * (i as f32 * 440.0 * std::f32::consts::TAU / RATE as f32).sin())
+            .collect()
+    }
+
+    fn write_wav(path: &Path, samples: &[f32]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for sample in samples {
+            writer
+                .write_sample((sample * f32::from(i16::MAX)) as i16)
+                .unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn finds_speech_between_silence() {
+        let samples = [silence(1.0), tone(2.0, 0.5), silence(1.5)].concat();
+
+        let range = voiced_range(&samples, -45.0, 0).unwrap();
+
+        // Frames are 30 ms, so the edges land on the frame around the change
+        let frame = RATE * FRAME_MS / 1000;
+        assert!(range.start.abs_diff(RATE) <= frame, "start {}", range.start);
+        assert!(range.end.abs_diff(3 * RATE) <= frame, "end {}", range.end);
+    }
+
+    #[test]
+    fn padding_widens_the_range_up_to_the_edges() {
+        let samples = [silence(1.0), tone(2.0, 0.5), silence(0.1)].concat();
+
+        let unpadded = voiced_range(&samples, -45.0, 0).unwrap();
+        let padded = voiced_range(&samples, -45.0, 300).unwrap();
+
+        assert_eq!(padded.start, unpadded.start - RATE * 300 / 1000);
+        assert_eq!(padded.end, samples.len());
+    }
+
+    #[test]
+    fn all_silent_input_has_no_speech() {
+        assert!(voiced_range(&silence(2.0), -45.0, 300).is_none());
+        assert!(voiced_range(&[], -45.0, 300).is_none());
+        assert_eq!(rms_db(&silence(0.1)), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn speech_must_be_louder_than_the_threshold() {
+        // About -53 dBFS
+        let quiet = [silence(0.5), tone(1.0, 0.003), silence(0.5)].concat();
+
+        assert!(voiced_range(&quiet, -45.0, 0).is_none());
+        assert!(voiced_range(&quiet, -60.0, 0).is_some());
+    }
+
+    #[test]
+    fn short_clicks_are_not_speech() {
+        // Two loud frames are shorter than MIN_SPEECH_FRAMES
+        let click = tone(2.0 * FRAME_MS as f64 / 1000.0, 0.9);
+        let samples = [silence(0.99), click, silence(1.0)].concat();
+
+        assert!(voiced_range(&samples, -45.0, 0).is_none());
+    }
+
+    #[test]
+    fn trims_leading_and_trailing_silence_from_wav() {
+        let dir = std::env::temp_dir().join(format!("vad-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.wav");
+        let output = dir.join("output.wav");
+        write_wav(
+            &input,
+            &[silence(1.0), tone(2.0, 0.5), silence(1.0)].concat(),
+        );
+        let settings = VadSettings {
+            padding_ms: 0,
+            ..VadSettings::default()
+        };
+
+        let trim = trim_silence(&input, &output, &settings).unwrap().unwrap();
+
+        assert!((trim.leading_secs - 1.0).abs() <= 0.03, "{:?}", trim);
+        assert!((trim.trailing_secs - 1.0).abs() <= 0.03, "{:?}", trim);
+        let trimmed = hound::WavReader::open(&output).unwrap().duration() as f64;
+        assert!((trimmed / RATE as f64 - 2.0).abs() <= 0.06);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn silent_wav_is_not_trimmed() {
+        let dir = std::env::temp_dir().join(format!("vad-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.wav");
+        let output = dir.join("output.wav");
+        write_wav(&input, &silence(2.0));
+
+        let trim = trim_silence(&input, &output, &VadSettings::default()).unwrap();
+
+        assert!(trim.is_none());
+        assert!(!output.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn auto_stop_fires_after_the_silence_limit() {
+        let mut timer = SilenceTimer::new(-45.0, Duration::from_secs(2), WHISPER_SAMPLE_RATE);
+        let chunk = silence(0.5);
+
+        assert!(!timer.push(&tone(0.5, 0.5)));
+        assert!(!timer.push(&chunk));
+        assert!(!timer.push(&chunk));
+        assert!(!timer.push(&chunk));
+        assert!(timer.push(&chunk));
+    }
+
+    #[test]
+    fn speech_resets_the_auto_stop_timer() {
+        let mut timer = SilenceTimer::new(-45.0, Duration::from_secs(1), WHISPER_SAMPLE_RATE);
+
+        assert!(!timer.push(&silence(0.9)));
+        assert!(!timer.push(&tone(0.1, 0.5)));
+        assert!(!timer.push(&silence(0.9)));
+        // Quieter than the threshold counts as silence
+        assert!(timer.push(&tone(0.1, 0.003)));
+    }
+}
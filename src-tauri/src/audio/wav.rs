@@ -0,0 +1,252 @@
+use rubato::{FftFixedIn, Resampler};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use super::WHISPER_SAMPLE_RATE;
+use crate::error::AppError;
+
+/// Input frames per resampler call
+const RESAMPLER_CHUNK: usize = 1024;
+
+/// Streams mono `f32` samples at any rate into a 16 kHz mono 16-bit PCM WAV,
+/// resampling on the fly so long recordings never sit in memory
+pub struct WhisperWavWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+    resampler: Option<FftFixedIn<f32>>,
+    pending: Vec<f32>,
+    input_rate: u32,
+    input_frames: u64,
+    /// Resampler delay still to be dropped from the start of the output
+    skip: usize,
+    written: u64,
+}
+
+impl WhisperWavWriter {
+    pub fn create(path: &Path, input_rate: u32) -> Result<Self, AppError> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let writer = hound::WavWriter::create(path, spec)
+            .map_err(|e| AppError::io(format!("Failed to create {}", path.display()), e))?;
+
+        let resampler = if input_rate == WHISPER_SAMPLE_RATE {
+            None
+        } else {
+            Some(
+                FftFixedIn::new(
+                    input_rate as usize,
+                    WHISPER_SAMPLE_RATE as usize,
+                    RESAMPLER_CHUNK,
+                    2,
+                    1,
+                )
+                .map_err(|e| AppError::Audio(format!("Failed to create resampler: {}", e)))?,
+            )
+        };
+        let skip = resampler.as_ref().map_or(0, |r| r.output_delay());
+
+        Ok(Self {
+            writer,
+            resampler,
+            pending: Vec::new(),
+            input_rate,
+            input_frames: 0,
+            skip,
+            written: 0,
+        })
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Result<(), AppError> {
+        self.input_frames += samples.len() as u64;
+
+        let Some(resampler) = self.resampler.as_mut() else {
+            return write_samples(&mut self.writer, &mut self.written, samples);
+        };
+
+        self.pending.extend_from_slice(samples);
+        let mut output = Vec::new();
+        while self.pending.len() >= resampler.input_frames_next() {
+            let chunk: Vec<f32> = self
+                .pending
+                .drain(..resampler.input_frames_next())
+                .collect();
+            let mut resampled = resampler
+                .process(&[chunk], None)
+                .map_err(|e| AppError::Audio(format!("Resampling failed: {}", e)))?;
+            output.append(&mut resampled[0]);
+        }
+        self.write_resampled(&output)
+    }
+
+    /// Flush buffered audio and finalize the WAV header.
+    /// Returns the duration written, in seconds.
+    pub fn finish(mut self) -> Result<f64, AppError> {
+        if self.resampler.is_some() {
+            let expected =
+                self.input_frames * u64::from(WHISPER_SAMPLE_RATE) / u64::from(self.input_rate);
+
+            // The leftover input first, then silence until the resampler
+            // delay has drained; output past the input's length is dropped
+            let pending = std::mem::take(&mut self.pending);
+            let mut input = (!pending.is_empty()).then(|| vec![pending]);
+            while self.written < expected {
+                let output = self.process_partial(input.take().as_deref())?;
+                let remaining = (expected - self.written) as usize;
+                self.write_resampled(&output[..output.len().min(remaining + self.skip)])?;
+            }
+        }
+
+        let written = self.written;
+        self.writer
+            .finalize()
+            .map_err(|e| AppError::io("Failed to finalize WAV file", e))?;
+        Ok(written as f64 / f64::from(WHISPER_SAMPLE_RATE))
+    }
+
+    fn process_partial(&mut self, input: Option<&[Vec<f32>]>) -> Result<Vec<f32>, AppError> {
+        let resampler = self.resampler.as_mut().expect("resampler present");
+        let mut output = resampler
+            .process_partial(input, None)
+            .map_err(|e| AppError::Audio(format!("Resampling failed: {}", e)))?;
+        Ok(output.swap_remove(0))
+    }
+
+    fn write_resampled(&mut self, samples: &[f32]) -> Result<(), AppError> {
+        let skipped = self.skip.min(samples.len());
+        self.skip -= skipped;
+        write_samples(&mut self.writer, &mut self.written, &samples[skipped..])
+    }
+}
+
+fn write_samples(
+    writer: &mut hound::WavWriter<BufWriter<File>>,
+    written: &mut u64,
+    samples: &[f32],
+) -> Result<(), AppError> {
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        writer
+            .write_sample(value)
+            .map_err(|e| AppError::io("Failed to write WAV samples", e))?;
+    }
+    *written += samples.len() as u64;
+    Ok(())
+}
+
+/// Whether `spec` is the 16 kHz mono 16-bit PCM format whisper reads directly
+pub fn is_whisper_spec(spec: &hound::WavSpec) -> bool {
+    spec.channels == 1
+        && spec.sample_rate == WHISPER_SAMPLE_RATE
+        && spec.bits_per_sample == 16
+        && spec.sample_format == hound::SampleFormat::Int
+}
+
+/// Whether the file at `path` is a WAV whisper can read without conversion
+pub fn is_whisper_wav(path: &Path) -> bool {
+    hound::WavReader::open(path).is_ok_and(|reader| is_whisper_spec(&reader.spec()))
+}
+
+/// Duration of a WAV file in seconds, from its header
+pub fn wav_duration(path: &Path) -> Option<f64> {
+    let reader = hound::WavReader::open(path).ok()?;
+    Some(f64::from(reader.duration()) / f64::from(reader.spec().sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_wav() -> PathBuf {
+        std::env::temp_dir().join(format!("wav-test-{}.wav", uuid::Uuid::new_v4()))
+    }
+
+    fn read_samples(path: &Path) -> Vec<i16> {
+        let samples = hound::WavReader::open(path)
+            .unwrap()
+            .into_samples::<i16>()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+        samples
+    }
+
+    /// Push `input` in uneven chunks so the resampler sees partial input
+    fn write(path: &Path, rate: u32, input: &[f32]) -> f64 {
+        let mut writer = WhisperWavWriter::create(path, rate).unwrap();
+        for chunk in input.chunks(777) {
+            writer.push(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn tone(rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn passes_16khz_audio_through() {
+        let path = temp_wav();
+        let input = tone(WHISPER_SAMPLE_RATE, 4000);
+
+        assert_eq!(write(&path, WHISPER_SAMPLE_RATE, &input), 0.25);
+
+        assert!(is_whisper_wav(&path));
+        let samples = read_samples(&path);
+        assert_eq!(samples.len(), input.len());
+        assert_eq!(samples[100], (input[100] * f32::from(i16::MAX)) as i16);
+    }
+
+    #[test]
+    fn flushes_the_resampler_to_the_exact_length() {
+        let path = temp_wav();
+        let input = tone(44_100, 10_007);
+
+        let duration = write(&path, 44_100, &input);
+
+        assert!(is_whisper_wav(&path));
+        let samples = read_samples(&path);
+        // 10007 frames at 44.1 kHz are 3630.6 frames at 16 kHz
+        assert_eq!(samples.len(), 3630);
+        assert_eq!(duration, 3630.0 / 16_000.0);
+        // The tail comes from the flush and must carry the tone, not silence
+        let tail_peak = samples[3530..]
+            .iter()
+            .map(|s| s.unsigned_abs())
+            .max()
+            .unwrap();
+        assert!(tail_peak > 10_000, "tail peak {}", tail_peak);
+    }
+
+    #[test]
+    fn drops_the_resampler_delay() {
+        let path = temp_wav();
+        // A click a quarter of the way into one second of silence
+        let mut input = vec![0.0; 48_000];
+        input[12_000] = 1.0;
+
+        write(&path, 48_000, &input);
+
+        let samples = read_samples(&path);
+        assert_eq!(samples.len(), 16_000);
+        let peak = (0..samples.len())
+            .max_by_key(|&i| samples[i].unsigned_abs())
+            .unwrap();
+        assert!(peak.abs_diff(4_000) <= 1, "click at {}", peak);
+    }
+
+    #[test]
+    fn reads_duration_from_header() {
+        let path = temp_wav();
+        write(&path, 8_000, &tone(8_000, 12_000));
+
+        assert_eq!(wav_duration(&path), Some(1.5));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,38 +1,45 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use futures::StreamExt;
 use std::io::Write;
-use tauri::{Emitter, Window};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+use tauri_plugin_store::StoreExt;
+
+use crate::audio::{
+    self, AutoStop, InputDevice, Recorder, RecordingResult, RecordingStatus, SilenceTrim,
+};
+use crate::downloads::{Download, DownloadInfo, DownloadKind, DownloadManager};
+use crate::error::AppError;
+use crate::http::{build_client, HttpClient};
+use crate::models::{self, ModelCatalog};
+use crate::queue::{JobQueue, JobRequest, TranscriptionJob};
+use crate::secrets::{write_private_file, SecretName, SecretStatus, SecretStore, VaultStatus};
+use crate::settings::{
+    AppSettings, BundledSecret, CustomModel, ImportPreview, MigrationReport, ModelSettings,
+    ProfileList, SettingsBundle, SettingsStore, VocabularySettings, WhisperOptions,
+    LEGACY_SETTINGS_KEY,
+};
+use crate::storage::{self, StorageUsage, TempFile, TempFileCleanup};
+use crate::subtitles::{self, SubtitleOptions};
+use crate::vocabulary;
+use crate::whisper::{self, Job, Segment, TranscriptionJobs};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AppSettings {
-    pub hotkey: String,
-    pub language: String,
-    pub enrichment_mode: String,
-    pub output_target: String,
-    pub retention_days: u32,
-    pub llm_provider: String,
-}
-
-impl Default for AppSettings {
-    fn default() -> Self {
-        Self {
-            hotkey: "CommandOrControl+Shift+Space".to_string(),
-            language: "en".to_string(),
-            enrichment_mode: "clean-transcript".to_string(),
-            output_target: "clipboard".to_string(),
-            retention_days: 7,
-            llm_provider: "openai".to_string(),
-        }
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TranscriptionResult {
     pub text: String,
+    /// The requested language, or the one whisper detected for "auto"
     pub language: String,
+    /// Whisper's confidence in the detected language, 0.0 – 1.0
+    pub language_probability: Option<f64>,
+    /// Length of the audio in seconds, before silence trimming
     pub duration: f64,
+    /// Silence cut from the start and end before running whisper
+    pub trimmed_silence: SilenceTrim,
+    /// Timed segments, relative to the original audio
+    pub segments: Vec<Segment>,
 }
 
 #[tauri::command]
@@ -41,18 +48,509 @@ pub fn get_app_version() -> String {
 }
 
 #[tauri::command]
-pub fn get_settings() -> AppSettings {
-    // TODO: Load from persistent storage
-    AppSettings::default()
+pub fn get_settings(store: State<'_, SettingsStore>) -> AppSettings {
+    store.get()
+}
+
+#[tauri::command]
+pub fn save_settings(
+    store: State<'_, SettingsStore>,
+    http: State<'_, HttpClient>,
+    queue: State<'_, JobQueue>,
+    settings: AppSettings,
+) -> Result<(), AppError> {
+    settings.validate()?;
+    // Reject proxy or CA settings the client can't use before persisting
+    // them, and only switch to the new client once they are saved
+    let client = build_client(&settings.network)?;
+    let network = settings.network.clone();
+    store.save(settings).map_err(AppError::Settings)?;
+    http.install(client, &network);
+    // Start more queued jobs right away if parallelism went up
+    queue.wake();
+    Ok(())
+}
+
+/// Vocabulary and replacement dictionary of the active profile
+#[tauri::command]
+pub fn get_vocabulary(store: State<'_, SettingsStore>) -> VocabularySettings {
+    store.get().vocabulary
+}
+
+/// Replace the vocabulary of the active profile. Entries are trimmed and
+/// duplicates dropped; the stored vocabulary is returned.
+#[tauri::command]
+pub fn set_vocabulary(
+    store: State<'_, SettingsStore>,
+    vocabulary: VocabularySettings,
+) -> Result<VocabularySettings, AppError> {
+    let mut settings = store.get();
+    settings.vocabulary = vocabulary.normalized();
+    settings.validate()?;
+    let vocabulary = settings.vocabulary.clone();
+    store.save(settings).map_err(AppError::Settings)?;
+    Ok(vocabulary)
+}
+
+/// Report of the settings migration performed at startup, if one ran
+#[tauri::command]
+pub fn get_settings_migration_report(store: State<'_, SettingsStore>) -> Option<MigrationReport> {
+    store.migration_report()
+}
+
+/// Payload of the `profile-switched` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitchedEvent {
+    pub profile: String,
+    pub settings: AppSettings,
+}
+
+/// List all settings profiles and the active one
+#[tauri::command]
+pub fn list_profiles(store: State<'_, SettingsStore>) -> ProfileList {
+    store.list_profiles()
+}
+
+/// Create a new profile with default settings
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfileList, AppError> {
+    let profiles = app.state::<SettingsStore>().create_profile(&name, None).map_err(AppError::Settings)?;
+    crate::refresh_tray_menu(&app);
+    Ok(profiles)
+}
+
+/// Create a new profile as a copy of an existing one
+#[tauri::command]
+pub fn clone_profile(app: AppHandle, source: String, name: String) -> Result<ProfileList, AppError> {
+    let profiles = app.state::<SettingsStore>().create_profile(&name, Some(&source)).map_err(AppError::Settings)?;
+    crate::refresh_tray_menu(&app);
+    Ok(profiles)
+}
+
+/// Switch the active profile
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<AppSettings, AppError> {
+    activate_profile(&app, &name)
 }
 
+/// Delete a profile that is not currently active
 #[tauri::command]
-pub fn save_settings(settings: AppSettings) -> Result<(), String> {
-    // TODO: Save to persistent storage
-    log::info!("Saving settings: {:?}", settings);
+pub fn delete_profile(app: AppHandle, name: String) -> Result<ProfileList, AppError> {
+    let profiles = app.state::<SettingsStore>().delete_profile(&name).map_err(AppError::Settings)?;
+    crate::refresh_tray_menu(&app);
+    Ok(profiles)
+}
+
+/// Export settings profiles to a JSON bundle file. Exports every profile
+/// unless `profile` is given. API keys are left out unless `include_secrets`.
+#[tauri::command]
+pub fn export_settings(
+    store: State<'_, SettingsStore>,
+    secrets: State<'_, SecretStore>,
+    path: String,
+    profile: Option<String>,
+    include_secrets: Option<bool>,
+) -> Result<(), AppError> {
+    let mut bundle = store.export_bundle(profile.as_deref()).map_err(AppError::Settings)?;
+    if include_secrets.unwrap_or(false) {
+        for name in SecretName::ALL {
+            if let Some(value) = secrets.get(name)? {
+                bundle.secrets.push(BundledSecret { name, value });
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize settings bundle: {}", e)))?;
+    // API keys may be in it, so it gets the same protection as the vault
+    write_private_file(Path::new(&path), json.as_bytes())
+        .map_err(|e| AppError::io("Failed to write settings bundle", e))?;
+
+    log::info!("Exported {} settings profile(s) to {}", bundle.profiles.len(), path);
     Ok(())
 }
 
+/// Validate a settings bundle and describe what importing it would change
+#[tauri::command]
+pub fn preview_settings_import(
+    store: State<'_, SettingsStore>,
+    path: String,
+) -> Result<ImportPreview, AppError> {
+    let bundle = read_settings_bundle(&path)?;
+    Ok(store.preview_import(&bundle))
+}
+
+/// Import a settings bundle previously checked with `preview_settings_import`
+#[tauri::command]
+pub fn import_settings(app: AppHandle, path: String) -> Result<ProfileList, AppError> {
+    let bundle = read_settings_bundle(&path)?;
+    let profiles = apply_settings_bundle(
+        &app.state::<SettingsStore>(),
+        &app.state::<SecretStore>(),
+        bundle,
+    )?;
+    log::info!("Imported settings bundle from {}", path);
+
+    // The active profile may have been overwritten; let the frontend re-apply it
+    activate_profile(&app, &profiles.active_profile)?;
+    Ok(profiles)
+}
+
+/// Import a bundle's profiles, then its secrets. The secret store is checked
+/// first, so a locked vault or unreachable keyring fails the import before
+/// any setting has changed.
+fn apply_settings_bundle(
+    store: &SettingsStore,
+    secrets: &SecretStore,
+    mut bundle: SettingsBundle,
+) -> Result<ProfileList, AppError> {
+    let bundled_secrets = std::mem::take(&mut bundle.secrets);
+    let names: Vec<SecretName> = bundled_secrets.iter().map(|s| s.name).collect();
+    secrets.check_writable(&names)?;
+
+    let profiles = store.import_bundle(bundle).map_err(AppError::Settings)?;
+    for secret in bundled_secrets {
+        secrets.set(secret.name, &secret.value)?;
+    }
+    Ok(profiles)
+}
+
+fn read_settings_bundle(path: &str) -> Result<SettingsBundle, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::io("Failed to read settings bundle", e))?;
+    SettingsBundle::parse(&content).map_err(AppError::Settings)
+}
+
+/// Store an API key in the secret store
+#[tauri::command]
+pub fn set_secret(
+    secrets: State<'_, SecretStore>,
+    name: SecretName,
+    value: String,
+) -> Result<SecretStatus, AppError> {
+    secrets.set(name, &value)?;
+    secrets.status(name)
+}
+
+/// Report which API keys are configured, for one key or all of them
+#[tauri::command]
+pub fn get_secret_status(
+    secrets: State<'_, SecretStore>,
+    name: Option<SecretName>,
+) -> Result<Vec<SecretStatus>, AppError> {
+    match name {
+        Some(name) => Ok(vec![secrets.status(name)?]),
+        None => SecretName::ALL.iter().map(|n| secrets.status(*n)).collect(),
+    }
+}
+
+/// Remove an API key from the secret store
+#[tauri::command]
+pub fn delete_secret(
+    secrets: State<'_, SecretStore>,
+    name: SecretName,
+) -> Result<SecretStatus, AppError> {
+    secrets.delete(name)?;
+    secrets.status(name)
+}
+
+/// Lock state of the secret vault, or `None` when secrets live in the OS keyring
+#[tauri::command]
+pub fn get_vault_status(secrets: State<'_, SecretStore>) -> Option<VaultStatus> {
+    secrets.vault_backend().map(|vault| vault.status())
+}
+
+/// Unlock the secret vault, creating it on first use
+#[tauri::command]
+pub async fn unlock_vault(app: AppHandle, passphrase: String) -> Result<VaultStatus, AppError> {
+    let handle = app.clone();
+    // Key derivation is intentionally expensive; keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        let secrets = handle.state::<SecretStore>();
+        let vault = secrets
+            .vault_backend()
+            .ok_or_else(|| {
+                AppError::InvalidInput(
+                    "Secrets are stored in the OS keyring; there is no vault to unlock".to_string(),
+                )
+            })?;
+        vault.unlock(&passphrase)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Vault unlock task failed: {}", e)))??;
+
+    // Plaintext keys couldn't be moved into the vault while it was locked
+    migrate_plaintext_keys(&app);
+    Ok(notify_vault_status(&app))
+}
+
+/// Lock the secret vault immediately
+#[tauri::command]
+pub fn lock_vault(app: AppHandle) -> Result<VaultStatus, AppError> {
+    app.state::<SecretStore>()
+        .vault_backend()
+        .ok_or_else(|| {
+            AppError::InvalidInput(
+                "Secrets are stored in the OS keyring; there is no vault to lock".to_string(),
+            )
+        })?
+        .lock();
+    Ok(notify_vault_status(&app))
+}
+
+/// Move API keys the frontend used to keep in plain text in its plugin-store
+/// settings into the secret store, and remove them from the file. A key that
+/// can't be stored yet, e.g. while the vault is locked, stays for the next try.
+pub fn migrate_plaintext_keys(app: &AppHandle) {
+    let store = match app.store(storage::HISTORY_STORE_FILE_NAME) {
+        Ok(store) => store,
+        Err(e) => {
+            log::warn!("Failed to open {}: {}", storage::HISTORY_STORE_FILE_NAME, e);
+            return;
+        }
+    };
+    let Some(serde_json::Value::Object(mut legacy)) = store.get(LEGACY_SETTINGS_KEY) else {
+        return;
+    };
+
+    let secrets = app.state::<SecretStore>();
+    let mut moved = Vec::new();
+    for name in SecretName::ALL {
+        let Some(value) = legacy.get(name.legacy_field()) else {
+            continue;
+        };
+        let key = value.as_str().unwrap_or_default().trim();
+        if !key.is_empty() {
+            // A key entered since then is newer than the plaintext one
+            let stored = match secrets.get(name) {
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => secrets.set(name, key),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = stored {
+                log::warn!("Keeping plaintext '{}' key until it can be moved: {}", name.key(), e);
+                continue;
+            }
+        }
+        legacy.remove(name.legacy_field());
+        moved.push(name.key());
+    }
+    if moved.is_empty() {
+        return;
+    }
+
+    store.set(LEGACY_SETTINGS_KEY, serde_json::Value::Object(legacy));
+    match store.save() {
+        Ok(()) => log::info!("Moved plaintext API keys into the secret store: {:?}", moved),
+        Err(e) => log::warn!("Failed to remove plaintext API keys from {}: {}", storage::HISTORY_STORE_FILE_NAME, e),
+    }
+}
+
+/// Emit `vault-status-changed` and refresh the tray lock indicator
+pub fn notify_vault_status(app: &AppHandle) -> VaultStatus {
+    let status = app
+        .state::<SecretStore>()
+        .vault_backend()
+        .map(|vault| vault.status())
+        .unwrap_or(VaultStatus {
+            initialized: false,
+            locked: false,
+        });
+    let _ = app.emit("vault-status-changed", status.clone());
+    crate::refresh_tray_menu(app);
+    status
+}
+
+/// Make `name` the active profile and emit `profile-switched` so the
+/// frontend re-applies hotkey, provider and output target in one go.
+/// Shared by the `switch_profile` command and the tray submenu.
+pub fn activate_profile(app: &AppHandle, name: &str) -> Result<AppSettings, AppError> {
+    let settings = app.state::<SettingsStore>().switch_profile(name).map_err(AppError::Settings)?;
+    log::info!("Switched to settings profile '{}'", name);
+
+    if let Err(e) = app.state::<HttpClient>().configure(&settings.network) {
+        log::warn!("Keeping previous HTTP client for profile '{}': {}", name, e);
+    }
+
+    let _ = app.emit("profile-switched", ProfileSwitchedEvent {
+        profile: name.to_string(),
+        settings: settings.clone(),
+    });
+    crate::refresh_tray_menu(app);
+
+    Ok(settings)
+}
+
+/// List the microphones available to native capture
+#[tauri::command]
+pub async fn list_input_devices() -> Result<Vec<InputDevice>, AppError> {
+    tauri::async_runtime::spawn_blocking(audio::list_input_devices)
+        .await
+        .map_err(|e| AppError::Internal(format!("Device enumeration task failed: {}", e)))?
+}
+
+/// Start native capture into a 16 kHz mono WAV in the app cache directory.
+/// Uses the microphone from settings unless `device_id` is given.
+#[tauri::command]
+pub async fn start_recording(
+    app: AppHandle,
+    device_id: Option<String>,
+) -> Result<RecordingStatus, AppError> {
+    let settings = app.state::<SettingsStore>().get();
+    let device_id = device_id.or(settings.selected_microphone);
+    let auto_stop = (settings.vad.auto_stop_silence_secs > 0).then(|| AutoStop {
+        threshold_db: settings.vad.threshold_db,
+        after: std::time::Duration::from_secs(u64::from(settings.vad.auto_stop_silence_secs)),
+    });
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve cache directory: {}", e)))?
+        .join(storage::RECORDINGS_DIR_NAME);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Failed to create {}", dir.display()), e))?;
+    let path = dir.join(format!(
+        "recording-{}.wav",
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+    ));
+
+    let handle = app.clone();
+    let status = tauri::async_runtime::spawn_blocking(move || {
+        handle.state::<Recorder>().start(handle.clone(), device_id, path, auto_stop)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Recording task failed: {}", e)))??;
+
+    let _ = app.emit("recording-state-changed", status.clone());
+    Ok(status)
+}
+
+/// Stop native capture and return the written WAV file
+#[tauri::command]
+pub async fn stop_recording(app: AppHandle) -> Result<RecordingResult, AppError> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<Recorder>().stop())
+        .await
+        .map_err(|e| AppError::Internal(format!("Recording task failed: {}", e)))?
+}
+
+/// Stop native capture and discard the audio
+#[tauri::command]
+pub async fn cancel_recording(app: AppHandle) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<Recorder>().cancel())
+        .await
+        .map_err(|e| AppError::Internal(format!("Recording task failed: {}", e)))?
+}
+
+#[tauri::command]
+pub fn get_recording_status(recorder: State<'_, Recorder>) -> RecordingStatus {
+    recorder.status()
+}
+
+/// An audio file accepted by `import_audio_files`, waiting to be transcribed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedAudioFile {
+    pub id: String,
+    pub path: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+}
+
+/// A file `import_audio_files` refused, with the reason
+#[derive(Debug, Serialize)]
+pub struct RejectedAudioFile {
+    pub path: String,
+    pub error: AppError,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioImportResult {
+    pub queued: Vec<ImportedAudioFile>,
+    pub rejected: Vec<RejectedAudioFile>,
+}
+
+/// Validate audio files picked in a dialog or dropped on the window and
+/// queue them for transcription and enrichment
+#[tauri::command]
+pub fn import_audio_files(app: AppHandle, paths: Vec<String>) -> Result<AudioImportResult, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::InvalidInput("No audio files given".to_string()));
+    }
+    Ok(queue_audio_files(&app, paths))
+}
+
+/// Emit `audio-import-queued` with the files that passed validation. The
+/// frontend works through the queue. Shared with the tray "Transcribe File…" item.
+///
+/// The webview's file system scope is left alone: transcription reads the
+/// files in the backend, so importing a path never lets the frontend read it.
+pub fn queue_audio_files(app: &AppHandle, paths: Vec<String>) -> AudioImportResult {
+    let mut result = AudioImportResult {
+        queued: Vec::new(),
+        rejected: Vec::new(),
+    };
+
+    for path in paths {
+        match validate_audio_file(&path) {
+            Ok(file) => result.queued.push(file),
+            Err(error) => {
+                log::warn!("Rejected audio import {}: {}", path, error);
+                result.rejected.push(RejectedAudioFile { path, error });
+            }
+        }
+    }
+
+    if !result.queued.is_empty() {
+        log::info!("Queued {} audio file(s) for transcription", result.queued.len());
+        let _ = app.emit("audio-import-queued", result.queued.clone());
+    }
+    result
+}
+
+fn validate_audio_file(path: &str) -> Result<ImportedAudioFile, AppError> {
+    let path_buf = PathBuf::from(path);
+    let metadata = std::fs::metadata(&path_buf).map_err(|_| AppError::AudioNotFound {
+        path: path.to_string(),
+    })?;
+    if !metadata.is_file() {
+        return Err(AppError::InvalidInput(format!("'{}' is not a file", path)));
+    }
+    if metadata.len() == 0 {
+        return Err(AppError::UnsupportedAudio {
+            path: path.to_string(),
+            reason: "The file is empty".to_string(),
+        });
+    }
+
+    let extension = path_buf
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !audio::SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AppError::UnsupportedAudio {
+            path: path.to_string(),
+            reason: format!(
+                "'.{}' files are not supported. Supported formats: {}",
+                extension,
+                audio::SUPPORTED_EXTENSIONS.join(", ")
+            ),
+        });
+    }
+
+    Ok(ImportedAudioFile {
+        id: uuid::Uuid::new_v4().to_string(),
+        file_name: path_buf
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string()),
+        path: path.to_string(),
+        size_bytes: metadata.len(),
+    })
+}
+
 /// Result of whisper availability check
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WhisperCheckResult {
@@ -62,7 +560,20 @@ pub struct WhisperCheckResult {
 
 /// Check if whisper is available on the system
 #[tauri::command]
-pub fn check_whisper_available(saved_path: Option<String>) -> WhisperCheckResult {
+pub async fn check_whisper_available(saved_path: Option<String>) -> WhisperCheckResult {
+    // Probing runs `--help` on candidate binaries
+    tauri::async_runtime::spawn_blocking(move || detect_whisper(saved_path))
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Whisper check task failed: {}", e);
+            WhisperCheckResult {
+                available: false,
+                path: None,
+            }
+        })
+}
+
+fn detect_whisper(saved_path: Option<String>) -> WhisperCheckResult {
     // First check the saved path if provided
     if let Some(ref path) = saved_path {
         let path_buf = PathBuf::from(path);
@@ -148,7 +659,20 @@ pub fn check_whisper_available(saved_path: Option<String>) -> WhisperCheckResult
 
 /// Verify a user-selected whisper path
 #[tauri::command]
-pub fn verify_whisper_path(path: String) -> WhisperCheckResult {
+pub async fn verify_whisper_path(path: String) -> WhisperCheckResult {
+    // Runs the binary with `--help`, which may take a while
+    tauri::async_runtime::spawn_blocking(move || probe_whisper_path(path))
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Whisper path check task failed: {}", e);
+            WhisperCheckResult {
+                available: false,
+                path: None,
+            }
+        })
+}
+
+fn probe_whisper_path(path: String) -> WhisperCheckResult {
     let path_buf = PathBuf::from(&path);
 
     if !path_buf.exists() {
@@ -189,30 +713,21 @@ pub struct OllamaCheckResult {
 
 /// Check if OLLAMA service is running via HTTP API
 #[tauri::command]
-pub async fn check_ollama_available(base_url: Option<String>) -> OllamaCheckResult {
+pub async fn check_ollama_available(app: AppHandle, base_url: Option<String>) -> OllamaCheckResult {
     let url = base_url.unwrap_or_else(|| "http://localhost:11434".to_string());
     let version_endpoint = format!("{}/api/version", url);
 
     log::info!("Checking OLLAMA availability at: {}", version_endpoint);
 
-    // Create HTTP client with timeout
-    let client = match reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("Failed to create HTTP client: {}", e);
-            return OllamaCheckResult {
-                available: false,
-                version: None,
-                base_url: url,
-            };
-        }
-    };
+    let client = app.state::<HttpClient>().client();
 
     // Try to reach the OLLAMA version endpoint
-    match client.get(&version_endpoint).send().await {
+    match client
+        .get(&version_endpoint)
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+    {
         Ok(response) => {
             if response.status().is_success() {
                 // Try to parse the version from the response text
@@ -267,130 +782,121 @@ pub struct OllamaModel {
 /// Result of getting OLLAMA models
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaModelsResult {
-    pub success: bool,
     pub models: Vec<OllamaModel>,
-    pub error: Option<String>,
 }
 
 /// Get list of installed models from OLLAMA
 #[tauri::command]
-pub async fn get_ollama_models(base_url: Option<String>) -> OllamaModelsResult {
+pub async fn get_ollama_models(
+    app: AppHandle,
+    base_url: Option<String>,
+) -> Result<OllamaModelsResult, AppError> {
     let url = base_url.unwrap_or_else(|| "http://localhost:11434".to_string());
     let tags_endpoint = format!("{}/api/tags", url);
 
     log::info!("Getting OLLAMA models from: {}", tags_endpoint);
 
-    // Create HTTP client with timeout
-    let client = match reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("Failed to create HTTP client: {}", e);
-            return OllamaModelsResult {
-                success: false,
-                models: vec![],
-                error: Some(format!("Failed to create HTTP client: {}", e)),
-            };
-        }
-    };
+    let client = app.state::<HttpClient>().client();
 
     // Query the OLLAMA tags endpoint
-    match client.get(&tags_endpoint).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.text().await {
-                    Ok(text) => {
-                        // Parse the JSON response
-                        match serde_json::from_str::<serde_json::Value>(&text) {
-                            Ok(json) => {
-                                let models = json.get("models")
-                                    .and_then(|m| m.as_array())
-                                    .map(|arr| {
-                                        arr.iter()
-                                            .filter_map(|m| {
-                                                Some(OllamaModel {
-                                                    name: m.get("name")?.as_str()?.to_string(),
-                                                    model: m.get("model")
-                                                        .and_then(|v| v.as_str())
-                                                        .unwrap_or_else(|| m.get("name").and_then(|v| v.as_str()).unwrap_or(""))
-                                                        .to_string(),
-                                                    size: m.get("size")
-                                                        .and_then(|v| v.as_u64())
-                                                        .unwrap_or(0),
-                                                    digest: m.get("digest")
-                                                        .and_then(|v| v.as_str())
-                                                        .unwrap_or("")
-                                                        .to_string(),
-                                                    modified_at: m.get("modified_at")
-                                                        .and_then(|v| v.as_str())
-                                                        .unwrap_or("")
-                                                        .to_string(),
-                                                })
-                                            })
-                                            .collect::<Vec<OllamaModel>>()
-                                    })
-                                    .unwrap_or_default();
-
-                                log::info!("Found {} OLLAMA models", models.len());
-                                OllamaModelsResult {
-                                    success: true,
-                                    models,
-                                    error: None,
-                                }
-                            }
-                            Err(e) => {
-                                log::warn!("Failed to parse OLLAMA response: {}", e);
-                                OllamaModelsResult {
-                                    success: false,
-                                    models: vec![],
-                                    error: Some(format!("Failed to parse response: {}", e)),
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to read OLLAMA response: {}", e);
-                        OllamaModelsResult {
-                            success: false,
-                            models: vec![],
-                            error: Some(format!("Failed to read response: {}", e)),
-                        }
-                    }
-                }
-            } else {
-                log::warn!("OLLAMA responded with non-success status: {}", response.status());
-                OllamaModelsResult {
-                    success: false,
-                    models: vec![],
-                    error: Some(format!("OLLAMA responded with status: {}", response.status())),
-                }
-            }
-        }
-        Err(e) => {
+    let response = client
+        .get(&tags_endpoint)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| {
             log::info!("Failed to connect to OLLAMA: {}", e);
-            OllamaModelsResult {
-                success: false,
-                models: vec![],
-                error: Some(format!("Failed to connect to OLLAMA: {}", e)),
-            }
-        }
+            AppError::network(&tags_endpoint, e)
+        })?;
+
+    if !response.status().is_success() {
+        log::warn!("OLLAMA responded with non-success status: {}", response.status());
+        return Err(AppError::HttpStatus {
+            url: tags_endpoint,
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
     }
+
+    let json = response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| AppError::Ollama(format!("Failed to parse response: {}", e)))?;
+
+    let models = json.get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| {
+                    Some(OllamaModel {
+                        name: m.get("name")?.as_str()?.to_string(),
+                        model: m.get("model")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_else(|| m.get("name").and_then(|v| v.as_str()).unwrap_or(""))
+                            .to_string(),
+                        size: m.get("size")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        digest: m.get("digest")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                        modified_at: m.get("modified_at")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                    })
+                })
+                .collect::<Vec<OllamaModel>>()
+        })
+        .unwrap_or_default();
+
+    log::info!("Found {} OLLAMA models", models.len());
+    Ok(OllamaModelsResult { models })
 }
 
 /// Transcribe audio using whisper.cpp
 #[tauri::command]
 pub async fn transcribe_audio(
+    app: AppHandle,
     audio_path: String,
     language: String,
     model: String,
     whisper_path: Option<String>,
-) -> Result<TranscriptionResult, String> {
+    job_id: Option<String>,
+    options: Option<WhisperOptions>,
+) -> Result<TranscriptionResult, AppError> {
+    // Progress events carry the job id, and `cancel_transcription` takes it
+    let job_id = job_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let jobs = app.state::<TranscriptionJobs>();
+    let mut job = jobs.register(&job_id)?;
+
+    transcribe_registered(app.clone(), &mut job, audio_path, language, model, whisper_path, options)
+        .await
+}
+
+/// `transcribe_audio` for a job already registered with `TranscriptionJobs`
+pub(crate) async fn transcribe_registered(
+    app: AppHandle,
+    job: &mut Job<'_>,
+    audio_path: String,
+    language: String,
+    model: String,
+    whisper_path: Option<String>,
+    options: Option<WhisperOptions>,
+) -> Result<TranscriptionResult, AppError> {
+    // Options given with the call override the ones saved in settings field
+    // by field
+    let settings = app.state::<SettingsStore>().get();
+    let options = options.unwrap_or_default().or(&settings.whisper);
+    options.validate()?;
+
     let audio_path_buf = PathBuf::from(&audio_path);
 
     if !audio_path_buf.exists() {
-        return Err(format!("Audio file not found: {}", audio_path_buf.display()));
+        return Err(AppError::AudioNotFound {
+            path: audio_path_buf.display().to_string(),
+        });
     }
 
     let audio_size = std::fs::metadata(&audio_path_buf)
@@ -398,8 +904,22 @@ pub async fn transcribe_audio(
         .unwrap_or(0);
     log::info!("Audio file size: {} bytes", audio_size);
 
-    // Audio should already be WAV format from the frontend
-    let wav_path = audio_path_buf.clone();
+    // A bounded number of jobs decode and run whisper at once; the rest wait
+    // here, still cancellable
+    let _slot = job.wait_for_slot().await?;
+
+    // Decoding and silence trimming are CPU-bound. Temporary files are
+    // removed when `prepared` drops, however this function returns.
+    let vad = settings.vad;
+    let input = audio_path_buf.clone();
+    let prepared = tauri::async_runtime::spawn_blocking(move || {
+        audio::prepare_for_whisper(&input, &vad)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Audio preparation task failed: {}", e)))??;
+    let wav_path = prepared.path.clone();
+    let duration = prepared.duration;
+    let trimmed_silence = prepared.trimmed_silence;
 
     // Try to find whisper binary - first check provided path, then search
     let whisper_cmd = if let Some(ref path) = whisper_path {
@@ -409,12 +929,14 @@ pub async fn transcribe_audio(
             path.clone()
         } else {
             log::warn!("Provided whisper path does not exist: {}", path);
-            find_whisper_binary()
-                .ok_or_else(|| format!("Whisper binary not found. Saved path '{}' does not exist and no whisper found in PATH.", path))?
+            find_whisper_binary_async()
+                .await
+                .ok_or_else(|| AppError::WhisperNotFound { path: Some(path.clone()) })?
         }
     } else {
-        find_whisper_binary()
-            .ok_or("Whisper binary not found. Please install whisper.cpp or configure the path in Settings.")?
+        find_whisper_binary_async()
+            .await
+            .ok_or(AppError::WhisperNotFound { path: None })?
     };
 
     log::info!("Using whisper binary: {}", whisper_cmd);
@@ -422,21 +944,25 @@ pub async fn transcribe_audio(
     // Verify whisper binary exists
     let whisper_path_buf = PathBuf::from(&whisper_cmd);
     if !whisper_path_buf.exists() && !whisper_cmd.starts_with("whisper") {
-        return Err(format!("Whisper binary not found at path: {}", whisper_cmd));
+        return Err(AppError::WhisperNotFound { path: Some(whisper_cmd) });
     }
 
     // Get model path (considering language for multilingual support)
-    let model_path = get_model_path(&model, &language)?;
+    let model_path = get_model_path(&app.state::<ModelCatalog>(), &settings.models, &model, &language)?;
     log::info!("Using model: {}", model_path);
 
     // Verify model exists
     if !PathBuf::from(&model_path).exists() {
-        return Err(format!("Whisper model file not found: {}", model_path));
+        return Err(AppError::ModelMissing {
+            model,
+            searched: vec![model_path],
+            download_url: None,
+        });
     }
 
     // Build command arguments - whisper.cpp uses different args
     // Standard whisper.cpp CLI: main -m <model> -f <audio> -l <lang>
-    let mut cmd = Command::new(&whisper_cmd);
+    let mut cmd = tokio::process::Command::new(&whisper_cmd);
     cmd.arg("-m").arg(&model_path)
        .arg("-f").arg(&wav_path);
 
@@ -446,79 +972,169 @@ pub async fn transcribe_audio(
         cmd.arg("-l").arg(&language);
     }
 
-    cmd.arg("--no-timestamps")
-       .arg("-otxt");  // Output as text
+    whisper::apply_options(&mut cmd, &options);
 
-    let cmd_str = format!("{:?}", cmd);
-    log::info!("Running whisper command: {}", cmd_str);
+    if let Some(prompt) = vocabulary::build_prompt(&settings.vocabulary.terms) {
+        cmd.arg("--prompt").arg(prompt);
+    }
 
-    let output = cmd.output().map_err(|e| {
-        format!(
-            "Failed to execute whisper command.\nCommand: {}\nError: {}\n\nMake sure whisper.cpp is properly installed.",
-            cmd_str, e
-        )
-    })?;
+    // Full JSON output carries the timed segments and token probabilities
+    let output_file = whisper::OutputFile::new();
+    cmd.arg("-ojf").arg("-of").arg(output_file.base());
 
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let whisper::WhisperRun { stdout, stderr } = whisper::run(&app, cmd, job).await?;
 
-    log::info!("Whisper stdout: {}", stdout);
-    log::info!("Whisper stderr: {}", stderr);
-    log::info!("Whisper exit code: {:?}", output.status.code());
+    // Segment times are relative to the trimmed audio; shift them back so
+    // they line up with the original recording
+    let offset_ms = (trimmed_silence.leading_secs * 1000.0).round() as u64;
+    let mut transcript = output_file.read(offset_ms)?;
 
-    if !output.status.success() {
-        return Err(format!(
-            "Whisper transcription failed (exit code: {:?}).\n\nCommand: {}\n\nStderr:\n{}\n\nStdout:\n{}",
-            output.status.code(),
-            cmd_str,
-            stderr,
-            stdout
-        ));
+    let replacements = &settings.vocabulary.replacements;
+    transcript.text = vocabulary::apply_replacements(&transcript.text, replacements);
+    for segment in &mut transcript.segments {
+        segment.text = vocabulary::apply_replacements(&segment.text, replacements);
     }
 
-    // Parse whisper output - the text output might be in stdout or in a .txt file
-    let mut text = stdout.trim().to_string();
+    if transcript.text.is_empty() {
+        log::warn!("Whisper returned empty transcription. Stdout: {}", stdout);
+        return Err(AppError::EmptyTranscription { stderr });
+    }
 
-    // If stdout is empty, check if a .txt file was created
-    if text.is_empty() {
-        let txt_path = wav_path.with_extension("wav.txt");
-        if txt_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&txt_path) {
-                text = content.trim().to_string();
+    let (language, language_probability) = if language == "auto" {
+        match whisper::parse_detected_language(&stderr) {
+            Some((detected, probability)) => {
+                log::info!("Whisper detected language '{}' (p = {:.3})", detected, probability);
+                (detected, Some(probability))
             }
-            // Clean up the txt file
-            let _ = std::fs::remove_file(&txt_path);
-        }
-
-        // Also try without .wav extension
-        let txt_path2 = wav_path.with_extension("txt");
-        if text.is_empty() && txt_path2.exists() {
-            if let Ok(content) = std::fs::read_to_string(&txt_path2) {
-                text = content.trim().to_string();
+            None => {
+                log::warn!("Whisper did not report a detection probability");
+                (transcript.language.unwrap_or(language), None)
             }
-            let _ = std::fs::remove_file(&txt_path2);
         }
-    }
+    } else {
+        (language, None)
+    };
 
-    // Clean up WAV file if we created it
-    if wav_path != audio_path_buf {
-        let _ = std::fs::remove_file(&wav_path);
-    }
+    Ok(TranscriptionResult {
+        text: transcript.text,
+        language,
+        language_probability,
+        duration,
+        trimmed_silence,
+        segments: transcript.segments,
+    })
+}
 
-    if text.is_empty() {
-        return Err(format!(
-            "Whisper returned empty transcription.\n\nThis could mean:\n- The audio is too short or silent\n- The audio format is not supported\n- Whisper couldn't process the file\n\nStderr: {}\nStdout: {}",
-            stderr, stdout
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleExport {
+    pub path: String,
+    pub cue_count: usize,
+}
+
+/// Transcribe `audio_path` and write its timed segments to `output_path` as
+/// SRT or WebVTT, re-flowed into cues by `options`
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_subtitles(
+    app: AppHandle,
+    audio_path: String,
+    output_path: String,
+    language: String,
+    model: String,
+    whisper_path: Option<String>,
+    options: SubtitleOptions,
+    job_id: Option<String>,
+    whisper_options: Option<WhisperOptions>,
+) -> Result<SubtitleExport, AppError> {
+    options.validate().map_err(AppError::InvalidInput)?;
+
+    let transcription = transcribe_audio(
+        app,
+        audio_path,
+        language,
+        model,
+        whisper_path,
+        job_id,
+        whisper_options,
+    )
+    .await?;
+    let cues = subtitles::build_cues(&transcription.segments, &options);
+    if cues.is_empty() {
+        return Err(AppError::InvalidInput(
+            "The time offset moves every subtitle before the start of the audio".to_string(),
         ));
     }
 
-    Ok(TranscriptionResult {
-        text,
-        language,
-        duration: 0.0,
+    std::fs::write(&output_path, subtitles::render(&cues, options.format))
+        .map_err(|e| AppError::io(format!("Failed to write {}", output_path), e))?;
+    log::info!("Wrote {} subtitle cues to {}", cues.len(), output_path);
+
+    Ok(SubtitleExport {
+        path: output_path,
+        cue_count: cues.len(),
     })
 }
 
+/// Stop a running transcription. Its whisper process is killed and its
+/// temporary files removed; the `transcribe_audio` call fails with `CANCELLED`.
+/// Queued jobs that have not started yet are simply cancelled.
+#[tauri::command]
+pub fn cancel_transcription(
+    app: AppHandle,
+    jobs: State<'_, TranscriptionJobs>,
+    queue: State<'_, JobQueue>,
+    job_id: String,
+) -> Result<(), AppError> {
+    if queue.list().iter().any(|job| job.id == job_id) {
+        return queue.cancel(&app, &job_id);
+    }
+    if jobs.cancel(&job_id) {
+        log::info!("Cancelling transcription {}", job_id);
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "No transcription with job id '{}' is running",
+            job_id
+        )))
+    }
+}
+
+/// Add a transcription to the background queue. Progress and state changes
+/// arrive as `transcription-progress` and `transcription-job-changed` events.
+#[tauri::command]
+pub fn enqueue_transcription(
+    app: AppHandle,
+    queue: State<'_, JobQueue>,
+    request: JobRequest,
+) -> Result<TranscriptionJob, AppError> {
+    queue.enqueue(&app, request)
+}
+
+/// All queued, running and finished jobs, oldest first. Finished jobs stay
+/// until removed, so results survive a restart.
+#[tauri::command]
+pub fn list_transcription_jobs(queue: State<'_, JobQueue>) -> Vec<TranscriptionJob> {
+    queue.list()
+}
+
+/// Forget a finished job once its result has been handled
+#[tauri::command]
+pub fn remove_transcription_job(queue: State<'_, JobQueue>, job_id: String) -> Result<(), AppError> {
+    queue.remove(&job_id)
+}
+
+/// `find_whisper_binary` on the blocking pool; probing candidates spawns
+/// processes
+async fn find_whisper_binary_async() -> Option<String> {
+    tauri::async_runtime::spawn_blocking(find_whisper_binary)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Whisper lookup task failed: {}", e);
+            None
+        })
+}
+
 /// Find the whisper binary on the system
 fn find_whisper_binary() -> Option<String> {
     // First check our installation directory
@@ -568,18 +1184,25 @@ fn find_whisper_binary() -> Option<String> {
 
 /// Install whisper.cpp
 #[tauri::command]
-pub async fn install_whisper() -> Result<InstallResult, String> {
+pub async fn install_whisper() -> Result<InstallResult, AppError> {
+    // Downloads and builds run as child processes and can take minutes
+    tauri::async_runtime::spawn_blocking(install_whisper_blocking)
+        .await
+        .map_err(|e| AppError::Internal(format!("Install task failed: {}", e)))?
+}
+
+fn install_whisper_blocking() -> Result<InstallResult, AppError> {
     // Get user's home directory
     let home = std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| "Could not find home directory")?;
+        .map_err(|e| AppError::io("Could not find home directory", e))?;
 
     let whisper_dir = PathBuf::from(&home).join(".voiceintelligence").join("whisper");
 
     // Create directory if it doesn't exist
     if !whisper_dir.exists() {
         std::fs::create_dir_all(&whisper_dir)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| AppError::io("Failed to create directory", e))?;
     }
 
     // Check the platform and provide instructions
@@ -601,14 +1224,12 @@ pub async fn install_whisper() -> Result<InstallResult, String> {
                 )
             ])
             .output()
-            .map_err(|e| format!("Failed to download: {}", e))?;
+            .map_err(|e| AppError::io("Failed to download", e))?;
 
         if !output.status.success() {
-            return Ok(InstallResult {
-                success: false,
-                message: "Failed to download whisper.cpp. Please install manually.".to_string(),
-                path: None,
-            });
+            return Err(AppError::WhisperInstallFailed(
+                "Failed to download whisper.cpp. Please install manually.".to_string(),
+            ));
         }
 
         // Extract the zip
@@ -622,14 +1243,12 @@ pub async fn install_whisper() -> Result<InstallResult, String> {
                 )
             ])
             .output()
-            .map_err(|e| format!("Failed to extract: {}", e))?;
+            .map_err(|e| AppError::io("Failed to extract", e))?;
 
         if !output.status.success() {
-            return Ok(InstallResult {
-                success: false,
-                message: "Failed to extract whisper.cpp. Please install manually.".to_string(),
-                path: None,
-            });
+            return Err(AppError::WhisperInstallFailed(
+                "Failed to extract whisper.cpp. Please install manually.".to_string(),
+            ));
         }
 
         // Download both English and multilingual models for language support
@@ -653,7 +1272,7 @@ pub async fn install_whisper() -> Result<InstallResult, String> {
                         )
                     ])
                     .output()
-                    .map_err(|e| format!("Failed to download model {}: {}", model_name, e))?;
+                    .map_err(|e| AppError::io(format!("Failed to download model {}", model_name), e))?;
 
                 if !output.status.success() {
                     log::warn!("Failed to download model: {}", model_name);
@@ -673,11 +1292,8 @@ pub async fn install_whisper() -> Result<InstallResult, String> {
             whisper_dir.to_string_lossy().to_string()
         };
 
-        return Ok(InstallResult {
-            success: true,
-            message: format!("Whisper.cpp installed to {}", whisper_dir.display()),
-            path: Some(installed_path),
-        });
+        log::info!("Whisper.cpp installed to {}", whisper_dir.display());
+        return Ok(InstallResult { path: installed_path });
     }
 
     #[cfg(target_os = "macos")]
@@ -689,44 +1305,34 @@ pub async fn install_whisper() -> Result<InstallResult, String> {
 
         match output {
             Ok(o) if o.status.success() => {
+                log::info!("Whisper.cpp installed via Homebrew");
                 return Ok(InstallResult {
-                    success: true,
-                    message: "Whisper.cpp installed via Homebrew".to_string(),
-                    path: Some("whisper".to_string()),
+                    path: "whisper".to_string(),
                 });
             }
             _ => {
-                return Ok(InstallResult {
-                    success: false,
-                    message: "Failed to install via Homebrew. Please run: brew install whisper-cpp".to_string(),
-                    path: None,
-                });
+                return Err(AppError::WhisperInstallFailed(
+                    "Failed to install via Homebrew. Please run: brew install whisper-cpp".to_string(),
+                ));
             }
         }
     }
 
     #[cfg(target_os = "linux")]
     {
-        return Ok(InstallResult {
-            success: false,
-            message: "Please install whisper.cpp manually: https://github.com/ggerganov/whisper.cpp".to_string(),
-            path: None,
-        });
+        return Err(AppError::WhisperInstallFailed(
+            "Please install whisper.cpp manually: https://github.com/ggerganov/whisper.cpp".to_string(),
+        ));
     }
 
     #[allow(unreachable_code)]
-    Ok(InstallResult {
-        success: false,
-        message: "Unsupported platform".to_string(),
-        path: None,
-    })
+    Err(AppError::WhisperInstallFailed("Unsupported platform".to_string()))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InstallResult {
-    pub success: bool,
-    pub message: String,
-    pub path: Option<String>,
+    /// Path of the installed whisper binary
+    pub path: String,
 }
 
 /// Information about a whisper model
@@ -737,60 +1343,194 @@ pub struct WhisperModel {
     pub name: String,
     pub size: String,
     pub size_bytes: u64,
-    pub download_url: String,
+    pub file_name: String,
+    /// None for custom models, which aren't downloaded
+    pub download_url: Option<String>,
     pub installed: bool,
     pub installed_path: Option<String>,
+    /// Size of the installed file
+    pub size_on_disk: Option<u64>,
     pub is_multilingual: bool,
+    /// Expected SHA-256 of the model file, checked after downloading
+    pub sha256: Option<String>,
+    pub recommended_ram_mb: u32,
+    pub quantization: Option<String>,
+    /// Registered by the user from a file outside the models directory
+    pub custom: bool,
 }
 
-/// Get list of available whisper models with their installation status
+/// Get list of available whisper models with their installation status:
+/// the catalog models, then the custom ones
 #[tauri::command]
-pub fn get_available_models() -> Vec<WhisperModel> {
-    // Define available models based on whisper.cpp
-    let models_info = vec![
-        ("tiny", "Tiny (English)", "75 MB", 75_000_000u64, "ggml-tiny.en.bin", false),
-        ("tiny-multi", "Tiny (Multilingual)", "75 MB", 75_000_000u64, "ggml-tiny.bin", true),
-        ("base", "Base (English)", "142 MB", 142_000_000u64, "ggml-base.en.bin", false),
-        ("base-multi", "Base (Multilingual)", "142 MB", 142_000_000u64, "ggml-base.bin", true),
-        ("small", "Small (English)", "466 MB", 466_000_000u64, "ggml-small.en.bin", false),
-        ("small-multi", "Small (Multilingual)", "466 MB", 466_000_000u64, "ggml-small.bin", true),
-        ("medium", "Medium (English)", "1.5 GB", 1_500_000_000u64, "ggml-medium.en.bin", false),
-        ("medium-multi", "Medium (Multilingual)", "1.5 GB", 1_500_000_000u64, "ggml-medium.bin", true),
-        ("large", "Large (Multilingual)", "2.9 GB", 2_900_000_000u64, "ggml-large.bin", true),
-    ];
-
-    let base_url = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+pub fn get_available_models(
+    store: State<'_, SettingsStore>,
+    catalog: State<'_, ModelCatalog>,
+) -> Vec<WhisperModel> {
+    list_models(&catalog, &store.get().models)
+}
 
-    // Get whisper models directory
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
+/// Register a `ggml-*.bin` file as a model with the given display name. The
+/// file is used where it is, not copied. Registering the same file again
+/// renames it.
+#[tauri::command]
+pub fn register_custom_model(
+    store: State<'_, SettingsStore>,
+    catalog: State<'_, ModelCatalog>,
+    path: String,
+    name: String,
+) -> Result<WhisperModel, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("Model name must not be empty".to_string()));
+    }
+    let path = std::fs::canonicalize(path.trim())
+        .map_err(|e| AppError::io(format!("Failed to open {}", path), e))?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
-    let whisper_dir = PathBuf::from(&home).join(".voiceintelligence").join("whisper");
+    if !models::is_model_file_name(&file_name) {
+        return Err(AppError::InvalidInput(format!(
+            "Expected a ggml-*.bin model file, got '{}'",
+            file_name
+        )));
+    }
+    let multilingual = models::inspect_model_file(&path)?;
+
+    let id = format!(
+        "{}{}",
+        models::CUSTOM_MODEL_PREFIX,
+        file_name.trim_end_matches(".bin")
+    );
+    let path = path.to_string_lossy().to_string();
+    let mut settings = store.get();
+    match settings.models.custom.iter_mut().find(|m| m.id == id) {
+        Some(existing) if existing.path == path => {
+            existing.name = name;
+            existing.multilingual = multilingual;
+        }
+        Some(existing) => {
+            return Err(AppError::InvalidInput(format!(
+                "A model named {} is already registered from {}",
+                file_name, existing.path
+            )));
+        }
+        None => settings.models.custom.push(CustomModel {
+            id: id.clone(),
+            name,
+            path: path.clone(),
+            multilingual,
+        }),
+    }
+    settings.validate()?;
+    let models_settings = settings.models.clone();
+    store.save(settings).map_err(AppError::Settings)?;
+    log::info!("Registered custom model {} at {}", id, path);
 
-    models_info
+    list_models(&catalog, &models_settings)
         .into_iter()
-        .map(|(id, name, size, size_bytes, filename, is_multilingual)| {
-            let download_url = format!("{}/{}", base_url, filename);
-            let model_path = whisper_dir.join(filename);
-            let installed = model_path.exists();
-            let installed_path = if installed {
-                Some(model_path.to_string_lossy().to_string())
-            } else {
-                None
-            };
+        .find(|m| m.id == id)
+        .ok_or_else(|| AppError::Internal(format!("Registered model {} not listed", id)))
+}
 
-            WhisperModel {
-                id: id.to_string(),
-                name: name.to_string(),
-                size: size.to_string(),
-                size_bytes,
-                download_url,
-                installed,
-                installed_path,
-                is_multilingual,
-            }
-        })
-        .collect()
+/// Forget a custom model. The file itself is left alone.
+#[tauri::command]
+pub fn unregister_custom_model(
+    store: State<'_, SettingsStore>,
+    model_id: String,
+) -> Result<(), AppError> {
+    let mut settings = store.get();
+    let count = settings.models.custom.len();
+    settings.models.custom.retain(|m| m.id != model_id);
+    if settings.models.custom.len() == count {
+        return Err(AppError::UnknownModel { model_id });
+    }
+    store.save(settings).map_err(AppError::Settings)?;
+    log::info!("Unregistered custom model {}", model_id);
+    Ok(())
+}
+
+/// Replace the model catalog with the manifest at `source`, or at the
+/// `models.manifestSource` setting if no source is given. Returns the new
+/// list of models.
+#[tauri::command]
+pub async fn refresh_model_catalog(
+    app: AppHandle,
+    source: Option<String>,
+) -> Result<Vec<WhisperModel>, AppError> {
+    let source = source
+        .or_else(|| app.state::<SettingsStore>().get().models.manifest_source)
+        .ok_or_else(|| {
+            AppError::InvalidInput("No model manifest URL or file configured".to_string())
+        })?;
+
+    let client = app.state::<HttpClient>().client();
+    let catalog = app.state::<ModelCatalog>();
+    catalog.refresh(&client, source.trim()).await?;
+    Ok(list_models(&catalog, &app.state::<SettingsStore>().get().models))
+}
+
+/// The catalog and custom models with their installation status
+fn list_models(catalog: &ModelCatalog, settings: &ModelSettings) -> Vec<WhisperModel> {
+    let whisper_dir = models::models_dir(settings);
+
+    let catalog_models = catalog.manifest().models.into_iter().map(|entry| {
+        let model_path = whisper_dir.join(&entry.file);
+        let size_on_disk = file_size(&model_path);
+
+        WhisperModel {
+            id: entry.id,
+            name: entry.name,
+            size: models::format_size(entry.size_bytes),
+            size_bytes: entry.size_bytes,
+            file_name: entry.file,
+            download_url: Some(entry.url),
+            installed: size_on_disk.is_some(),
+            installed_path: size_on_disk.map(|_| model_path.to_string_lossy().to_string()),
+            size_on_disk,
+            is_multilingual: entry.multilingual,
+            sha256: Some(entry.sha256),
+            recommended_ram_mb: entry.recommended_ram_mb,
+            quantization: entry.quantization,
+            custom: false,
+        }
+    });
+
+    let custom_models = settings.custom.iter().map(|custom| {
+        let model_path = PathBuf::from(&custom.path);
+        let size_on_disk = file_size(&model_path);
+        let size_bytes = size_on_disk.unwrap_or_default();
+
+        WhisperModel {
+            id: custom.id.clone(),
+            name: custom.name.clone(),
+            size: models::format_size(size_bytes),
+            size_bytes,
+            file_name: model_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            download_url: None,
+            installed: size_on_disk.is_some(),
+            installed_path: Some(custom.path.clone()),
+            size_on_disk,
+            is_multilingual: custom.multilingual,
+            sha256: None,
+            recommended_ram_mb: 0,
+            quantization: None,
+            custom: true,
+        }
+    });
+
+    catalog_models.chain(custom_models).collect()
+}
+
+/// Size of the file at `path`, or None if there is no such file
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
 }
 
 /// Progress event for model download
@@ -804,57 +1544,111 @@ pub struct DownloadProgress {
     pub status: String,
 }
 
+/// `downloaded` as a percentage of `total`, 0 while the total is unknown
+fn download_percentage(downloaded: u64, total: u64) -> f32 {
+    if total == 0 {
+        return 0.0;
+    }
+    (downloaded as f32 / total as f32) * 100.0
+}
+
 /// Result of model download
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadResult {
-    pub success: bool,
-    pub message: String,
     pub model_path: Option<String>,
 }
 
-/// Download a whisper model with progress tracking
+/// Download a whisper model with progress tracking. `cancel_download` with
+/// id `whisper:<model id>` stops it; the last `download-progress` event then
+/// has status "cancelled", or "error" if the download failed.
 #[tauri::command]
 pub async fn download_whisper_model(
     window: Window,
+    downloads: State<'_, DownloadManager>,
     model_id: String,
-) -> Result<DownloadResult, String> {
+) -> Result<DownloadResult, AppError> {
     // Get model info from available models
-    let models = get_available_models();
+    let model_settings = window.state::<SettingsStore>().get().models;
+    let models = list_models(&window.state::<ModelCatalog>(), &model_settings);
     let model = models
         .iter()
         .find(|m| m.id == model_id)
-        .ok_or_else(|| format!("Model '{}' not found", model_id))?;
+        .ok_or_else(|| AppError::UnknownModel { model_id: model_id.clone() })?;
 
     // Check if already installed
     if model.installed {
         return Ok(DownloadResult {
-            success: true,
-            message: "Model already installed".to_string(),
             model_path: model.installed_path.clone(),
         });
     }
+    let Some(url) = model.download_url.as_deref() else {
+        return Err(AppError::InvalidInput(format!(
+            "Custom model {} is missing from {} and can't be downloaded",
+            model_id,
+            model.installed_path.as_deref().unwrap_or_default()
+        )));
+    };
 
-    // Get whisper models directory
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .map_err(|_| "Could not find home directory")?;
-    let whisper_dir = PathBuf::from(&home).join(".voiceintelligence").join("whisper");
+    // One download per model, so two requests can't write the same temp file
+    let mut download = downloads.start(DownloadKind::WhisperModel, &model_id)?;
 
-    // Create directory if it doesn't exist
-    if !whisper_dir.exists() {
-        std::fs::create_dir_all(&whisper_dir)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
+    let whisper_dir = models::models_dir(&model_settings);
+    let model_path = whisper_dir.join(&model.file_name);
+    // A `.bin.tmp` left by an interrupted download is resumed where it
+    // stopped instead of starting over
+    let temp_path = model_path.with_extension("bin.tmp");
 
-    // Determine model filename from URL
-    let filename = model.download_url
-        .split('/')
-        .last()
-        .ok_or("Invalid download URL")?;
-    let model_path = whisper_dir.join(filename);
+    // Failures here end the download too, so they get the final event below
+    let result = async {
+        if !whisper_dir.exists() {
+            std::fs::create_dir_all(&whisper_dir)
+                .map_err(|e| AppError::io("Failed to create directory", e))?;
+        }
 
-    log::info!("Downloading model {} from {}", model_id, model.download_url);
+        // Only the part not downloaded yet needs room
+        let partial_size = std::fs::metadata(&temp_path).map(|m| m.len()).unwrap_or(0);
+        storage::ensure_free_space(&whisper_dir, model.size_bytes.saturating_sub(partial_size))?;
+
+        fetch_whisper_model(&window, model, url, &model_path, &temp_path, &mut download).await
+    }
+    .await;
+    if let Err(e) = &result {
+        let status = if matches!(e, AppError::Cancelled) {
+            // A cancelled download isn't resumed
+            if let Err(e) = std::fs::remove_file(&temp_path) {
+                log::warn!("Failed to remove {}: {}", temp_path.display(), e);
+            }
+            log::info!("Download of model {} cancelled", model_id);
+            "cancelled"
+        } else {
+            log::warn!("Download of model {} failed: {}", model_id, e);
+            "error"
+        };
+        let (downloaded, total) = download.progress();
+        let total = total.unwrap_or(model.size_bytes);
+        let _ = window.emit("download-progress", DownloadProgress {
+            model_id: model_id.clone(),
+            downloaded,
+            total,
+            percentage: download_percentage(downloaded, total),
+            status: status.to_string(),
+        });
+    }
+    result
+}
+
+/// Stream `model` from `url` into `temp_path`, verify it and move it to `model_path`
+async fn fetch_whisper_model(
+    window: &Window,
+    model: &WhisperModel,
+    url: &str,
+    model_path: &Path,
+    temp_path: &Path,
+    download: &mut Download<'_>,
+) -> Result<DownloadResult, AppError> {
+    let model_id = model.id.clone();
+    log::info!("Downloading model {} from {}", model_id, url);
 
     // Emit initial progress
     let _ = window.emit("download-progress", DownloadProgress {
@@ -865,50 +1659,118 @@ pub async fn download_whisper_model(
         status: "starting".to_string(),
     });
 
-    // Create HTTP client and start download
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&model.download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to start download: {}", e))?;
+    let partial_size = std::fs::metadata(temp_path).map(|m| m.len()).unwrap_or(0);
+
+    // Start the download with the shared client (proxy/CA aware)
+    let client = window.state::<HttpClient>().client();
+    let mut request = client.get(url);
+    if partial_size > 0 {
+        log::info!("Resuming download of {} from byte {}", model_id, partial_size);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", partial_size));
+    }
+    let mut response = download
+        .or_cancelled(async {
+            request.send().await.map_err(|e| AppError::network(url, e))
+        })
+        .await?;
+
+    let mut restart = false;
+    if partial_size > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // Nothing follows the partial file on the server, so it may already
+        // be the whole model if an earlier download stopped before the rename
+        emit_verifying(window, &model_id, partial_size, model.size_bytes);
+        let hasher = hash_partial_download(download, temp_path).await?;
+        let actual = format!("{:x}", hasher.clone().finalize());
+        if model.sha256.as_deref().is_some_and(|expected| actual.eq_ignore_ascii_case(expected)) {
+            log::info!("Partial download of {} is already complete", model_id);
+            return install_whisper_model(window, model, model_path, temp_path, hasher, partial_size);
+        }
+        log::warn!("Discarding partial download of {} ({} bytes)", model_id, partial_size);
+        restart = true;
+    } else if partial_size > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        // Appending anything but the bytes right after the partial file
+        // would corrupt it
+        let start = content_range_start(response.headers());
+        if start != Some(partial_size) {
+            log::warn!(
+                "Server resumed download of {} from {:?} instead of byte {}; starting over",
+                model_id,
+                start,
+                partial_size
+            );
+            restart = true;
+        }
+    }
+    if restart {
+        response = download
+            .or_cancelled(async {
+                client
+                    .get(url)
+                    .send()
+                    .await
+                    .map_err(|e| AppError::network(url, e))
+            })
+            .await?;
+    }
 
     if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
+        return Err(AppError::HttpStatus {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+            body: String::new(),
+        });
     }
 
+    // Servers that ignore the range send the whole file with 200
+    let resumed = !restart
+        && partial_size > 0
+        && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut downloaded: u64 = if resumed { partial_size } else { 0 };
+
     // Get content length for progress tracking
     let total_size = response
         .content_length()
+        .map(|len| len + downloaded)
         .unwrap_or(model.size_bytes);
 
-    // Create temporary file for download
-    let temp_path = model_path.with_extension("bin.tmp");
-    let mut file = std::fs::File::create(&temp_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
+    // The checksum covers the whole file, so hash what is already on disk
+    // before appending to it
+    let (mut file, mut hasher) = if resumed {
+        emit_verifying(window, &model_id, downloaded, total_size);
+        let hasher = hash_partial_download(download, temp_path).await?;
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(temp_path)
+            .map_err(|e| AppError::io("Failed to open partial download", e))?;
+        (file, hasher)
+    } else {
+        let file = std::fs::File::create(temp_path)
+            .map_err(|e| AppError::io("Failed to create file", e))?;
+        (file, Sha256::new())
+    };
 
     // Stream the download with progress updates
-    let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
     let mut last_progress_update = std::time::Instant::now();
 
-    while let Some(chunk_result) = stream.next().await {
+    while let Some(chunk_result) = download.or_cancelled(async { Ok(stream.next().await) }).await? {
         let chunk = chunk_result
-            .map_err(|e| format!("Download error: {}", e))?;
+            .map_err(|e| AppError::network(url, e))?;
 
         file.write_all(&chunk)
-            .map_err(|e| format!("Failed to write to file: {}", e))?;
+            .map_err(|e| AppError::io("Failed to write to file", e))?;
+        hasher.update(&chunk);
 
         downloaded += chunk.len() as u64;
 
         // Emit progress every 100ms to avoid overwhelming the frontend
         if last_progress_update.elapsed().as_millis() >= 100 {
-            let percentage = (downloaded as f32 / total_size as f32) * 100.0;
+            download.set_progress(downloaded, Some(total_size));
             let _ = window.emit("download-progress", DownloadProgress {
                 model_id: model_id.clone(),
                 downloaded,
                 total: total_size,
-                percentage,
+                percentage: download_percentage(downloaded, total_size),
                 status: "downloading".to_string(),
             });
             last_progress_update = std::time::Instant::now();
@@ -917,12 +1779,43 @@ pub async fn download_whisper_model(
 
     // Flush and close the file
     file.flush()
-        .map_err(|e| format!("Failed to flush file: {}", e))?;
+        .map_err(|e| AppError::io("Failed to flush file", e))?;
     drop(file);
 
+    install_whisper_model(window, model, model_path, temp_path, hasher, total_size)
+}
+
+/// Check the SHA-256 of a finished download in `temp_path` and move it to `model_path`
+fn install_whisper_model(
+    window: &Window,
+    model: &WhisperModel,
+    model_path: &Path,
+    temp_path: &Path,
+    hasher: Sha256,
+    total_size: u64,
+) -> Result<DownloadResult, AppError> {
+    let model_id = model.id.clone();
+
+    // A corrupted model only shows up later as an obscure whisper crash, so
+    // check it before it is put in place. A bad file can't be resumed either.
+    let actual = format!("{:x}", hasher.finalize());
+    if let Some(expected) = &model.sha256 {
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = std::fs::remove_file(temp_path);
+            log::warn!("Checksum mismatch for model {}: expected {}, got {}", model_id, expected, actual);
+            return Err(AppError::ChecksumMismatch {
+                model_id,
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    } else {
+        log::warn!("No checksum known for model {}; SHA-256 is {}", model_id, actual);
+    }
+
     // Rename temp file to final path
-    std::fs::rename(&temp_path, &model_path)
-        .map_err(|e| format!("Failed to move downloaded file: {}", e))?;
+    std::fs::rename(temp_path, model_path)
+        .map_err(|e| AppError::io("Failed to move downloaded file", e))?;
 
     // Emit completion progress
     let _ = window.emit("download-progress", DownloadProgress {
@@ -933,24 +1826,100 @@ pub async fn download_whisper_model(
         status: "completed".to_string(),
     });
 
-    log::info!("Model {} downloaded successfully to {}", model_id, model_path.display());
+    log::info!("Model {} downloaded successfully to {}", model_id, model_path.display());
+
+    Ok(DownloadResult {
+        model_path: Some(model_path.to_string_lossy().to_string()),
+    })
+}
+
+/// Tell the frontend the partial download is being hashed, which takes a
+/// while for the large models
+fn emit_verifying(window: &Window, model_id: &str, downloaded: u64, total: u64) {
+    let _ = window.emit("download-progress", DownloadProgress {
+        model_id: model_id.to_string(),
+        downloaded,
+        total,
+        percentage: download_percentage(downloaded, total),
+        status: "verifying".to_string(),
+    });
+}
+
+/// SHA-256 state of the partial download in `temp_path`, hashed off the async runtime
+async fn hash_partial_download(
+    download: &mut Download<'_>,
+    temp_path: &Path,
+) -> Result<Sha256, AppError> {
+    let partial = temp_path.to_path_buf();
+    download
+        .or_cancelled(async {
+            tauri::async_runtime::spawn_blocking(move || hash_file(&partial))
+                .await
+                .map_err(|e| AppError::Internal(format!("Checksum task failed: {}", e)))?
+                .map_err(|e| AppError::io("Failed to read partial download", e))
+        })
+        .await
+}
 
-    Ok(DownloadResult {
-        success: true,
-        message: format!("Model '{}' downloaded successfully", model_id),
-        model_path: Some(model_path.to_string_lossy().to_string()),
-    })
+/// First byte of a 206 response, from its `Content-Range: bytes <first>-<last>/<size>`
+fn content_range_start(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let range = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let (first, _) = range.trim().strip_prefix("bytes ")?.split_once('-')?;
+    first.trim().parse().ok()
+}
+
+/// Stop a model download started with `download_whisper_model` or
+/// `pull_ollama_model`. `download_id` is `whisper:<model id>` or
+/// `ollama:<model name>`, as listed by `list_downloads`.
+#[tauri::command]
+pub fn cancel_download(
+    downloads: State<'_, DownloadManager>,
+    download_id: String,
+) -> Result<(), AppError> {
+    if downloads.cancel(&download_id) {
+        log::info!("Cancelling download {}", download_id);
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "No download with id '{}' is running",
+            download_id
+        )))
+    }
+}
+
+/// Model downloads in progress, oldest first
+#[tauri::command]
+pub fn list_downloads(downloads: State<'_, DownloadManager>) -> Vec<DownloadInfo> {
+    downloads.list()
+}
+
+/// SHA-256 state of a file's contents, to continue hashing where it ends
+fn hash_file(path: &Path) -> std::io::Result<Sha256> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher)
 }
 
-/// Delete a whisper model
+/// Delete a downloaded whisper model. Custom models may be shared with other
+/// tools, so they are unregistered with `unregister_custom_model` instead.
 #[tauri::command]
-pub fn delete_whisper_model(model_id: String) -> Result<bool, String> {
+pub fn delete_whisper_model(
+    store: State<'_, SettingsStore>,
+    catalog: State<'_, ModelCatalog>,
+    model_id: String,
+) -> Result<bool, AppError> {
     // Get model info from available models
-    let models = get_available_models();
+    let models = list_models(&catalog, &store.get().models);
     let model = models
         .iter()
         .find(|m| m.id == model_id)
-        .ok_or_else(|| format!("Model '{}' not found", model_id))?;
+        .ok_or_else(|| AppError::UnknownModel { model_id: model_id.clone() })?;
+    if model.custom {
+        return Err(AppError::InvalidInput(format!(
+            "{} is a custom model; unregister it instead of deleting the file",
+            model.name
+        )));
+    }
 
     // Check if installed
     if !model.installed {
@@ -960,13 +1929,13 @@ pub fn delete_whisper_model(model_id: String) -> Result<bool, String> {
     // Get the installed path
     let model_path = model.installed_path
         .as_ref()
-        .ok_or_else(|| "Model path not found".to_string())?;
+        .ok_or_else(|| AppError::Internal("Model path not found".to_string()))?;
 
     // Delete the file
     let path = PathBuf::from(model_path);
     if path.exists() {
         std::fs::remove_file(&path)
-            .map_err(|e| format!("Failed to delete model file: {}", e))?;
+            .map_err(|e| AppError::io("Failed to delete model file", e))?;
         log::info!("Model {} deleted successfully from {}", model_id, model_path);
     }
 
@@ -990,35 +1959,30 @@ pub struct OllamaPullProgress {
 /// Result of OLLAMA model pull
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaPullResult {
-    pub success: bool,
-    pub message: String,
     pub model: String,
+    /// Last status line OLLAMA reported, normally "success"
+    pub status: String,
 }
 
 /// Result of OLLAMA model deletion
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaDeleteResult {
-    pub success: bool,
-    pub message: String,
     pub model: String,
 }
 
 /// Delete an OLLAMA model
 #[tauri::command]
 pub async fn delete_ollama_model(
+    app: AppHandle,
     model: String,
     base_url: Option<String>,
-) -> Result<OllamaDeleteResult, String> {
+) -> Result<OllamaDeleteResult, AppError> {
     let url = base_url.unwrap_or_else(|| "http://localhost:11434".to_string());
     let delete_endpoint = format!("{}/api/delete", url);
 
     log::info!("Deleting OLLAMA model '{}' via: {}", model, delete_endpoint);
 
-    // Create HTTP client with timeout
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let client = app.state::<HttpClient>().client();
 
     // Prepare the request body
     let body = serde_json::json!({ "name": model }).to_string();
@@ -1026,19 +1990,16 @@ pub async fn delete_ollama_model(
     // Send DELETE request to remove the model
     let response = client
         .delete(&delete_endpoint)
+        .timeout(std::time::Duration::from_secs(30))
         .header("Content-Type", "application/json")
         .body(body)
         .send()
         .await
-        .map_err(|e| format!("Failed to send delete request: {}", e))?;
+        .map_err(|e| AppError::network(&delete_endpoint, e))?;
 
     if response.status().is_success() {
         log::info!("Model '{}' deleted successfully", model);
-        Ok(OllamaDeleteResult {
-            success: true,
-            message: format!("Model '{}' deleted successfully", model),
-            model,
-        })
+        Ok(OllamaDeleteResult { model })
     } else {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
@@ -1054,18 +2015,59 @@ pub async fn delete_ollama_model(
         };
 
         log::warn!("Failed to delete model '{}': {} - {}", model, status, error_msg);
-        Err(format!("Failed to delete model: {}", error_msg))
+        Err(AppError::Ollama(format!("Failed to delete model: {}", error_msg)))
     }
 }
 
-/// Pull an OLLAMA model with streaming progress events
+/// Pull an OLLAMA model with streaming progress events. OLLAMA doesn't say
+/// how big a model is before pulling it, so free disk space is checked per
+/// layer as each one is announced: the pull fails with
+/// `InsufficientDiskSpace` once the layers seen so far no longer fit, which
+/// can be after earlier layers were downloaded.
 #[tauri::command]
 pub async fn pull_ollama_model(
     window: Window,
+    downloads: State<'_, DownloadManager>,
     model: String,
     base_url: Option<String>,
-) -> Result<OllamaPullResult, String> {
+) -> Result<OllamaPullResult, AppError> {
+    let mut download = downloads.start(DownloadKind::OllamaModel, &model)?;
     let url = base_url.unwrap_or_else(|| "http://localhost:11434".to_string());
+
+    let result = stream_ollama_pull(&window, &model, &url, &mut download).await;
+    if let Err(e) = &result {
+        let status = if matches!(e, AppError::Cancelled) {
+            log::info!("Pull of OLLAMA model '{}' cancelled", model);
+            "cancelled"
+        } else {
+            log::warn!("Pull of OLLAMA model '{}' failed: {}", model, e);
+            "error"
+        };
+        let (completed, total) = download.progress();
+        let _ = window.emit("ollama-pull-progress", OllamaPullProgress {
+            model: model.clone(),
+            status: status.to_string(),
+            digest: None,
+            total,
+            completed: Some(completed),
+            percentage: match total {
+                Some(t) if t > 0 => (completed as f32 / t as f32) * 100.0,
+                _ => 0.0,
+            },
+        });
+    }
+    result
+}
+
+/// Ask OLLAMA at `url` to pull `model`, relaying its progress. Closing the
+/// request on cancellation makes OLLAMA stop the pull.
+async fn stream_ollama_pull(
+    window: &Window,
+    model: &str,
+    url: &str,
+    download: &mut Download<'_>,
+) -> Result<OllamaPullResult, AppError> {
+    let model = model.to_string();
     let pull_endpoint = format!("{}/api/pull", url);
 
     log::info!("Pulling OLLAMA model '{}' from: {}", model, pull_endpoint);
@@ -1080,27 +2082,43 @@ pub async fn pull_ollama_model(
         percentage: 0.0,
     });
 
-    // Create HTTP client without timeout for long downloads
-    let client = reqwest::Client::builder()
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    // OLLAMA doesn't report a model's size before pulling it. For a server on
+    // this machine, each layer is checked against the free space when OLLAMA
+    // announces it, before its data arrives.
+    let models_dir = storage::ollama_models_dir();
+    let available = storage::is_local_url(url)
+        .then(|| storage::free_space(&models_dir).ok())
+        .flatten();
+    let mut layers = std::collections::HashSet::new();
+    let mut required: u64 = 0;
+
+    // No overall timeout for long downloads; the read timeout still applies
+    let client = window.state::<HttpClient>().client();
 
     // Prepare the request body
     let body = serde_json::json!({ "name": model }).to_string();
 
     // Send POST request to pull the model
-    let response = client
-        .post(&pull_endpoint)
-        .header("Content-Type", "application/json")
-        .body(body)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to start model pull: {}", e))?;
+    let response = download
+        .or_cancelled(async {
+            client
+                .post(&pull_endpoint)
+                .header("Content-Type", "application/json")
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| AppError::network(&pull_endpoint, e))
+        })
+        .await?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Pull failed with status {}: {}", status, error_text));
+        return Err(AppError::HttpStatus {
+            url: pull_endpoint,
+            status: status.as_u16(),
+            body: error_text,
+        });
     }
 
     // Stream the response and parse progress updates
@@ -1108,9 +2126,9 @@ pub async fn pull_ollama_model(
     let mut last_progress_update = std::time::Instant::now();
     let mut final_status = String::new();
 
-    while let Some(chunk_result) = stream.next().await {
+    while let Some(chunk_result) = download.or_cancelled(async { Ok(stream.next().await) }).await? {
         let chunk = chunk_result
-            .map_err(|e| format!("Stream error: {}", e))?;
+            .map_err(|e| AppError::network(&pull_endpoint, e))?;
 
         // OLLAMA sends newline-delimited JSON
         let chunk_str = String::from_utf8_lossy(&chunk);
@@ -1131,15 +2149,7 @@ pub async fn pull_ollama_model(
 
                 // Check for error
                 if let Some(error) = json.get("error").and_then(|v| v.as_str()) {
-                    let _ = window.emit("ollama-pull-progress", OllamaPullProgress {
-                        model: model.clone(),
-                        status: "error".to_string(),
-                        digest: None,
-                        total: None,
-                        completed: None,
-                        percentage: 0.0,
-                    });
-                    return Err(format!("Pull error: {}", error));
+                    return Err(AppError::Ollama(format!("Pull error: {}", error)));
                 }
 
                 let digest = json.get("digest")
@@ -1157,6 +2167,23 @@ pub async fn pull_ollama_model(
                     (Some(t), Some(c)) if t > 0 => Some((c as f32 / t as f32) * 100.0),
                     _ => None,
                 };
+                if let Some(c) = completed {
+                    download.set_progress(c, total);
+                }
+
+                if let (Some(available), Some(digest), Some(total)) = (available, &digest, total) {
+                    if layers.insert(digest.clone()) {
+                        // Layers already on disk report their size as completed
+                        required += total.saturating_sub(completed.unwrap_or(0));
+                        if required > available {
+                            return Err(AppError::InsufficientDiskSpace {
+                                path: models_dir.to_string_lossy().to_string(),
+                                required,
+                                available,
+                            });
+                        }
+                    }
+                }
 
                 // Emit progress every 100ms to avoid overwhelming the frontend
                 if last_progress_update.elapsed().as_millis() >= 100
@@ -1190,66 +2217,52 @@ pub async fn pull_ollama_model(
     log::info!("Model '{}' pulled successfully", model);
 
     Ok(OllamaPullResult {
-        success: true,
-        message: format!("Model '{}' pulled successfully. Final status: {}", model, final_status),
         model,
+        status: final_status,
     })
 }
 
 /// Get the path to the whisper model
-fn get_model_path(model: &str, language: &str) -> Result<String, String> {
-    // Use multilingual model for non-English languages and auto-detect
-    // English-only models (.en) only work for English
-    let use_multilingual = language != "en";  // "auto", "de", "no", etc. all use multilingual
-
-    let model_name = if use_multilingual {
-        // Multilingual models (support all languages including German, Norwegian, etc.)
-        match model {
-            "tiny" | "tiny.en" => "ggml-tiny.bin",
-            "base" | "base.en" => "ggml-base.bin",
-            "small" | "small.en" => "ggml-small.bin",
-            "medium" | "medium.en" => "ggml-medium.bin",
-            "large" => "ggml-large.bin",
-            _ => "ggml-base.bin",
+fn get_model_path(
+    catalog: &ModelCatalog,
+    settings: &ModelSettings,
+    model: &str,
+    language: &str,
+) -> Result<String, AppError> {
+    if let Some(custom) = settings.custom.iter().find(|m| m.id == model) {
+        if !Path::new(&custom.path).is_file() {
+            return Err(AppError::ModelMissing {
+                model: custom.name.clone(),
+                searched: vec![custom.path.clone()],
+                download_url: None,
+            });
         }
-    } else {
-        // English-only models (faster for English)
-        match model {
-            "tiny" | "tiny.en" => "ggml-tiny.en.bin",
-            "base" | "base.en" => "ggml-base.en.bin",
-            "small" | "small.en" => "ggml-small.en.bin",
-            "medium" | "medium.en" => "ggml-medium.en.bin",
-            "large" => "ggml-large.bin",
-            _ => "ggml-base.en.bin",
+        if language != "en" && !custom.multilingual {
+            log::warn!("Custom model '{}' is English-only; {} transcription needs a multilingual model", custom.name, language);
         }
-    };
-
-    log::info!("Looking for model: {} (language: {}, multilingual: {})", model_name, language, use_multilingual);
+        return Ok(custom.path.clone());
+    }
 
-    // Check common model paths
-    let home = std::env::var("HOME")
-        .or_else(|_| std::env::var("USERPROFILE"))
-        .unwrap_or_default();
+    let entry = catalog
+        .find(model)
+        .ok_or_else(|| AppError::UnknownModel { model_id: model.to_string() })?;
 
-    // Use PathBuf for cross-platform path handling
-    let mut search_paths: Vec<PathBuf> = vec![
-        // Our installation directory (most likely location)
-        PathBuf::from(&home).join(".voiceintelligence").join("whisper").join(model_name),
-        // Common locations
-        PathBuf::from(&home).join(".cache").join("whisper").join(model_name),
-        PathBuf::from(&home).join("whisper.cpp").join("models").join(model_name),
-        PathBuf::from("models").join(model_name),
-        PathBuf::from(model_name),
-    ];
+    // English-only models (.en) only work for English. For other languages
+    // and auto-detect, use the model's multilingual variant if there is one.
+    let use_multilingual = language != "en";  // "auto", "de", "no", etc. all use multilingual
+    let preferred = match (&entry.multilingual_variant, use_multilingual && !entry.multilingual) {
+        (Some(variant), true) => catalog.find(variant).unwrap_or_else(|| entry.clone()),
+        _ => entry.clone(),
+    };
 
-    // Also check next to the whisper binary if it's in our install dir
-    let whisper_dir = PathBuf::from(&home).join(".voiceintelligence").join("whisper");
-    if whisper_dir.exists() {
-        // Check for model in subdirectories
-        search_paths.insert(0, whisper_dir.join("models").join(model_name));
-    }
+    log::info!(
+        "Looking for model: {} (language: {}, multilingual: {})",
+        preferred.file, language, preferred.multilingual
+    );
 
-    log::info!("Searching for model {} in paths: {:?}", model_name, search_paths);
+    let whisper_dir = models::models_dir(settings);
+    let search_paths = model_search_paths(&whisper_dir, &preferred.file);
+    log::info!("Searching for model {} in paths: {:?}", preferred.file, search_paths);
 
     for path in &search_paths {
         if path.exists() {
@@ -1259,52 +2272,377 @@ fn get_model_path(model: &str, language: &str) -> Result<String, String> {
         }
     }
 
-    // If multilingual model not found for non-English, try English model as fallback
-    // (will still work but might transcribe in wrong language)
-    if use_multilingual {
-        let fallback_model_name = match model {
-            "tiny" | "tiny.en" => "ggml-tiny.en.bin",
-            "base" | "base.en" => "ggml-base.en.bin",
-            "small" | "small.en" => "ggml-small.en.bin",
-            "medium" | "medium.en" => "ggml-medium.en.bin",
-            "large" => "ggml-large.bin",
-            _ => "ggml-base.en.bin",
-        };
+    // If the multilingual variant isn't installed, fall back to the English
+    // model (will still work but might transcribe in the wrong language)
+    if preferred.id != entry.id {
+        if let Some(path) = model_search_paths(&whisper_dir, &entry.file).into_iter().find(|p| p.exists()) {
+            log::warn!(
+                "Multilingual model '{}' not found for language '{}'. Using English model '{}' as fallback. For best results, download the multilingual model.",
+                preferred.file, language, entry.file
+            );
+            return Ok(path.to_string_lossy().to_string());
+        }
+    }
 
-        let fallback_paths: Vec<PathBuf> = vec![
-            PathBuf::from(&home).join(".voiceintelligence").join("whisper").join(fallback_model_name),
-            PathBuf::from(&home).join(".cache").join("whisper").join(fallback_model_name),
-            PathBuf::from(&home).join("whisper.cpp").join("models").join(fallback_model_name),
-        ];
+    let searched: Vec<String> = search_paths.iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+
+    if use_multilingual && !preferred.multilingual {
+        log::warn!("Model '{}' is English-only; {} transcription needs a multilingual model", preferred.id, language);
+    }
+
+    Err(AppError::ModelMissing {
+        model: preferred.file,
+        searched,
+        download_url: Some(preferred.url),
+    })
+}
+
+/// Places a model file named `file_name` is looked for, most likely first.
+/// Only the models directory is searched; models elsewhere are registered
+/// as custom models.
+fn model_search_paths(whisper_dir: &Path, file_name: &str) -> Vec<PathBuf> {
+    vec![
+        // Where downloads go
+        whisper_dir.join(file_name),
+        // whisper.cpp's own layout, for a models directory shared with it
+        whisper_dir.join("models").join(file_name),
+    ]
+}
+
+// ============================================
+// Storage Commands
+// ============================================
+
+/// Disk space used by models, history, cached audio and logs. OLLAMA's
+/// models are counted from the sizes it reports, so it must be running.
+#[tauri::command]
+pub async fn get_storage_usage(
+    app: AppHandle,
+    base_url: Option<String>,
+) -> Result<StorageUsage, AppError> {
+    let ollama_models = match get_ollama_models(app.clone(), base_url).await {
+        Ok(result) => Some(result.models.iter().map(|m| m.size).sum()),
+        Err(e) => {
+            log::info!("Leaving OLLAMA models out of storage usage: {}", e);
+            None
+        }
+    };
 
-        for path in &fallback_paths {
-            if path.exists() {
-                let path_str = path.to_string_lossy().to_string();
-                log::warn!(
-                    "Multilingual model '{}' not found for language '{}'. Using English model '{}' as fallback. For best results, download the multilingual model.",
-                    model_name, language, fallback_model_name
-                );
-                return Ok(path_str);
+    let model_settings = app.state::<SettingsStore>().get().models;
+    let models_dir = models::models_dir(&model_settings);
+    // Custom models may be registered from inside the models directory too
+    let model_paths: std::collections::HashSet<PathBuf> =
+        list_models(&app.state::<ModelCatalog>(), &model_settings)
+            .into_iter()
+            .filter(|m| m.installed)
+            .filter_map(|m| m.installed_path.map(PathBuf::from))
+            .collect();
+
+    let path = app.path();
+    let data_dir = app_dir(path.app_data_dir())?;
+    let cache_dir = app_dir(path.app_cache_dir())?;
+    let log_dir = app_dir(path.app_log_dir())?;
+    let (temp_dirs, in_use) = temp_file_locations(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || StorageUsage {
+        whisper_models: model_paths.iter().map(|p| storage::dir_size(p)).sum(),
+        ollama_models,
+        history: storage::dir_size(&data_dir.join(storage::HISTORY_STORE_FILE_NAME)),
+        cached_audio: storage::dir_size(&cache_dir.join(storage::RECORDINGS_DIR_NAME)),
+        logs: storage::dir_size(&log_dir),
+        temp_files: storage::find_temp_files(&temp_dirs, &in_use),
+        models_directory_free: storage::free_space(&models_dir).ok(),
+        models_directory: models_dir.to_string_lossy().to_string(),
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Storage scan task failed: {}", e)))
+}
+
+/// Delete the `.tmp` files listed by `get_storage_usage`. Partial model
+/// downloads among them can no longer be resumed; those still downloading
+/// are kept.
+#[tauri::command]
+pub async fn clean_temp_files(app: AppHandle) -> Result<TempFileCleanup, AppError> {
+    let (temp_dirs, in_use) = temp_file_locations(&app)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut cleanup = TempFileCleanup::default();
+        for TempFile { path, size } in storage::find_temp_files(&temp_dirs, &in_use) {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    log::info!("Removed temporary file {}", path);
+                    cleanup.removed.push(path);
+                    cleanup.freed_bytes += size;
+                }
+                Err(e) => log::warn!("Failed to remove {}: {}", path, e),
             }
         }
-    }
+        cleanup
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Cleanup task failed: {}", e)))
+}
 
-    let paths_str: Vec<String> = search_paths.iter()
-        .map(|p| p.to_string_lossy().to_string())
+/// Directories the app writes `.tmp` files to, and the partial files of
+/// whisper downloads in progress
+fn temp_file_locations(app: &AppHandle) -> Result<(Vec<PathBuf>, Vec<PathBuf>), AppError> {
+    let models_dir = models::models_dir(&app.state::<SettingsStore>().get().models);
+    let catalog = app.state::<ModelCatalog>();
+    let in_use = app
+        .state::<DownloadManager>()
+        .list()
+        .into_iter()
+        .filter(|d| d.kind == DownloadKind::WhisperModel)
+        .filter_map(|d| catalog.find(&d.model_id))
+        .map(|entry| models_dir.join(entry.file).with_extension("bin.tmp"))
         .collect();
 
-    let extra_hint = if use_multilingual {
-        format!("\n\nFor {} transcription, you need the multilingual model '{}'.\nRe-run the whisper installation or download from:\nhttps://huggingface.co/ggerganov/whisper.cpp/resolve/main/{}", language, model_name, model_name)
-    } else {
-        String::new()
+    let path = app.path();
+    let dirs = vec![
+        models_dir,
+        app_dir(path.app_data_dir())?,
+        app_dir(path.app_config_dir())?,
+    ];
+    Ok((dirs, in_use))
+}
+
+fn app_dir(dir: tauri::Result<PathBuf>) -> Result<PathBuf, AppError> {
+    dir.map_err(|e| AppError::Internal(format!("Failed to resolve app directory: {}", e)))
+}
+
+// ============================================
+// Hosted LLM and Whisper API Commands
+// ============================================
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Formats the Whisper API accepts, by file extension
+const WHISPER_API_EXTENSIONS: &[&str] = &["flac", "mp3", "mp4", "mpeg", "mpga", "m4a", "ogg", "wav", "webm"];
+
+/// Largest upload the Whisper API accepts
+const WHISPER_API_MAX_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct TokenUsage {
+    pub prompt: u64,
+    pub completion: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatCompletion {
+    pub content: String,
+    pub model: String,
+    pub tokens_used: TokenUsage,
+    pub finish_reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudTranscription {
+    pub text: String,
+    /// The requested language, or the one the API detected for "auto"
+    pub language: String,
+    pub duration: f64,
+}
+
+/// Run a chat completion on OpenAI or OpenRouter with the stored API key
+#[tauri::command]
+pub async fn llm_chat_completion(
+    app: AppHandle,
+    provider: String,
+    request: ChatCompletionRequest,
+) -> Result<ChatCompletion, AppError> {
+    let (base_url, secret) = match provider.as_str() {
+        "openai" => (OPENAI_BASE_URL, SecretName::OpenAi),
+        "openrouter" => (OPENROUTER_BASE_URL, SecretName::OpenRouter),
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "'{}' is not a hosted LLM provider",
+                other
+            )))
+        }
+    };
+    let api_key = app.state::<SecretStore>().require(secret)?;
+    let client = app.state::<HttpClient>().client();
+
+    let url = format!("{}/chat/completions", base_url);
+    let body = serde_json::json!({
+        "model": request.model,
+        "messages": request.messages,
+        "temperature": request.temperature.unwrap_or(0.7),
+        "max_tokens": request.max_tokens.unwrap_or(2048),
+    });
+    let mut builder = client.post(&url).bearer_auth(api_key).json(&body);
+    if secret == SecretName::OpenRouter {
+        // Attribution headers OpenRouter asks apps to send
+        builder = builder
+            .header("HTTP-Referer", "https://voiceintelligence.app")
+            .header("X-Title", "Voice Intelligence");
+    }
+    let resp = builder.send().await.map_err(|e| AppError::network(&url, e))?;
+
+    if !resp.status().is_success() {
+        return Err(AppError::HttpStatus {
+            status: resp.status().as_u16(),
+            body: resp.text().await.unwrap_or_default(),
+            url,
+        });
+    }
+
+    let json: serde_json::Value = resp.json().await.map_err(|e| AppError::network(&url, e))?;
+    let choice = &json["choices"][0];
+    let usage = &json["usage"];
+    Ok(ChatCompletion {
+        content: choice["message"]["content"].as_str().unwrap_or_default().to_string(),
+        model: json["model"].as_str().unwrap_or(&request.model).to_string(),
+        tokens_used: TokenUsage {
+            prompt: usage["prompt_tokens"].as_u64().unwrap_or(0),
+            completion: usage["completion_tokens"].as_u64().unwrap_or(0),
+            total: usage["total_tokens"].as_u64().unwrap_or(0),
+        },
+        finish_reason: choice["finish_reason"].as_str().unwrap_or("unknown").to_string(),
+    })
+}
+
+/// Transcribe an audio file with the OpenAI Whisper API, using the Whisper
+/// API key or, without one, the OpenAI key
+#[tauri::command]
+pub async fn transcribe_with_openai(
+    app: AppHandle,
+    audio_path: String,
+    language: String,
+) -> Result<CloudTranscription, AppError> {
+    let secrets = app.state::<SecretStore>();
+    let api_key = match secrets.get(SecretName::OpenAiWhisper)? {
+        Some(key) => key,
+        None => secrets.get(SecretName::OpenAi)?.ok_or_else(|| AppError::SecretMissing {
+            name: SecretName::OpenAiWhisper.key().to_string(),
+        })?,
+    };
+
+    let path = Path::new(&audio_path);
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !WHISPER_API_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AppError::UnsupportedAudio {
+            path: audio_path,
+            reason: format!(
+                "The Whisper API accepts {} files",
+                WHISPER_API_EXTENSIONS.join(", ")
+            ),
+        });
+    }
+    let read_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::AudioNotFound {
+            path: audio_path.clone(),
+        },
+        _ => AppError::io("Failed to read audio file", e),
     };
+    // Check the size before reading, so a long recording isn't loaded only
+    // to be rejected
+    let size = tokio::fs::metadata(path).await.map_err(read_error)?.len();
+    if size > WHISPER_API_MAX_BYTES {
+        return Err(AppError::UnsupportedAudio {
+            path: audio_path,
+            reason: format!(
+                "The Whisper API accepts files up to {}",
+                models::format_size(WHISPER_API_MAX_BYTES)
+            ),
+        });
+    }
+    let audio = tokio::fs::read(path).await.map_err(read_error)?;
+
+    let mut fields = vec![("model", "whisper-1"), ("response_format", "verbose_json")];
+    if language != "auto" {
+        fields.push(("language", language.as_str()));
+    }
+    let boundary = format!("voiceintelligence-{}", uuid::Uuid::new_v4().simple());
+    let body = multipart_body(&boundary, &fields, &format!("audio.{}", extension), &audio);
+
+    let url = format!("{}/audio/transcriptions", OPENAI_BASE_URL);
+    let resp = app
+        .state::<HttpClient>()
+        .client()
+        .post(&url)
+        .bearer_auth(api_key)
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::network(&url, e))?;
+
+    if !resp.status().is_success() {
+        return Err(AppError::HttpStatus {
+            status: resp.status().as_u16(),
+            body: resp.text().await.unwrap_or_default(),
+            url,
+        });
+    }
+
+    let json: serde_json::Value = resp.json().await.map_err(|e| AppError::network(&url, e))?;
+    let text = json["text"].as_str().unwrap_or_default().trim().to_string();
+    if text.is_empty() {
+        return Err(AppError::EmptyTranscription {
+            stderr: String::new(),
+        });
+    }
+    Ok(CloudTranscription {
+        text,
+        language: match language.as_str() {
+            "auto" => json["language"].as_str().unwrap_or("auto").to_string(),
+            _ => language,
+        },
+        duration: json["duration"].as_f64().unwrap_or(0.0),
+    })
+}
 
-    Err(format!(
-        "Whisper model '{}' not found. Searched in:\n{}{}",
-        model_name,
-        paths_str.join("\n"),
-        extra_hint
-    ))
+/// `multipart/form-data` body with text `fields` followed by one file part
+fn multipart_body(boundary: &str, fields: &[(&str, &str)], file_name: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(data.len() + 512);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            boundary, file_name
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
 }
 
 // ============================================
@@ -1317,7 +2655,6 @@ const NOTION_BASE_URL: &str = "https://api.notion.com/v1";
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotionTestResult {
-    pub success: bool,
     pub message: String,
 }
 
@@ -1333,149 +2670,114 @@ pub struct NotionPage {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotionSearchResult {
-    pub success: bool,
     pub pages: Vec<NotionPage>,
-    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NotionCreatePageResult {
-    pub success: bool,
     pub page_id: Option<String>,
     pub url: Option<String>,
-    pub error: Option<String>,
 }
 
-/// Test Notion API connection by making a search request
+/// Map a non-success Notion response to an error
+async fn notion_error(url: &str, resp: reqwest::Response) -> AppError {
+    match resp.status().as_u16() {
+        401 => AppError::NotionUnauthorized,
+        403 => AppError::NotionForbidden,
+        404 => AppError::NotionNotFound,
+        status => AppError::HttpStatus {
+            url: url.to_string(),
+            status,
+            body: resp.text().await.unwrap_or_default(),
+        },
+    }
+}
+
+/// Test Notion API connection by making a search request. Tests the stored
+/// key, or `api_key` to check a key before it is saved.
 #[tauri::command]
-pub async fn notion_test_connection(api_key: String) -> NotionTestResult {
+pub async fn notion_test_connection(
+    app: AppHandle,
+    api_key: Option<String>,
+) -> Result<NotionTestResult, AppError> {
+    let api_key = match api_key {
+        Some(key) if !key.trim().is_empty() => key.trim().to_string(),
+        _ => app.state::<SecretStore>().require(SecretName::Notion)?,
+    };
+
     // Validate API key format
     if !api_key.starts_with("secret_") && !api_key.starts_with("ntn_") {
-        return NotionTestResult {
-            success: false,
-            message: "Invalid API key format. Notion API keys should start with 'secret_' or 'ntn_'.".to_string(),
-        };
+        return Err(AppError::InvalidInput(
+            "Invalid API key format. Notion API keys should start with 'secret_' or 'ntn_'.".to_string(),
+        ));
     }
 
-    let client = match reqwest::Client::builder().build() {
-        Ok(c) => c,
-        Err(e) => {
-            return NotionTestResult {
-                success: false,
-                message: format!("Failed to create HTTP client: {}", e),
-            };
-        }
-    };
+    let client = app.state::<HttpClient>().client();
 
     // Use /search endpoint instead of /users/me - doesn't require "Read user information" permission
-    let response = client
-        .post(format!("{}/search", NOTION_BASE_URL))
+    let search_url = format!("{}/search", NOTION_BASE_URL);
+    let resp = client
+        .post(&search_url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Notion-Version", NOTION_API_VERSION)
         .header("Content-Type", "application/json")
         .body(r#"{"page_size": 1}"#)
         .send()
-        .await;
+        .await
+        .map_err(|e| AppError::network(&search_url, e))?;
 
-    match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                NotionTestResult {
-                    success: true,
-                    message: "Connection successful".to_string(),
-                }
-            } else if resp.status().as_u16() == 401 {
-                NotionTestResult {
-                    success: false,
-                    message: "Invalid API key".to_string(),
-                }
-            } else {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                NotionTestResult {
-                    success: false,
-                    message: format!("API error ({}): {}", status, body),
-                }
-            }
-        }
-        Err(e) => NotionTestResult {
-            success: false,
-            message: format!("Network error: {}", e),
-        },
+    if !resp.status().is_success() {
+        return Err(notion_error(&search_url, resp).await);
     }
+
+    Ok(NotionTestResult {
+        message: "Connection successful".to_string(),
+    })
 }
 
 /// Search for accessible pages and databases in Notion
 #[tauri::command]
-pub async fn notion_search_pages(api_key: String) -> NotionSearchResult {
-    let client = match reqwest::Client::builder().build() {
-        Ok(c) => c,
-        Err(e) => {
-            return NotionSearchResult {
-                success: false,
-                pages: vec![],
-                error: Some(format!("Failed to create HTTP client: {}", e)),
-            };
-        }
-    };
+pub async fn notion_search_pages(app: AppHandle) -> Result<NotionSearchResult, AppError> {
+    let api_key = app.state::<SecretStore>().require(SecretName::Notion)?;
+
+    let client = app.state::<HttpClient>().client();
 
     let mut all_pages: Vec<NotionPage> = vec![];
 
     // Search for pages
-    let page_response = client
-        .post(format!("{}/search", NOTION_BASE_URL))
+    let search_url = format!("{}/search", NOTION_BASE_URL);
+    let resp = client
+        .post(&search_url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Notion-Version", NOTION_API_VERSION)
         .header("Content-Type", "application/json")
         .body(r#"{"filter": {"value": "page", "property": "object"}, "sort": {"direction": "descending", "timestamp": "last_edited_time"}, "page_size": 50}"#)
         .send()
-        .await;
+        .await
+        .map_err(|e| AppError::network(&search_url, e))?;
 
-    match page_response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                if let Ok(json) = resp.json::<serde_json::Value>().await {
-                    if let Some(results) = json.get("results").and_then(|r| r.as_array()) {
-                        for item in results {
-                            let id = item.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                            let name = extract_page_title(item).unwrap_or_else(|| "Untitled".to_string());
-                            all_pages.push(NotionPage {
-                                id,
-                                name,
-                                page_type: "page".to_string(),
-                            });
-                        }
-                    }
-                }
-            } else if resp.status().as_u16() == 401 {
-                return NotionSearchResult {
-                    success: false,
-                    pages: vec![],
-                    error: Some("Invalid API key".to_string()),
-                };
-            } else {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                return NotionSearchResult {
-                    success: false,
-                    pages: vec![],
-                    error: Some(format!("API error ({}): {}", status, body)),
-                };
+    if !resp.status().is_success() {
+        return Err(notion_error(&search_url, resp).await);
+    }
+
+    if let Ok(json) = resp.json::<serde_json::Value>().await {
+        if let Some(results) = json.get("results").and_then(|r| r.as_array()) {
+            for item in results {
+                let id = item.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                let name = extract_page_title(item).unwrap_or_else(|| "Untitled".to_string());
+                all_pages.push(NotionPage {
+                    id,
+                    name,
+                    page_type: "page".to_string(),
+                });
             }
         }
-        Err(e) => {
-            return NotionSearchResult {
-                success: false,
-                pages: vec![],
-                error: Some(format!("Network error: {}", e)),
-            };
-        }
     }
 
     // Search for databases
     let db_response = client
-        .post(format!("{}/search", NOTION_BASE_URL))
+        .post(&search_url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Notion-Version", NOTION_API_VERSION)
         .header("Content-Type", "application/json")
@@ -1501,33 +2803,21 @@ pub async fn notion_search_pages(api_key: String) -> NotionSearchResult {
         }
     }
 
-    NotionSearchResult {
-        success: true,
-        pages: all_pages,
-        error: None,
-    }
+    Ok(NotionSearchResult { pages: all_pages })
 }
 
 /// Create a new page in Notion
 #[tauri::command]
 pub async fn notion_create_page(
-    api_key: String,
+    app: AppHandle,
     parent_id: String,
     parent_type: String,
     title: String,
     content: String,
-) -> NotionCreatePageResult {
-    let client = match reqwest::Client::builder().build() {
-        Ok(c) => c,
-        Err(e) => {
-            return NotionCreatePageResult {
-                success: false,
-                page_id: None,
-                url: None,
-                error: Some(format!("Failed to create HTTP client: {}", e)),
-            };
-        }
-    };
+) -> Result<NotionCreatePageResult, AppError> {
+    let api_key = app.state::<SecretStore>().require(SecretName::Notion)?;
+
+    let client = app.state::<HttpClient>().client();
 
     // Build content blocks
     let blocks: Vec<serde_json::Value> = content
@@ -1572,59 +2862,26 @@ pub async fn notion_create_page(
         })
     };
 
-    let response = client
-        .post(format!("{}/pages", NOTION_BASE_URL))
+    let pages_url = format!("{}/pages", NOTION_BASE_URL);
+    let resp = client
+        .post(&pages_url)
         .header("Authorization", format!("Bearer {}", api_key))
         .header("Notion-Version", NOTION_API_VERSION)
         .header("Content-Type", "application/json")
         .body(request_body.to_string())
         .send()
-        .await;
+        .await
+        .map_err(|e| AppError::network(&pages_url, e))?;
 
-    match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                if let Ok(json) = resp.json::<serde_json::Value>().await {
-                    let page_id = json.get("id").and_then(|v| v.as_str()).map(String::from);
-                    let url = json.get("url").and_then(|v| v.as_str()).map(String::from);
-                    NotionCreatePageResult {
-                        success: true,
-                        page_id,
-                        url,
-                        error: None,
-                    }
-                } else {
-                    NotionCreatePageResult {
-                        success: true,
-                        page_id: None,
-                        url: None,
-                        error: None,
-                    }
-                }
-            } else {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                let error_msg = match status.as_u16() {
-                    401 => "Invalid or expired API key".to_string(),
-                    403 => "Access denied. Make sure the integration has access to the parent page or database.".to_string(),
-                    404 => "Parent page or database not found. Check the ID and ensure the integration has access.".to_string(),
-                    _ => format!("API error ({}): {}", status, body),
-                };
-                NotionCreatePageResult {
-                    success: false,
-                    page_id: None,
-                    url: None,
-                    error: Some(error_msg),
-                }
-            }
-        }
-        Err(e) => NotionCreatePageResult {
-            success: false,
-            page_id: None,
-            url: None,
-            error: Some(format!("Network error: {}", e)),
-        },
+    if !resp.status().is_success() {
+        return Err(notion_error(&pages_url, resp).await);
     }
+
+    let json = resp.json::<serde_json::Value>().await.unwrap_or_default();
+    Ok(NotionCreatePageResult {
+        page_id: json.get("id").and_then(|v| v.as_str()).map(String::from),
+        url: json.get("url").and_then(|v| v.as_str()).map(String::from),
+    })
 }
 
 /// Helper function to extract page title from Notion API response
@@ -1667,3 +2924,74 @@ fn extract_database_title(item: &serde_json::Value) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::Vault;
+    use crate::settings::SETTINGS_FILE_NAME;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("commands-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A bundle adding a "Work" profile and carrying an OpenAI key
+    fn bundle_with_secret(store: &SettingsStore) -> SettingsBundle {
+        let mut bundle = store.export_bundle(None).unwrap();
+        bundle.profiles[0].name = "Work".to_string();
+        bundle.secrets.push(BundledSecret {
+            name: SecretName::OpenAi,
+            value: "sk-imported".to_string(),
+        });
+        bundle
+    }
+
+    #[test]
+    fn import_with_locked_vault_changes_nothing() {
+        let dir = temp_dir();
+        let store = SettingsStore::load(dir.join(SETTINGS_FILE_NAME), &dir.join("settings.json"));
+        let secrets = SecretStore::vault(Vault::new(dir.join("secrets.vault")));
+        let bundle = bundle_with_secret(&store);
+
+        let result = apply_settings_bundle(&store, &secrets, bundle);
+
+        assert!(matches!(result, Err(AppError::VaultLocked)));
+        assert_eq!(store.list_profiles().profiles, ["Default"]);
+        assert!(!dir.join(SETTINGS_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn import_with_unlocked_vault_stores_profiles_and_secrets() {
+        let dir = temp_dir();
+        let store = SettingsStore::load(dir.join(SETTINGS_FILE_NAME), &dir.join("settings.json"));
+        let vault = Vault::new(dir.join("secrets.vault"));
+        vault.unlock("correct horse battery staple").unwrap();
+        let secrets = SecretStore::vault(vault);
+        let bundle = bundle_with_secret(&store);
+
+        let profiles = apply_settings_bundle(&store, &secrets, bundle).unwrap();
+
+        assert_eq!(profiles.profiles, ["Default", "Work"]);
+        assert_eq!(
+            secrets.get(SecretName::OpenAi).unwrap().as_deref(),
+            Some("sk-imported")
+        );
+    }
+
+    #[test]
+    fn reads_content_range_start() {
+        let headers = |value: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::CONTENT_RANGE, value.parse().unwrap());
+            headers
+        };
+
+        assert_eq!(content_range_start(&headers("bytes 1024-2047/2048")), Some(1024));
+        assert_eq!(content_range_start(&headers("bytes 0-99/*")), Some(0));
+        assert_eq!(content_range_start(&headers("bytes */2048")), None);
+        assert_eq!(content_range_start(&headers("items 1024-2047/2048")), None);
+        assert_eq!(content_range_start(&reqwest::header::HeaderMap::new()), None);
+    }
+}
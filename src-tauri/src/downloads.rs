@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard};
+use tokio::sync::oneshot;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadKind {
+    WhisperModel,
+    OllamaModel,
+}
+
+impl DownloadKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            DownloadKind::WhisperModel => "whisper",
+            DownloadKind::OllamaModel => "ollama",
+        }
+    }
+}
+
+/// A download in flight, as returned by `list_downloads`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadInfo {
+    /// `whisper:<model id>` or `ollama:<model name>`
+    pub id: String,
+    pub kind: DownloadKind,
+    pub model_id: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// Unix timestamp in milliseconds
+    pub started_at: i64,
+    /// Cancellation was requested and the download is winding down
+    pub cancelling: bool,
+}
+
+struct ActiveDownload {
+    info: DownloadInfo,
+    /// Taken when the download is cancelled
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// Model downloads in flight, keyed by download id. Held in Tauri managed
+/// state so `cancel_download` and `list_downloads` can reach them.
+#[derive(Default)]
+pub struct DownloadManager {
+    active: Mutex<HashMap<String, ActiveDownload>>,
+}
+
+impl DownloadManager {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ActiveDownload>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register a download, failing if the same model is already being
+    /// downloaded. It is removed again when the returned handle drops, so a
+    /// cancelled download keeps its id until it has cleaned up.
+    pub fn start(&self, kind: DownloadKind, model_id: &str) -> Result<Download<'_>, AppError> {
+        let id = format!("{}:{}", kind.prefix(), model_id);
+        let mut active = self.lock();
+        if active.contains_key(&id) {
+            return Err(AppError::DownloadInProgress { id });
+        }
+
+        let (cancel, cancelled) = oneshot::channel();
+        active.insert(
+            id.clone(),
+            ActiveDownload {
+                info: DownloadInfo {
+                    id: id.clone(),
+                    kind,
+                    model_id: model_id.to_string(),
+                    downloaded: 0,
+                    total: None,
+                    started_at: chrono::Utc::now().timestamp_millis(),
+                    cancelling: false,
+                },
+                cancel: Some(cancel),
+            },
+        );
+        Ok(Download {
+            manager: self,
+            id,
+            cancelled,
+        })
+    }
+
+    /// Signal a download to stop. Returns false if no such download is running.
+    pub fn cancel(&self, id: &str) -> bool {
+        let mut active = self.lock();
+        let Some(download) = active.get_mut(id) else {
+            return false;
+        };
+        if let Some(cancel) = download.cancel.take() {
+            let _ = cancel.send(());
+        }
+        download.info.cancelling = true;
+        true
+    }
+
+    /// Downloads in flight, oldest first
+    pub fn list(&self) -> Vec<DownloadInfo> {
+        let mut downloads: Vec<DownloadInfo> =
+            self.lock().values().map(|d| d.info.clone()).collect();
+        downloads.sort_by_key(|d| d.started_at);
+        downloads
+    }
+}
+
+/// A registered download
+pub struct Download<'a> {
+    manager: &'a DownloadManager,
+    pub id: String,
+    cancelled: oneshot::Receiver<()>,
+}
+
+impl Download<'_> {
+    /// Await `future`, failing with `Cancelled` if the download is cancelled
+    /// first
+    pub async fn or_cancelled<T>(
+        &mut self,
+        future: impl Future<Output = Result<T, AppError>>,
+    ) -> Result<T, AppError> {
+        tokio::select! {
+            result = future => result,
+            Ok(()) = &mut self.cancelled => Err(AppError::Cancelled),
+        }
+    }
+
+    /// Record progress for `list_downloads`
+    pub fn set_progress(&self, downloaded: u64, total: Option<u64>) {
+        if let Some(download) = self.manager.lock().get_mut(&self.id) {
+            download.info.downloaded = downloaded;
+            download.info.total = total;
+        }
+    }
+
+    /// Last recorded progress
+    pub fn progress(&self) -> (u64, Option<u64>) {
+        self.manager
+            .lock()
+            .get(&self.id)
+            .map(|d| (d.info.downloaded, d.info.total))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Download<'_> {
+    fn drop(&mut self) {
+        self.manager.lock().remove(&self.id);
+    }
+}
@@ -0,0 +1,411 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::json;
+
+/// Error returned by every backend command.
+///
+/// Serializes as `{ code, message, details }`: `code` is a stable
+/// SCREAMING_SNAKE_CASE identifier the UI can match on to offer a targeted
+/// fix, `message` is a readable summary and `details` carries structured data.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Whisper binary not found{}", path.as_ref().map(|p| format!(" at '{}'", p)).unwrap_or_default())]
+    WhisperNotFound { path: Option<String> },
+
+    #[error("Whisper transcription failed (exit code: {exit_code:?})")]
+    WhisperFailed {
+        exit_code: Option<i32>,
+        command: String,
+        stderr: String,
+        stdout: String,
+    },
+
+    #[error("Whisper returned an empty transcription. The audio may be too short, silent or in an unsupported format.")]
+    EmptyTranscription { stderr: String },
+
+    #[error("{0}")]
+    WhisperInstallFailed(String),
+
+    #[error("Audio file not found: {path}")]
+    AudioNotFound { path: String },
+
+    #[error("Unsupported audio file: {reason}")]
+    UnsupportedAudio { path: String, reason: String },
+
+    #[error("Audio input unavailable: {reason}")]
+    AudioDevice { device: Option<String>, reason: String },
+
+    #[error("A recording is already in progress")]
+    RecordingInProgress,
+
+    #[error("No recording in progress")]
+    NotRecording,
+
+    #[error("{0}")]
+    Audio(String),
+
+    #[error("Whisper model '{model}' not found")]
+    ModelMissing {
+        model: String,
+        searched: Vec<String>,
+        download_url: Option<String>,
+    },
+
+    #[error("Unknown model '{model_id}'")]
+    UnknownModel { model_id: String },
+
+    #[error("Downloaded model '{model_id}' is corrupted (checksum mismatch). Please download it again.")]
+    ChecksumMismatch {
+        model_id: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("'{id}' is already being downloaded")]
+    DownloadInProgress { id: String },
+
+    #[error(
+        "Not enough disk space in {path}: {} needed, {} free",
+        crate::models::format_size(*.required),
+        crate::models::format_size(*.available)
+    )]
+    InsufficientDiskSpace {
+        path: String,
+        required: u64,
+        available: u64,
+    },
+
+    #[error("Network unavailable: {reason}")]
+    NetworkUnavailable { url: String, reason: String },
+
+    #[error("Request to {url} failed with status {status}")]
+    HttpStatus { url: String, status: u16, body: String },
+
+    /// The request couldn't be built or the response couldn't be decoded;
+    /// retrying won't help
+    #[error("Request to {url} failed: {reason}")]
+    HttpRequest { url: String, reason: String },
+
+    #[error("{0}")]
+    NetworkConfig(String),
+
+    #[error("Invalid or expired Notion API key")]
+    NotionUnauthorized,
+
+    #[error("Access denied. Make sure the integration has access to the parent page or database.")]
+    NotionForbidden,
+
+    #[error("Parent page or database not found. Check the ID and ensure the integration has access.")]
+    NotionNotFound,
+
+    #[error("OLLAMA error: {0}")]
+    Ollama(String),
+
+    #[error("No '{name}' API key configured. Add it in Settings.")]
+    SecretMissing { name: String },
+
+    #[error("Secret vault is locked. Unlock it in Settings.")]
+    VaultLocked,
+
+    #[error("Incorrect passphrase or corrupted vault")]
+    IncorrectPassphrase,
+
+    #[error("Vault file has an unsupported key derivation setting: {param} is {value}, expected {min} to {max}")]
+    VaultKdfParams {
+        param: String,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+
+    #[error("{0}")]
+    SecretStore(String),
+
+    #[error("{message}")]
+    InvalidSetting { field: String, message: String },
+
+    /// Reading, writing or changing the settings store failed
+    #[error("{0}")]
+    Settings(String),
+
+    #[error("{0}")]
+    InvalidInput(String),
+
+    #[error("{context}: {message}")]
+    Io { context: String, message: String },
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::WhisperNotFound { .. } => "WHISPER_NOT_FOUND",
+            AppError::WhisperFailed { .. } => "WHISPER_FAILED",
+            AppError::EmptyTranscription { .. } => "EMPTY_TRANSCRIPTION",
+            AppError::WhisperInstallFailed(_) => "WHISPER_INSTALL_FAILED",
+            AppError::AudioNotFound { .. } => "AUDIO_NOT_FOUND",
+            AppError::UnsupportedAudio { .. } => "UNSUPPORTED_AUDIO_FORMAT",
+            AppError::AudioDevice { .. } => "AUDIO_DEVICE_UNAVAILABLE",
+            AppError::RecordingInProgress => "RECORDING_IN_PROGRESS",
+            AppError::NotRecording => "NOT_RECORDING",
+            AppError::Audio(_) => "AUDIO_ERROR",
+            AppError::ModelMissing { .. } => "MODEL_MISSING",
+            AppError::UnknownModel { .. } => "UNKNOWN_MODEL",
+            AppError::ChecksumMismatch { .. } => "MODEL_CHECKSUM_MISMATCH",
+            AppError::DownloadInProgress { .. } => "DOWNLOAD_IN_PROGRESS",
+            AppError::InsufficientDiskSpace { .. } => "INSUFFICIENT_DISK_SPACE",
+            AppError::NetworkUnavailable { .. } => "NETWORK_UNAVAILABLE",
+            AppError::HttpStatus { .. } => "HTTP_ERROR",
+            AppError::HttpRequest { .. } => "HTTP_REQUEST_FAILED",
+            AppError::NetworkConfig(_) => "NETWORK_CONFIG_INVALID",
+            AppError::NotionUnauthorized => "NOTION_UNAUTHORIZED",
+            AppError::NotionForbidden => "NOTION_FORBIDDEN",
+            AppError::NotionNotFound => "NOTION_NOT_FOUND",
+            AppError::Ollama(_) => "OLLAMA_ERROR",
+            AppError::SecretMissing { .. } => "SECRET_MISSING",
+            AppError::VaultLocked => "VAULT_LOCKED",
+            AppError::IncorrectPassphrase => "INCORRECT_PASSPHRASE",
+            AppError::VaultKdfParams { .. } => "VAULT_KDF_PARAMS_INVALID",
+            AppError::SecretStore(_) => "SECRET_STORE_ERROR",
+            AppError::InvalidSetting { .. } => "INVALID_SETTING",
+            AppError::Settings(_) => "SETTINGS_ERROR",
+            AppError::InvalidInput(_) => "INVALID_INPUT",
+            AppError::Io { .. } => "IO_ERROR",
+            AppError::Cancelled => "CANCELLED",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// Structured details for the UI, `null` when there are none
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            AppError::WhisperNotFound { path } => json!({ "path": path }),
+            AppError::WhisperFailed { exit_code, command, stderr, stdout } => json!({
+                "exitCode": exit_code,
+                "command": command,
+                "stderr": stderr,
+                "stdout": stdout,
+            }),
+            AppError::EmptyTranscription { stderr } => json!({ "stderr": stderr }),
+            AppError::AudioNotFound { path } => json!({ "path": path }),
+            AppError::UnsupportedAudio { path, reason } => json!({ "path": path, "reason": reason }),
+            AppError::AudioDevice { device, reason } => json!({ "device": device, "reason": reason }),
+            AppError::ModelMissing { model, searched, download_url } => json!({
+                "model": model,
+                "searched": searched,
+                "downloadUrl": download_url,
+            }),
+            AppError::UnknownModel { model_id } => json!({ "modelId": model_id }),
+            AppError::ChecksumMismatch { model_id, expected, actual } => json!({
+                "modelId": model_id,
+                "expected": expected,
+                "actual": actual,
+            }),
+            AppError::DownloadInProgress { id } => json!({ "id": id }),
+            AppError::InsufficientDiskSpace { path, required, available } => json!({
+                "path": path,
+                "required": required,
+                "available": available,
+            }),
+            AppError::NetworkUnavailable { url, reason } => json!({ "url": url, "reason": reason }),
+            AppError::HttpRequest { url, reason } => json!({ "url": url, "reason": reason }),
+            AppError::HttpStatus { url, status, body } => json!({
+                "url": url,
+                "status": status,
+                "body": body,
+            }),
+            AppError::SecretMissing { name } => json!({ "name": name }),
+            AppError::VaultKdfParams { param, value, min, max } => json!({
+                "param": param,
+                "value": value,
+                "min": min,
+                "max": max,
+            }),
+            AppError::InvalidSetting { field, .. } => json!({ "field": field }),
+            AppError::Io { context, message } => json!({ "context": context, "message": message }),
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    /// Wrap a `reqwest` error from talking to `url`. Failing to connect,
+    /// timing out and losing the connection while sending the request or
+    /// reading the body mean the network is unavailable. A request that can't
+    /// be built or a response that can't be decoded is not reported as being
+    /// offline.
+    pub fn network(url: &str, err: reqwest::Error) -> Self {
+        let url = url.to_string();
+        let root: &(dyn std::error::Error + 'static) = &err;
+        let chain: Vec<_> = std::iter::successors(Some(root), |e| e.source()).collect();
+        // The innermost error holds the actual cause, e.g. "connection reset"
+        let reason = match chain.last() {
+            Some(cause) if chain.len() > 1 => format!("{}: {}", err, cause),
+            _ => err.to_string(),
+        };
+        // A body that breaks off while it is collected or streamed is
+        // reported as a decode error wrapping the body error, so the whole
+        // chain counts
+        let lost_connection = chain
+            .iter()
+            .filter_map(|e| e.downcast_ref::<reqwest::Error>())
+            .any(|e| e.is_connect() || e.is_timeout() || e.is_request() || e.is_body());
+
+        if lost_connection {
+            AppError::NetworkUnavailable { url, reason }
+        } else if let Some(status) = err.status() {
+            AppError::HttpStatus {
+                url,
+                status: status.as_u16(),
+                body: String::new(),
+            }
+        } else {
+            // Decoding, building the request, redirect loops
+            AppError::HttpRequest { url, reason }
+        }
+    }
+
+    /// Wrap an I/O error with a short description of what was being done
+    pub fn io(context: impl Into<String>, err: impl std::fmt::Display) -> Self {
+        AppError::Io {
+            context: context.into(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<crate::settings::InvalidSetting> for AppError {
+    fn from(err: crate::settings::InvalidSetting) -> Self {
+        AppError::InvalidSetting {
+            field: err.field,
+            message: err.message,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    /// Serve one connection on a local port: read the request, send
+    /// `response` and close. Returns the URL to request.
+    fn serve_once(response: &'static [u8], delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request);
+            std::thread::sleep(delay);
+            let _ = stream.write_all(response);
+        });
+        url
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap()
+    }
+
+    async fn map(url: &str, result: Result<reqwest::Response, reqwest::Error>) -> AppError {
+        let result = match result {
+            Ok(response) => response.json::<serde_json::Value>().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        AppError::network(url, result.unwrap_err())
+    }
+
+    #[tokio::test]
+    async fn refused_connection_is_network_unavailable() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let url = format!("http://127.0.0.1:{}/", port);
+
+        let err = map(&url, client().get(&url).send().await).await;
+
+        assert_eq!(err.code(), "NETWORK_UNAVAILABLE");
+        assert_eq!(err.details()["url"], url);
+    }
+
+    #[tokio::test]
+    async fn timeout_is_network_unavailable() {
+        let url = serve_once(b"", Duration::from_secs(2));
+
+        let err = map(&url, client().get(&url).send().await).await;
+
+        assert_eq!(err.code(), "NETWORK_UNAVAILABLE");
+    }
+
+    #[tokio::test]
+    async fn closed_connection_is_network_unavailable() {
+        // Closed before any response
+        let url = serve_once(b"", Duration::ZERO);
+        let err = map(&url, client().get(&url).send().await).await;
+        assert_eq!(err.code(), "NETWORK_UNAVAILABLE");
+
+        // Closed halfway through the body
+        let url = serve_once(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n{\"partial\"",
+            Duration::ZERO,
+        );
+        let err = map(&url, client().get(&url).send().await).await;
+        assert_eq!(err.code(), "NETWORK_UNAVAILABLE");
+    }
+
+    #[tokio::test]
+    async fn undecodable_response_is_a_request_error() {
+        let url = serve_once(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 8\r\n\r\nnot json",
+            Duration::ZERO,
+        );
+
+        let err = map(&url, client().get(&url).send().await).await;
+
+        assert_eq!(err.code(), "HTTP_REQUEST_FAILED");
+        assert_eq!(err.details()["url"], url);
+    }
+
+    #[tokio::test]
+    async fn unbuildable_request_is_a_request_error() {
+        let url = "http://[::1";
+
+        let err = map(url, client().get(url).send().await).await;
+
+        assert_eq!(err.code(), "HTTP_REQUEST_FAILED");
+    }
+
+    #[tokio::test]
+    async fn status_errors_keep_the_status() {
+        let url = serve_once(
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n",
+            Duration::ZERO,
+        );
+
+        let response = client().get(&url).send().await.unwrap();
+        let err = AppError::network(&url, response.error_for_status().unwrap_err());
+
+        assert_eq!(err.code(), "HTTP_ERROR");
+        assert_eq!(err.details()["status"], 503);
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::settings::NetworkSettings;
+
+/// User agent sent when the settings don't override it
+const DEFAULT_USER_AGENT: &str = concat!("VoiceIntelligence/", env!("CARGO_PKG_VERSION"));
+
+/// Hosts that never go through the proxy, so a local OLLAMA keeps working
+const ALWAYS_NO_PROXY: &str = "localhost,127.0.0.1,::1";
+
+/// Shared HTTP client held in Tauri managed state. Rebuilt whenever the
+/// network settings change; callers take a cheap clone per request.
+pub struct HttpClient {
+    client: RwLock<reqwest::Client>,
+}
+
+impl HttpClient {
+    /// Build the client from settings, falling back to defaults if they are
+    /// unusable (e.g. the CA bundle was removed) so the app still starts
+    pub fn new(settings: &NetworkSettings) -> Self {
+        let client = build_client(settings).unwrap_or_else(|e| {
+            log::warn!("Invalid network settings, using defaults: {}", e);
+            build_client(&NetworkSettings::default()).unwrap_or_default()
+        });
+        Self {
+            client: RwLock::new(client),
+        }
+    }
+
+    /// The current client. Clones share the connection pool.
+    pub fn client(&self) -> reqwest::Client {
+        self.client.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the client with one built from new settings. On error the
+    /// previous client stays in place.
+    pub fn configure(&self, settings: &NetworkSettings) -> Result<(), AppError> {
+        let client = build_client(settings)?;
+        self.install(client, settings);
+        Ok(())
+    }
+
+    /// Replace the client with one already built from `settings` by
+    /// `build_client`, e.g. once those settings have been saved
+    pub fn install(&self, client: reqwest::Client, settings: &NetworkSettings) {
+        *self.client.write().unwrap_or_else(|e| e.into_inner()) = client;
+        log::info!(
+            "HTTP client configured (proxy: {}, CA bundle: {})",
+            settings.proxy_url.as_deref().unwrap_or("none"),
+            settings.ca_bundle_path.as_deref().unwrap_or("none")
+        );
+    }
+}
+
+/// Build a `reqwest::Client` for the given network settings
+pub fn build_client(settings: &NetworkSettings) -> Result<reqwest::Client, AppError> {
+    let user_agent = settings
+        .user_agent
+        .as_deref()
+        .filter(|ua| !ua.trim().is_empty())
+        .unwrap_or(DEFAULT_USER_AGENT);
+
+    let mut builder = reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_secs))
+        .read_timeout(Duration::from_secs(settings.read_timeout_secs));
+
+    if let Some(proxy_url) = settings.proxy_url.as_deref().filter(|p| !p.trim().is_empty()) {
+        let no_proxy = match settings.no_proxy.as_deref().filter(|n| !n.trim().is_empty()) {
+            Some(extra) => format!("{},{}", ALWAYS_NO_PROXY, extra),
+            None => ALWAYS_NO_PROXY.to_string(),
+        };
+        let proxy = reqwest::Proxy::all(proxy_url.trim())
+            .map_err(|e| AppError::NetworkConfig(format!("Invalid proxy URL '{}': {}", proxy_url, e)))?
+            .no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = settings.ca_bundle_path.as_deref().filter(|p| !p.trim().is_empty()) {
+        let pem = std::fs::read(path)
+            .map_err(|e| AppError::NetworkConfig(format!("Failed to read CA bundle '{}': {}", path, e)))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| AppError::NetworkConfig(format!("Invalid CA bundle '{}': {}", path, e)))?;
+        if certificates.is_empty() {
+            return Err(AppError::NetworkConfig(format!(
+                "CA bundle '{}' contains no certificates",
+                path
+            )));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::NetworkConfig(format!("Failed to create HTTP client: {}", e)))
+}
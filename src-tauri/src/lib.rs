@@ -1,10 +1,129 @@
 use tauri::{
-    Manager,
-    menu::{Menu, MenuItem},
+    AppHandle, Emitter, Manager, Wry,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
 };
+use tauri_plugin_dialog::DialogExt;
 
+mod audio;
 mod commands;
+mod downloads;
+mod error;
+mod http;
+mod models;
+mod queue;
+mod secrets;
+mod settings;
+mod storage;
+mod subtitles;
+mod vocabulary;
+mod whisper;
+
+const TRAY_ID: &str = "main";
+const TRAY_TOOLTIP: &str = "Voice Intelligence";
+const PROFILE_MENU_PREFIX: &str = "profile:";
+
+/// How often the idle vault auto-lock is checked
+const VAULT_IDLE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+fn vault_locked(app: &AppHandle) -> bool {
+    app.state::<secrets::SecretStore>()
+        .vault_backend()
+        .is_some_and(|vault| vault.status().locked)
+}
+
+fn tray_tooltip(app: &AppHandle) -> String {
+    if vault_locked(app) {
+        format!("{} (secrets locked)", TRAY_TOOLTIP)
+    } else {
+        TRAY_TOOLTIP.to_string()
+    }
+}
+
+/// Build the tray menu, including a submenu for switching settings profiles
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let profiles = app.state::<settings::SettingsStore>().list_profiles();
+    let profile_items = profiles
+        .profiles
+        .iter()
+        .map(|name| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", PROFILE_MENU_PREFIX, name),
+                name,
+                true,
+                *name == profiles.active_profile,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_refs: Vec<&dyn IsMenuItem<Wry>> = profile_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let profiles_menu = Submenu::with_items(app, "Profiles", true, &profile_refs)?;
+
+    let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let transcribe_item =
+        MenuItem::with_id(app, "transcribe-file", "Transcribe File…", true, None::<&str>)?;
+    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[&show_item, &transcribe_item, &settings_item, &profiles_menu, &separator, &quit_item],
+    )?;
+
+    // Surface a locked vault at the top of the menu
+    if vault_locked(app) {
+        let unlock_item =
+            MenuItem::with_id(app, "unlock-vault", "🔒 Secrets Locked – Unlock…", true, None::<&str>)?;
+        menu.prepend(&unlock_item)?;
+    }
+
+    Ok(menu)
+}
+
+/// Let the user pick audio files from the tray and queue them for transcription
+fn pick_audio_files(app: &AppHandle) {
+    let handle = app.clone();
+    app.dialog()
+        .file()
+        .set_title("Transcribe Audio Files")
+        .add_filter("Audio", audio::SUPPORTED_EXTENSIONS)
+        .pick_files(move |files| {
+            let Some(files) = files else { return };
+            let paths = files
+                .into_iter()
+                .filter_map(|file| file.into_path().ok())
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+
+            // The queue is processed by the main window
+            if let Some(window) = handle.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            commands::queue_audio_files(&handle, paths);
+        });
+}
+
+/// Rebuild the tray menu and tooltip after profiles or the vault lock state changed
+pub(crate) fn refresh_tray_menu(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tray_tooltip(app)));
+
+        match build_tray_menu(app) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    log::warn!("Failed to update tray menu: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to build tray menu: {}", e),
+        }
+    }
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -25,17 +144,73 @@ pub fn run() {
                 )?;
             }
 
-            // Setup system tray
-            let show_item = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-            let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+            // Load persistent settings, importing those the frontend kept in its
+            // plugin-store file on first run
+            let config_dir = app.path().app_config_dir()?;
+            let legacy_settings = app.path().app_data_dir()?.join(storage::HISTORY_STORE_FILE_NAME);
+            let settings_store = settings::SettingsStore::load(
+                config_dir.join(settings::SETTINGS_FILE_NAME),
+                &legacy_settings,
+            );
+
+            // One HTTP client for all outgoing requests, honouring proxy and CA settings
+            app.manage(http::HttpClient::new(&settings_store.get().network));
+            app.manage(settings_store);
+
+            // Native microphone capture, usable from the hotkey while the window is hidden
+            app.manage(audio::Recorder::new());
+            app.manage(whisper::TranscriptionJobs::default());
+            app.manage(downloads::DownloadManager::default());
+
+            // Whisper model catalog, bundled or last refreshed
+            let catalog_path = app.path().app_data_dir()?.join(models::MANIFEST_CACHE_FILE_NAME);
+            app.manage(models::ModelCatalog::load(catalog_path));
+
+            // Transcription jobs survive restarts; interrupted ones start again
+            let queue_path = app.path().app_data_dir()?.join(queue::QUEUE_FILE_NAME);
+            app.manage(queue::JobQueue::load(queue_path));
+            tauri::async_runtime::spawn(queue::run(app.handle().clone()));
+
+            // Secrets live in the OS keyring, never in the settings file. Without
+            // a reachable keyring (or once a vault exists) use the encrypted vault.
+            let vault_path = app.path().app_data_dir()?.join(secrets::VAULT_FILE_NAME);
+            if !vault_path.exists() && secrets::KeyringBackend::is_available() {
+                app.manage(secrets::SecretStore::keyring());
+            } else {
+                log::info!("Using encrypted secret vault at {}", vault_path.display());
+                app.manage(secrets::SecretStore::vault(secrets::Vault::new(vault_path)));
+
+                // Auto-lock the vault once it has been idle for the configured time
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        tokio::time::sleep(VAULT_IDLE_CHECK_INTERVAL).await;
+                        let minutes = handle.state::<settings::SettingsStore>().get().vault_auto_lock_minutes;
+                        if minutes == 0 {
+                            continue;
+                        }
+                        let timeout = std::time::Duration::from_secs(u64::from(minutes) * 60);
+                        let locked = handle
+                            .state::<secrets::SecretStore>()
+                            .vault_backend()
+                            .is_some_and(|vault| vault.lock_if_idle(timeout));
+                        if locked {
+                            commands::notify_vault_status(&handle);
+                        }
+                    }
+                });
+            }
+
+            // Keys saved by older versions in the frontend's plain-text store
+            commands::migrate_plaintext_keys(app.handle());
 
-            let menu = Menu::with_items(app, &[&show_item, &settings_item, &quit_item])?;
+            // Setup system tray
+            let menu = build_tray_menu(app.handle())?;
 
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
-                .tooltip("Voice Intelligence")
+                .tooltip(tray_tooltip(app.handle()))
                 .on_menu_event(|app, event| {
                     match event.id.as_ref() {
                         "show" => {
@@ -44,6 +219,7 @@ pub fn run() {
                                 let _ = window.set_focus();
                             }
                         }
+                        "transcribe-file" => pick_audio_files(app),
                         "settings" => {
                             if let Some(window) = app.get_webview_window("main") {
                                 let _ = window.show();
@@ -51,10 +227,24 @@ pub fn run() {
                                 let _ = window.eval("window.location.href = '/settings'");
                             }
                         }
+                        "unlock-vault" => {
+                            // The window shows the passphrase prompt on this event
+                            if let Some(window) = app.get_webview_window("main") {
+                                let _ = window.show();
+                                let _ = window.set_focus();
+                            }
+                            let _ = app.emit("vault-unlock-requested", ());
+                        }
                         "quit" => {
                             app.exit(0);
                         }
-                        _ => {}
+                        id => {
+                            if let Some(name) = id.strip_prefix(PROFILE_MENU_PREFIX) {
+                                if let Err(e) = commands::activate_profile(app, name) {
+                                    log::warn!("Failed to switch profile from tray: {}", e);
+                                }
+                            }
+                        }
                     }
                 })
                 .on_tray_icon_event(|tray, event| {
@@ -74,17 +264,54 @@ pub fn run() {
             commands::get_app_version,
             commands::get_settings,
             commands::save_settings,
+            commands::get_settings_migration_report,
+            commands::get_vocabulary,
+            commands::set_vocabulary,
+            commands::list_profiles,
+            commands::create_profile,
+            commands::clone_profile,
+            commands::switch_profile,
+            commands::delete_profile,
+            commands::export_settings,
+            commands::preview_settings_import,
+            commands::import_settings,
+            commands::set_secret,
+            commands::get_secret_status,
+            commands::delete_secret,
+            commands::get_vault_status,
+            commands::unlock_vault,
+            commands::lock_vault,
+            commands::list_input_devices,
+            commands::start_recording,
+            commands::stop_recording,
+            commands::cancel_recording,
+            commands::get_recording_status,
+            commands::import_audio_files,
             commands::check_whisper_available,
             commands::verify_whisper_path,
             commands::transcribe_audio,
+            commands::export_subtitles,
+            commands::cancel_transcription,
+            commands::enqueue_transcription,
+            commands::list_transcription_jobs,
+            commands::remove_transcription_job,
             commands::install_whisper,
             commands::get_available_models,
+            commands::refresh_model_catalog,
             commands::download_whisper_model,
             commands::delete_whisper_model,
+            commands::register_custom_model,
+            commands::unregister_custom_model,
             commands::check_ollama_available,
             commands::get_ollama_models,
             commands::delete_ollama_model,
             commands::pull_ollama_model,
+            commands::cancel_download,
+            commands::list_downloads,
+            commands::get_storage_usage,
+            commands::clean_temp_files,
+            commands::llm_chat_completion,
+            commands::transcribe_with_openai,
             commands::notion_test_connection,
             commands::notion_search_pages,
             commands::notion_create_page,
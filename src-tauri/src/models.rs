@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::error::AppError;
+use crate::settings::ModelSettings;
+
+/// Catalog shipped with the app, used until a refreshed one is available
+const BUNDLED_MANIFEST: &str = include_str!("../resources/whisper-models.json");
+
+/// File in the app data directory holding the last refreshed catalog
+pub const MANIFEST_CACHE_FILE_NAME: &str = "whisper-models.json";
+
+/// Manifest format this version of the app understands
+const MANIFEST_VERSION: u32 = 1;
+
+/// Prefix of the ids of models registered from a file
+pub const CUSTOM_MODEL_PREFIX: &str = "custom:";
+
+/// `lmgg`, the magic number whisper.cpp model files start with
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// Vocabulary size of the multilingual whisper models; English-only models
+/// have one token fewer
+const MULTILINGUAL_VOCAB_SIZE: i32 = 51865;
+
+/// List of whisper models that can be downloaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelManifest {
+    pub version: u32,
+    pub models: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelEntry {
+    pub id: String,
+    pub name: String,
+    /// File name in the models directory, e.g. `ggml-base.en.bin`
+    pub file: String,
+    pub url: String,
+    pub size_bytes: u64,
+    /// Checked after downloading; every catalog model must have one
+    pub sha256: String,
+    pub multilingual: bool,
+    /// Memory the machine should have to run the model comfortably
+    pub recommended_ram_mb: u32,
+    /// Quantization type such as `q5_0`, for quantized files
+    #[serde(default)]
+    pub quantization: Option<String>,
+    /// For English-only models, the model to use instead for other languages
+    #[serde(default)]
+    pub multilingual_variant: Option<String>,
+    /// Former ids that still select this model, e.g. from saved settings
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl ModelManifest {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let manifest: ModelManifest =
+            serde_json::from_str(json).map_err(|e| format!("Invalid model manifest: {}", e))?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.version != MANIFEST_VERSION {
+            return Err(format!(
+                "Unsupported model manifest version {} (expected {})",
+                self.version, MANIFEST_VERSION
+            ));
+        }
+        if self.models.is_empty() {
+            return Err("Model manifest lists no models".to_string());
+        }
+
+        let mut ids = HashSet::new();
+        let mut files = HashSet::new();
+        for model in &self.models {
+            for id in std::iter::once(&model.id).chain(&model.aliases) {
+                if id.trim().is_empty() || !ids.insert(id.as_str()) {
+                    return Err(format!("Duplicate or empty model id '{}'", id));
+                }
+            }
+            // The file name ends up in a path inside the models directory
+            if !is_model_file_name(&model.file) || !files.insert(model.file.as_str()) {
+                return Err(format!(
+                    "Model '{}' has an invalid or duplicate file name '{}'",
+                    model.id, model.file
+                ));
+            }
+            if !model.url.starts_with("https://") && !model.url.starts_with("http://") {
+                return Err(format!("Model '{}' has an invalid URL", model.id));
+            }
+            if model.sha256.len() != 64 || !model.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Model '{}' has an invalid SHA-256", model.id));
+            }
+        }
+
+        for model in &self.models {
+            if let Some(variant) = &model.multilingual_variant {
+                if !self
+                    .models
+                    .iter()
+                    .any(|m| &m.id == variant && m.multilingual)
+                {
+                    return Err(format!(
+                        "Model '{}' names '{}' as its multilingual variant, which is not a multilingual model in the manifest",
+                        model.id, variant
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Look a model up by id or former id
+    pub fn find(&self, id: &str) -> Option<&ModelEntry> {
+        self.models
+            .iter()
+            .find(|m| m.id == id || m.aliases.iter().any(|a| a == id))
+    }
+}
+
+/// A plain `ggml-*.bin` file name without any path components
+pub fn is_model_file_name(name: &str) -> bool {
+    name.starts_with("ggml-")
+        && name.ends_with(".bin")
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
+
+/// Directory catalog models are downloaded to and loaded from
+pub fn models_dir(settings: &ModelSettings) -> PathBuf {
+    match &settings.directory {
+        Some(directory) => PathBuf::from(directory),
+        None => {
+            let home = std::env::var("HOME")
+                .or_else(|_| std::env::var("USERPROFILE"))
+                .unwrap_or_default();
+            PathBuf::from(home)
+                .join(".voiceintelligence")
+                .join("whisper")
+        }
+    }
+}
+
+/// Check that `path` is a whisper.cpp model file and report whether it is
+/// multilingual, from the vocabulary size in its header
+pub fn inspect_model_file(path: &Path) -> Result<bool, AppError> {
+    let mut header = [0u8; 8];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| AppError::io(format!("Failed to read {}", path.display()), e))?;
+
+    let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    if magic != GGML_MAGIC {
+        return Err(AppError::InvalidInput(format!(
+            "{} is not a whisper.cpp model file",
+            path.display()
+        )));
+    }
+    let n_vocab = i32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    Ok(n_vocab >= MULTILINGUAL_VOCAB_SIZE)
+}
+
+/// The model catalog in use, held in Tauri managed state
+pub struct ModelCatalog {
+    cache_path: PathBuf,
+    manifest: Mutex<ModelManifest>,
+}
+
+impl ModelCatalog {
+    /// Use the refreshed catalog at `cache_path` if there is a valid one, and
+    /// the bundled catalog otherwise
+    pub fn load(cache_path: PathBuf) -> Self {
+        let cached = match std::fs::read_to_string(&cache_path) {
+            Ok(json) => ModelManifest::parse(&json)
+                .map_err(|e| log::warn!("Ignoring model catalog {}: {}", cache_path.display(), e))
+                .ok(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::warn!(
+                    "Failed to read model catalog {}: {}",
+                    cache_path.display(),
+                    e
+                );
+                None
+            }
+        };
+
+        let manifest = cached.unwrap_or_else(|| {
+            ModelManifest::parse(BUNDLED_MANIFEST).expect("bundled model manifest is valid")
+        });
+        log::info!("Model catalog lists {} models", manifest.models.len());
+
+        Self {
+            cache_path,
+            manifest: Mutex::new(manifest),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ModelManifest> {
+        self.manifest.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn manifest(&self) -> ModelManifest {
+        self.lock().clone()
+    }
+
+    /// Look a model up by id or former id
+    pub fn find(&self, id: &str) -> Option<ModelEntry> {
+        self.lock().find(id).cloned()
+    }
+
+    /// Replace the catalog with the manifest at `source`, an http(s) URL or a
+    /// local file path. The new catalog is kept for later runs.
+    pub async fn refresh(
+        &self,
+        client: &reqwest::Client,
+        source: &str,
+    ) -> Result<ModelManifest, AppError> {
+        let json = if source.starts_with("https://") || source.starts_with("http://") {
+            let response = client
+                .get(source)
+                .send()
+                .await
+                .map_err(|e| AppError::network(source, e))?;
+            if !response.status().is_success() {
+                return Err(AppError::HttpStatus {
+                    url: source.to_string(),
+                    status: response.status().as_u16(),
+                    body: String::new(),
+                });
+            }
+            response
+                .text()
+                .await
+                .map_err(|e| AppError::network(source, e))?
+        } else {
+            let path = source.strip_prefix("file://").unwrap_or(source);
+            std::fs::read_to_string(path)
+                .map_err(|e| AppError::io(format!("Failed to read {}", path), e))?
+        };
+
+        let manifest = ModelManifest::parse(&json).map_err(AppError::InvalidInput)?;
+        write_manifest_file(&self.cache_path, &manifest)
+            .map_err(|e| AppError::io("Failed to save model catalog", e))?;
+        log::info!(
+            "Refreshed model catalog from {} ({} models)",
+            source,
+            manifest.models.len()
+        );
+
+        *self.lock() = manifest.clone();
+        Ok(manifest)
+    }
+}
+
+/// Write the manifest atomically, like the settings file
+fn write_manifest_file(path: &Path, manifest: &ModelManifest) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(manifest)?;
+    let temp_path = path.with_extension("json.tmp");
+    let mut temp_file = std::fs::File::create(&temp_path)?;
+    temp_file.write_all(json.as_bytes())?;
+    temp_file.sync_all()?;
+    drop(temp_file);
+    std::fs::rename(&temp_path, path)
+}
+
+/// Human-readable file size, e.g. `142 MB` or `1.5 GB`
+pub fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1} GB", bytes / GB)
+    } else {
+        format!("{:.0} MB", bytes / MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, file: &str, multilingual: bool) -> ModelEntry {
+        ModelEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            file: file.to_string(),
+            url: format!("https://example.com/{}", file),
+            size_bytes: 1,
+            sha256: "ab".repeat(32),
+            multilingual,
+            recommended_ram_mb: 1024,
+            quantization: None,
+            multilingual_variant: None,
+            aliases: Vec::new(),
+        }
+    }
+
+    fn manifest(models: Vec<ModelEntry>) -> ModelManifest {
+        ModelManifest {
+            version: MANIFEST_VERSION,
+            models,
+        }
+    }
+
+    fn english_and_multilingual() -> Vec<ModelEntry> {
+        vec![
+            ModelEntry {
+                multilingual_variant: Some("base-multi".to_string()),
+                aliases: vec!["base.en".to_string()],
+                ..entry("base", "ggml-base.en.bin", false)
+            },
+            entry("base-multi", "ggml-base.bin", true),
+        ]
+    }
+
+    #[test]
+    fn bundled_manifest_is_valid() {
+        let manifest = ModelManifest::parse(BUNDLED_MANIFEST).unwrap();
+
+        assert!(!manifest.models.is_empty());
+        assert_eq!(manifest.find("base.en").unwrap().id, "base");
+        for model in manifest.models.iter().filter(|m| !m.multilingual) {
+            assert!(
+                model.multilingual_variant.is_some(),
+                "{} has no multilingual variant",
+                model.id
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_a_valid_manifest() {
+        assert!(manifest(english_and_multilingual()).validate().is_ok());
+    }
+
+    #[test]
+    fn requires_a_sha256() {
+        let json = serde_json::to_value(manifest(english_and_multilingual())).unwrap();
+        let mut missing = json.clone();
+        missing["models"][1]["sha256"] = serde_json::Value::Null;
+        let mut malformed = json;
+        malformed["models"][1]["sha256"] = "abc".into();
+
+        assert!(ModelManifest::parse(&missing.to_string()).is_err());
+        assert!(ModelManifest::parse(&malformed.to_string()).is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_ids_and_aliases() {
+        let mut models = english_and_multilingual();
+        models.push(entry("base", "ggml-other.bin", true));
+        assert!(manifest(models).validate().is_err());
+
+        let mut models = english_and_multilingual();
+        models[1].aliases.push("base.en".to_string());
+        assert!(manifest(models).validate().is_err());
+
+        let mut models = english_and_multilingual();
+        models[1].aliases.push("base".to_string());
+        assert!(manifest(models).validate().is_err());
+    }
+
+    #[test]
+    fn rejects_bad_file_names() {
+        for file in [
+            "ggml-base.en.bin.txt",
+            "base.bin",
+            "../ggml-base.bin",
+            "models/ggml-base.bin",
+            "ggml-base.en.bin",
+        ] {
+            let mut models = english_and_multilingual();
+            models[1].file = file.to_string();
+            assert!(manifest(models).validate().is_err(), "{}", file);
+        }
+    }
+
+    #[test]
+    fn rejects_a_missing_multilingual_variant() {
+        let mut models = english_and_multilingual();
+        models[0].multilingual_variant = Some("large".to_string());
+        assert!(manifest(models).validate().is_err());
+
+        // The variant has to be multilingual itself
+        let mut models = english_and_multilingual();
+        models[1].multilingual = false;
+        assert!(manifest(models).validate().is_err());
+    }
+
+    #[test]
+    fn model_file_names_have_no_path_parts() {
+        assert!(is_model_file_name("ggml-base.en.bin"));
+        assert!(is_model_file_name("ggml-large-v3-turbo-q5_0.bin"));
+
+        for name in [
+            "base.bin",
+            "ggml-base.gguf",
+            "ggml-../base.bin",
+            "ggml-..bin",
+            "ggml-sub/base.bin",
+            "ggml-sub\\base.bin",
+            "../ggml-base.bin",
+        ] {
+            assert!(!is_model_file_name(name), "{}", name);
+        }
+    }
+
+    /// A file starting with a whisper.cpp model header
+    fn model_file(magic: u32, n_vocab: i32) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ggml-test-{}.bin", uuid::Uuid::new_v4()));
+        let mut header = magic.to_le_bytes().to_vec();
+        header.extend_from_slice(&n_vocab.to_le_bytes());
+        header.extend_from_slice(&[0; 16]);
+        std::fs::write(&path, header).unwrap();
+        path
+    }
+
+    #[test]
+    fn inspects_model_files() {
+        let english = model_file(GGML_MAGIC, MULTILINGUAL_VOCAB_SIZE - 1);
+        let multilingual = model_file(GGML_MAGIC, MULTILINGUAL_VOCAB_SIZE);
+        // large-v3 added a language token
+        let large_v3 = model_file(GGML_MAGIC, MULTILINGUAL_VOCAB_SIZE + 1);
+
+        assert!(!inspect_model_file(&english).unwrap());
+        assert!(inspect_model_file(&multilingual).unwrap());
+        assert!(inspect_model_file(&large_v3).unwrap());
+
+        for path in [english, multilingual, large_v3] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_models() {
+        let gguf = model_file(u32::from_le_bytes(*b"GGUF"), MULTILINGUAL_VOCAB_SIZE);
+        let short = std::env::temp_dir().join(format!("ggml-test-{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&short, GGML_MAGIC.to_le_bytes()).unwrap();
+
+        assert!(matches!(
+            inspect_model_file(&gguf),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(inspect_model_file(&short).is_err());
+        assert!(inspect_model_file(Path::new("/nonexistent/ggml-base.bin")).is_err());
+
+        let _ = std::fs::remove_file(gguf);
+        let _ = std::fs::remove_file(short);
+    }
+
+    #[test]
+    fn rejects_other_versions_and_empty_manifests() {
+        let mut other_version = manifest(english_and_multilingual());
+        other_version.version = MANIFEST_VERSION + 1;
+
+        assert!(other_version.validate().is_err());
+        assert!(manifest(Vec::new()).validate().is_err());
+    }
+}
@@ -0,0 +1,610 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::commands::{transcribe_registered, TranscriptionResult};
+use crate::error::AppError;
+use crate::settings::{SettingsStore, WhisperOptions};
+use crate::storage;
+use crate::whisper::TranscriptionJobs;
+
+/// File in the app data directory holding the queue
+pub const QUEUE_FILE_NAME: &str = "transcription-queue.json";
+
+/// A job interrupted this many times (the app crashed or was killed while it
+/// ran) is failed instead of being started again
+const MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// What to transcribe and what the UI should do with the transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRequest {
+    pub audio_path: String,
+    pub language: String,
+    pub model: String,
+    #[serde(default)]
+    pub whisper_path: Option<String>,
+    /// Overrides the whisper options in settings for this job
+    #[serde(default)]
+    pub whisper_options: Option<WhisperOptions>,
+    /// Applied by the UI once the transcript is ready
+    pub enrichment_mode: String,
+    /// Applied by the UI once the transcript is ready
+    pub output_target: String,
+    /// Remove the audio file after a successful transcription, for
+    /// recordings the app made itself
+    #[serde(default)]
+    pub delete_audio: bool,
+}
+
+/// A queued transcription. Also the payload of the `transcription-job-changed`
+/// event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionJob {
+    pub id: String,
+    #[serde(flatten)]
+    pub request: JobRequest,
+    pub state: JobState,
+    /// Unix timestamps in milliseconds
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Number of times the job was started, including runs cut short by a crash
+    pub attempts: u32,
+    pub result: Option<TranscriptionResult>,
+    /// The `AppError` of the last failure, as sent to the UI
+    pub error: Option<serde_json::Value>,
+    /// Set when a running job is cancelled, for the worker to find if the
+    /// job had not registered with `TranscriptionJobs` yet
+    #[serde(skip)]
+    pub cancel_requested: bool,
+}
+
+/// Transcription jobs persisted to disk and worked through in order. Held in
+/// Tauri managed state; `run` does the work.
+pub struct JobQueue {
+    path: PathBuf,
+    jobs: Mutex<Vec<TranscriptionJob>>,
+    wake: Notify,
+}
+
+impl JobQueue {
+    /// Load the queue from `path`. Jobs that were running when the app last
+    /// stopped are queued again.
+    pub fn load(path: PathBuf) -> Self {
+        let mut jobs: Vec<TranscriptionJob> = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Unreadable transcription queue {}: {}", path.display(), e);
+                // Keep the jobs it held for inspection instead of saving the
+                // empty queue over them
+                match storage::move_aside(&path) {
+                    Ok(corrupt_path) => log::warn!(
+                        "Moved unreadable transcription queue to {}",
+                        corrupt_path.display()
+                    ),
+                    Err(e) => log::warn!("Failed to move {} aside: {}", path.display(), e),
+                }
+                Vec::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                log::warn!(
+                    "Failed to read transcription queue {}: {}",
+                    path.display(),
+                    e
+                );
+                Vec::new()
+            }
+        };
+
+        let mut recovered = 0;
+        for job in jobs.iter_mut().filter(|j| j.state == JobState::Running) {
+            if job.attempts >= MAX_ATTEMPTS {
+                job.state = JobState::Failed;
+                job.error = serde_json::to_value(AppError::Internal(format!(
+                    "Transcription was interrupted {} times and has been given up",
+                    job.attempts
+                )))
+                .ok();
+            } else {
+                job.state = JobState::Queued;
+                recovered += 1;
+            }
+        }
+        if recovered > 0 {
+            log::info!("Resuming {} interrupted transcription job(s)", recovered);
+            write_queue_file(&path, &jobs);
+        }
+
+        Self {
+            path,
+            jobs: Mutex::new(jobs),
+            wake: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<TranscriptionJob>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// All jobs, oldest first
+    pub fn list(&self) -> Vec<TranscriptionJob> {
+        self.lock().clone()
+    }
+
+    /// Let the worker re-check the queue, e.g. after the parallelism changed
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    pub fn enqueue(
+        &self,
+        app: &AppHandle,
+        request: JobRequest,
+    ) -> Result<TranscriptionJob, AppError> {
+        if !Path::new(&request.audio_path).is_file() {
+            return Err(AppError::AudioNotFound {
+                path: request.audio_path,
+            });
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let job = TranscriptionJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            state: JobState::Queued,
+            created_at: now,
+            updated_at: now,
+            attempts: 0,
+            result: None,
+            error: None,
+            cancel_requested: false,
+        };
+        log::info!(
+            "Queued transcription {} for {}",
+            job.id,
+            job.request.audio_path
+        );
+
+        {
+            let mut jobs = self.lock();
+            jobs.push(job.clone());
+            write_queue_file(&self.path, &jobs);
+        }
+        let _ = app.emit("transcription-job-changed", job.clone());
+        self.wake();
+        Ok(job)
+    }
+
+    /// Cancel a queued job, or stop a running one
+    pub fn cancel(&self, app: &AppHandle, id: &str) -> Result<(), AppError> {
+        match self.request_cancel(id)? {
+            JobState::Queued => {
+                self.update(app, id, |job| job.state = JobState::Cancelled);
+                Ok(())
+            }
+            // The worker records the cancellation once whisper has stopped
+            JobState::Running => {
+                app.state::<TranscriptionJobs>().cancel(id);
+                Ok(())
+            }
+            _ => Err(AppError::InvalidInput(format!(
+                "Transcription job '{}' has already finished",
+                id
+            ))),
+        }
+    }
+
+    /// Flag the job as cancelled if it is running and return its state
+    fn request_cancel(&self, id: &str) -> Result<JobState, AppError> {
+        let mut jobs = self.lock();
+        let job = jobs
+            .iter_mut()
+            .find(|j| j.id == id)
+            .ok_or_else(|| unknown_job(id))?;
+        if job.state == JobState::Running {
+            job.cancel_requested = true;
+        }
+        Ok(job.state)
+    }
+
+    /// Whether `cancel` was called for the job while it was running
+    fn cancel_requested(&self, id: &str) -> bool {
+        self.lock().iter().any(|j| j.id == id && j.cancel_requested)
+    }
+
+    /// Drop a finished job, typically once the UI has handled its result
+    pub fn remove(&self, id: &str) -> Result<(), AppError> {
+        let mut jobs = self.lock();
+        let index = jobs
+            .iter()
+            .position(|j| j.id == id)
+            .ok_or_else(|| unknown_job(id))?;
+        if matches!(jobs[index].state, JobState::Queued | JobState::Running) {
+            return Err(AppError::InvalidInput(format!(
+                "Transcription job '{}' has not finished; cancel it first",
+                id
+            )));
+        }
+        jobs.remove(index);
+        write_queue_file(&self.path, &jobs);
+        Ok(())
+    }
+
+    /// Apply `change` to a job, persist the queue and announce the new state
+    fn update(&self, app: &AppHandle, id: &str, change: impl FnOnce(&mut TranscriptionJob)) {
+        let updated = {
+            let mut jobs = self.lock();
+            let Some(job) = jobs.iter_mut().find(|j| j.id == id) else {
+                return;
+            };
+            change(job);
+            job.updated_at = chrono::Utc::now().timestamp_millis();
+            let updated = job.clone();
+            write_queue_file(&self.path, &jobs);
+            updated
+        };
+        let _ = app.emit("transcription-job-changed", updated);
+    }
+
+    /// Mark the oldest queued job as running, if fewer than `parallel` jobs are,
+    /// and announce it
+    fn start_next(&self, app: &AppHandle, parallel: usize) -> Option<TranscriptionJob> {
+        let job = self.claim_next(parallel)?;
+        let _ = app.emit("transcription-job-changed", job.clone());
+        Some(job)
+    }
+
+    /// The part of `start_next` that picks and persists the job
+    fn claim_next(&self, parallel: usize) -> Option<TranscriptionJob> {
+        let mut jobs = self.lock();
+        if jobs.iter().filter(|j| j.state == JobState::Running).count() >= parallel {
+            return None;
+        }
+        let job = jobs.iter_mut().find(|j| j.state == JobState::Queued)?;
+        job.state = JobState::Running;
+        job.attempts += 1;
+        job.error = None;
+        job.updated_at = chrono::Utc::now().timestamp_millis();
+        let started = job.clone();
+        write_queue_file(&self.path, &jobs);
+        Some(started)
+    }
+}
+
+fn unknown_job(id: &str) -> AppError {
+    AppError::InvalidInput(format!("No transcription job with id '{}'", id))
+}
+
+/// Work through the queue for the lifetime of the app, starting up to
+/// `queue.parallelJobs` jobs at a time
+pub async fn run(app: AppHandle) {
+    let queue = app.state::<JobQueue>();
+    loop {
+        let parallel = app.state::<SettingsStore>().get().queue.parallel_jobs as usize;
+        while let Some(job) = queue.start_next(&app, parallel) {
+            tauri::async_runtime::spawn(process(app.clone(), job));
+        }
+        queue.wake.notified().await;
+    }
+}
+
+async fn process(app: AppHandle, job: TranscriptionJob) {
+    let request = job.request.clone();
+    log::info!(
+        "Starting transcription job {} (attempt {})",
+        job.id,
+        job.attempts
+    );
+
+    let queue = app.state::<JobQueue>();
+    let result = transcribe(&app, &queue, &job).await;
+    match result {
+        Ok(transcription) => {
+            if request.delete_audio {
+                if let Err(e) = std::fs::remove_file(&request.audio_path) {
+                    log::warn!("Failed to remove {}: {}", request.audio_path, e);
+                }
+            }
+            queue.update(&app, &job.id, |job| {
+                job.state = JobState::Completed;
+                job.result = Some(transcription);
+            });
+        }
+        Err(AppError::Cancelled) => {
+            queue.update(&app, &job.id, |job| job.state = JobState::Cancelled);
+        }
+        Err(e) => {
+            log::warn!("Transcription job {} failed: {}", job.id, e);
+            queue.update(&app, &job.id, |job| {
+                job.state = JobState::Failed;
+                job.error = serde_json::to_value(&e).ok();
+            });
+        }
+    }
+    queue.wake();
+}
+
+async fn transcribe(
+    app: &AppHandle,
+    queue: &JobQueue,
+    job: &TranscriptionJob,
+) -> Result<TranscriptionResult, AppError> {
+    // The job is marked running before it registers with
+    // `TranscriptionJobs`, and a cancel in between can't signal it there.
+    // Checking the flag after registering closes that gap.
+    if queue.cancel_requested(&job.id) {
+        return Err(AppError::Cancelled);
+    }
+    let jobs = app.state::<TranscriptionJobs>();
+    let mut handle = jobs.register(&job.id)?;
+    if queue.cancel_requested(&job.id) {
+        return Err(AppError::Cancelled);
+    }
+
+    let request = job.request.clone();
+    transcribe_registered(
+        app.clone(),
+        &mut handle,
+        request.audio_path,
+        request.language,
+        request.model,
+        request.whisper_path,
+        request.whisper_options,
+    )
+    .await
+}
+
+/// Write the queue atomically, like the settings file. A failed write is
+/// logged; the in-memory queue keeps working.
+fn write_queue_file(path: &Path, jobs: &[TranscriptionJob]) {
+    let write = || -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(jobs)?;
+        let temp_path = path.with_extension("json.tmp");
+        let mut temp_file = std::fs::File::create(&temp_path)?;
+        temp_file.write_all(json.as_bytes())?;
+        temp_file.sync_all()?;
+        drop(temp_file);
+        std::fs::rename(&temp_path, path)
+    };
+    if let Err(e) = write() {
+        log::warn!(
+            "Failed to save transcription queue to {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_queue_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("queue-test-{}", uuid::Uuid::new_v4()))
+            .join(QUEUE_FILE_NAME)
+    }
+
+    fn job(id: &str, state: JobState, attempts: u32) -> TranscriptionJob {
+        TranscriptionJob {
+            id: id.to_string(),
+            request: JobRequest {
+                audio_path: format!("/tmp/{}.wav", id),
+                language: "en".to_string(),
+                model: "base".to_string(),
+                whisper_path: None,
+                whisper_options: None,
+                enrichment_mode: "clean-transcript".to_string(),
+                output_target: "clipboard".to_string(),
+                delete_audio: false,
+            },
+            state,
+            created_at: 0,
+            updated_at: 0,
+            attempts,
+            result: None,
+            error: None,
+            cancel_requested: false,
+        }
+    }
+
+    fn queue_with(jobs: &[(&str, JobState)]) -> JobQueue {
+        let queue = JobQueue::load(temp_queue_path());
+        queue
+            .lock()
+            .extend(jobs.iter().map(|(id, state)| job(id, *state, 1)));
+        queue
+    }
+
+    fn states(queue: &JobQueue) -> Vec<(String, JobState)> {
+        queue.list().into_iter().map(|j| (j.id, j.state)).collect()
+    }
+
+    #[test]
+    fn cancelling_a_running_job_flags_it() {
+        let queue = queue_with(&[("a", JobState::Running), ("b", JobState::Running)]);
+
+        assert_eq!(queue.request_cancel("a").unwrap(), JobState::Running);
+
+        assert!(queue.cancel_requested("a"));
+        assert!(!queue.cancel_requested("b"));
+    }
+
+    #[test]
+    fn cancelling_other_jobs_does_not_flag_them() {
+        let queue = queue_with(&[("queued", JobState::Queued), ("done", JobState::Completed)]);
+
+        assert_eq!(queue.request_cancel("queued").unwrap(), JobState::Queued);
+        assert_eq!(queue.request_cancel("done").unwrap(), JobState::Completed);
+
+        assert!(!queue.cancel_requested("queued"));
+        assert!(!queue.cancel_requested("done"));
+        assert!(queue.request_cancel("missing").is_err());
+    }
+
+    #[test]
+    fn cancel_before_the_job_registers_is_not_lost() {
+        let queue = queue_with(&[("a", JobState::Running)]);
+        let jobs = TranscriptionJobs::default();
+
+        // What `cancel` does while the worker has not registered the job yet
+        assert_eq!(queue.request_cancel("a").unwrap(), JobState::Running);
+        assert!(!jobs.cancel("a"));
+
+        // The worker registers afterwards and finds the flag
+        let _handle = jobs.register("a").unwrap();
+        assert!(queue.cancel_requested("a"));
+    }
+
+    #[test]
+    fn cancel_requests_are_not_saved() {
+        let queue = queue_with(&[("a", JobState::Running)]);
+        queue.request_cancel("a").unwrap();
+
+        let json = serde_json::to_string(&queue.list()).unwrap();
+
+        assert!(!json.contains("cancelRequested"));
+    }
+
+    #[test]
+    fn load_requeues_interrupted_jobs() {
+        let path = temp_queue_path();
+        write_queue_file(
+            &path,
+            &[
+                job("done", JobState::Completed, 1),
+                job("interrupted", JobState::Running, 1),
+                job("waiting", JobState::Queued, 0),
+            ],
+        );
+
+        let queue = JobQueue::load(path.clone());
+
+        let expected = [
+            ("done".to_string(), JobState::Completed),
+            ("interrupted".to_string(), JobState::Queued),
+            ("waiting".to_string(), JobState::Queued),
+        ];
+        assert_eq!(states(&queue), expected);
+        // The recovered states are saved right away
+        assert_eq!(states(&JobQueue::load(path)), expected);
+    }
+
+    #[test]
+    fn load_gives_up_after_max_attempts() {
+        let path = temp_queue_path();
+        write_queue_file(
+            &path,
+            &[
+                job("retry", JobState::Running, MAX_ATTEMPTS - 1),
+                job("give-up", JobState::Running, MAX_ATTEMPTS),
+            ],
+        );
+
+        let jobs = JobQueue::load(path).list();
+
+        assert_eq!(jobs[0].state, JobState::Queued);
+        assert_eq!(jobs[1].state, JobState::Failed);
+        assert_eq!(jobs[1].error.as_ref().unwrap()["code"], "INTERNAL_ERROR");
+    }
+
+    #[test]
+    fn queue_survives_a_reload() {
+        let path = temp_queue_path();
+        let mut completed = job("done", JobState::Completed, 2);
+        completed.created_at = 1_700_000_000_000;
+        completed.request.delete_audio = true;
+        completed.error = Some(serde_json::json!({ "code": "AUDIO_ERROR" }));
+        write_queue_file(
+            &path,
+            &[completed.clone(), job("next", JobState::Queued, 0)],
+        );
+
+        let jobs = JobQueue::load(path).list();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&jobs[0]).unwrap(),
+            serde_json::to_value(&completed).unwrap()
+        );
+        assert_eq!(jobs[1].id, "next");
+    }
+
+    #[test]
+    fn unreadable_queue_file_is_kept() {
+        let path = temp_queue_path();
+        let dir = path.parent().unwrap().to_path_buf();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "[{ not json").unwrap();
+
+        let queue = JobQueue::load(path.clone());
+        assert!(queue.list().is_empty());
+        queue.lock().push(job("new", JobState::Queued, 0));
+        queue.claim_next(1).unwrap();
+
+        let corrupt: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p != &path)
+            .collect();
+        assert_eq!(corrupt.len(), 1);
+        assert!(corrupt[0]
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("transcription-queue.json.corrupt-"));
+        assert_eq!(std::fs::read_to_string(&corrupt[0]).unwrap(), "[{ not json");
+    }
+
+    #[test]
+    fn starts_queued_jobs_in_order_up_to_parallel() {
+        let queue = queue_with(&[
+            ("done", JobState::Completed),
+            ("a", JobState::Queued),
+            ("b", JobState::Queued),
+            ("c", JobState::Queued),
+        ]);
+
+        let started = queue.claim_next(2).unwrap();
+        assert_eq!(started.id, "a");
+        assert_eq!(started.state, JobState::Running);
+        assert_eq!(started.attempts, 2);
+        assert_eq!(queue.claim_next(2).unwrap().id, "b");
+        // Two are running now
+        assert!(queue.claim_next(2).is_none());
+
+        queue.lock()[1].state = JobState::Completed;
+        assert_eq!(queue.claim_next(2).unwrap().id, "c");
+        assert!(queue.claim_next(2).is_none());
+    }
+
+    #[test]
+    fn started_jobs_are_saved() {
+        let queue = queue_with(&[("a", JobState::Queued)]);
+        queue.lock()[0].error = Some(serde_json::json!({ "code": "AUDIO_ERROR" }));
+
+        queue.claim_next(1).unwrap();
+
+        let reloaded: Vec<TranscriptionJob> =
+            serde_json::from_str(&std::fs::read_to_string(&queue.path).unwrap()).unwrap();
+        assert_eq!(reloaded[0].state, JobState::Running);
+        assert_eq!(reloaded[0].error, None);
+    }
+}
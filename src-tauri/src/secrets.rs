@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::AppError;
+
+mod vault;
+
+pub use vault::{Vault, VaultStatus, VAULT_FILE_NAME};
+
+/// Service name under which secrets are stored in the OS keyring
+const KEYRING_SERVICE: &str = "com.voiceintelligence.app";
+
+/// API keys the backend knows how to store
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SecretName {
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "openrouter")]
+    OpenRouter,
+    #[serde(rename = "openaiWhisper")]
+    OpenAiWhisper,
+    #[serde(rename = "notion")]
+    Notion,
+}
+
+impl SecretName {
+    pub const ALL: [SecretName; 4] = [
+        SecretName::OpenAi,
+        SecretName::OpenRouter,
+        SecretName::OpenAiWhisper,
+        SecretName::Notion,
+    ];
+
+    /// Account name used for the keyring entry
+    pub fn key(self) -> &'static str {
+        match self {
+            SecretName::OpenAi => "openai",
+            SecretName::OpenRouter => "openrouter",
+            SecretName::OpenAiWhisper => "openaiWhisper",
+            SecretName::Notion => "notion",
+        }
+    }
+
+    /// Field the frontend kept this key in, in plain text, before keys moved
+    /// into the secret store
+    pub fn legacy_field(self) -> &'static str {
+        match self {
+            SecretName::OpenAi => "openaiApiKey",
+            SecretName::OpenRouter => "openrouterApiKey",
+            SecretName::OpenAiWhisper => "openaiWhisperApiKey",
+            SecretName::Notion => "notionApiKey",
+        }
+    }
+}
+
+/// Whether a secret is configured. Never carries the secret itself.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStatus {
+    pub name: SecretName,
+    pub configured: bool,
+    pub backend: String,
+    /// The vault is locked, so whether the secret is configured is unknown
+    pub locked: bool,
+}
+
+/// Storage for secrets
+pub trait SecretBackend: Send + Sync {
+    /// Short identifier shown to the user, e.g. "keyring"
+    fn name(&self) -> &'static str;
+    fn set(&self, name: SecretName, value: &str) -> Result<(), AppError>;
+    fn get(&self, name: SecretName) -> Result<Option<String>, AppError>;
+    fn delete(&self, name: SecretName) -> Result<(), AppError>;
+
+    /// Whether the secret is stored. Unlike `get`, this doesn't count as
+    /// using the secret, so polling it keeps nothing unlocked.
+    fn contains(&self, name: SecretName) -> Result<bool, AppError> {
+        Ok(self.get(name)?.is_some())
+    }
+}
+
+/// Secrets in the platform credential store (Keychain, Credential Manager,
+/// Secret Service)
+pub struct KeyringBackend;
+
+impl KeyringBackend {
+    /// Whether the platform credential store can be reached. Headless Linux
+    /// systems often have no Secret Service daemon running.
+    pub fn is_available() -> bool {
+        let probe = match keyring::Entry::new(KEYRING_SERVICE, "availability-probe") {
+            Ok(entry) => entry,
+            Err(_) => return false,
+        };
+        match probe.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => true,
+            Err(e) => {
+                log::warn!("OS keyring unavailable: {}", e);
+                false
+            }
+        }
+    }
+
+    fn entry(name: SecretName) -> Result<keyring::Entry, AppError> {
+        keyring::Entry::new(KEYRING_SERVICE, name.key())
+            .map_err(|e| AppError::SecretStore(format!("Failed to open keyring entry '{}': {}", name.key(), e)))
+    }
+}
+
+impl SecretBackend for KeyringBackend {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), AppError> {
+        Self::entry(name)?
+            .set_password(value)
+            .map_err(|e| AppError::SecretStore(format!("Failed to store '{}' in keyring: {}", name.key(), e)))
+    }
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, AppError> {
+        match Self::entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(AppError::SecretStore(format!(
+                "Failed to read '{}' from keyring: {}",
+                name.key(),
+                e
+            ))),
+        }
+    }
+
+    fn delete(&self, name: SecretName) -> Result<(), AppError> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(AppError::SecretStore(format!(
+                "Failed to delete '{}' from keyring: {}",
+                name.key(),
+                e
+            ))),
+        }
+    }
+}
+
+/// Secret store held in Tauri managed state. Backend commands read keys from
+/// here so the webview only ever handles a key while the user enters it.
+pub struct SecretStore {
+    backend: Arc<dyn SecretBackend>,
+    vault: Option<Arc<Vault>>,
+}
+
+impl SecretStore {
+    /// Store secrets in the OS keyring
+    pub fn keyring() -> Self {
+        Self {
+            backend: Arc::new(KeyringBackend),
+            vault: None,
+        }
+    }
+
+    /// Store secrets in a passphrase-encrypted vault file
+    pub fn vault(vault: Vault) -> Self {
+        let vault = Arc::new(vault);
+        Self {
+            backend: vault.clone(),
+            vault: Some(vault),
+        }
+    }
+
+    /// The vault, if secrets are stored in one rather than the OS keyring
+    pub fn vault_backend(&self) -> Option<&Vault> {
+        self.vault.as_deref()
+    }
+
+    pub fn set(&self, name: SecretName, value: &str) -> Result<(), AppError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "Secret '{}' must not be empty",
+                name.key()
+            )));
+        }
+        self.backend.set(name, value)?;
+        log::info!("Stored secret '{}' in {}", name.key(), self.backend.name());
+        Ok(())
+    }
+
+    pub fn get(&self, name: SecretName) -> Result<Option<String>, AppError> {
+        self.backend.get(name)
+    }
+
+    /// Check that `names` could be stored right now, without storing
+    /// anything: the vault is unlocked, or the keyring can be reached
+    pub fn check_writable(&self, names: &[SecretName]) -> Result<(), AppError> {
+        for name in names {
+            self.backend.contains(*name)?;
+        }
+        Ok(())
+    }
+
+    /// Look up a secret that a command needs, with a readable error if missing
+    pub fn require(&self, name: SecretName) -> Result<String, AppError> {
+        self.get(name)?.ok_or_else(|| AppError::SecretMissing {
+            name: name.key().to_string(),
+        })
+    }
+
+    pub fn delete(&self, name: SecretName) -> Result<(), AppError> {
+        self.backend.delete(name)?;
+        log::info!("Deleted secret '{}' from {}", name.key(), self.backend.name());
+        Ok(())
+    }
+
+    pub fn status(&self, name: SecretName) -> Result<SecretStatus, AppError> {
+        let (configured, locked) = match self.backend.contains(name) {
+            Ok(configured) => (configured, false),
+            Err(AppError::VaultLocked) => (false, true),
+            Err(e) => return Err(e),
+        };
+        Ok(SecretStatus {
+            name,
+            configured,
+            backend: self.backend.name().to_string(),
+            locked,
+        })
+    }
+}
+
+/// Write a file holding secrets atomically and readable by the current user
+/// only: a sibling temp file is created with mode 0600, synced, then renamed
+/// over `path`
+pub fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files; a stale temp file keeps its own
+        if temp_path.exists() {
+            std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    let mut temp_file = options.open(&temp_path)?;
+    temp_file.write_all(contents)?;
+    temp_file.sync_all()?;
+    drop(temp_file);
+
+    std::fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_files_replace_the_old_content() {
+        let dir = std::env::temp_dir().join(format!("secrets-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("export.json");
+        std::fs::write(&path, "old content that is longer").unwrap();
+
+        write_private_file(&path, b"new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["export.json"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("secrets-test-{}.json", uuid::Uuid::new_v4()));
+        write_private_file(&path, b"{}").unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
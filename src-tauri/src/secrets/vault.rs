@@ -0,0 +1,478 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+use super::{write_private_file, SecretBackend, SecretName};
+use crate::error::AppError;
+
+/// File name of the encrypted vault inside the app data dir
+pub const VAULT_FILE_NAME: &str = "secrets.vault";
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+/// Binds the ciphertext to this file format
+const VAULT_AAD: &[u8] = b"voice-intelligence-vault-v1";
+
+/// Argon2id cost parameters, stored with the vault so they can be raised later
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+/// Accepted ranges for the stored KDF parameters. A crafted or corrupted
+/// file could otherwise make unlocking allocate gigabytes or run for hours.
+const MEMORY_KIB_RANGE: (u32, u32) = (8, 1024 * 1024);
+const ITERATIONS_RANGE: (u32, u32) = (1, 16);
+const PARALLELISM_RANGE: (u32, u32) = (1, 16);
+
+impl KdfParams {
+    fn check(&self) -> Result<(), AppError> {
+        for (param, value, (min, max)) in [
+            ("memoryKib", self.memory_kib, MEMORY_KIB_RANGE),
+            ("iterations", self.iterations, ITERATIONS_RANGE),
+            ("parallelism", self.parallelism, PARALLELISM_RANGE),
+        ] {
+            if !(min..=max).contains(&value) {
+                return Err(AppError::VaultKdfParams {
+                    param: param.to_string(),
+                    value,
+                    min,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+/// On-disk vault document. Everything except the KDF header is encrypted.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Lock state of the vault
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    /// A vault file exists; unlocking will open it rather than create it
+    pub initialized: bool,
+    pub locked: bool,
+}
+
+struct UnlockedVault {
+    key: Zeroizing<[u8; KEY_LEN]>,
+    salt: Vec<u8>,
+    kdf: KdfParams,
+    secrets: BTreeMap<String, Zeroizing<String>>,
+    last_used: Instant,
+}
+
+/// Passphrase-encrypted secret file, used where no OS keyring is available.
+/// The derived key is held in memory only while unlocked.
+pub struct Vault {
+    path: PathBuf,
+    unlocked: Mutex<Option<UnlockedVault>>,
+}
+
+impl Vault {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            unlocked: Mutex::new(None),
+        }
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        VaultStatus {
+            initialized: self.path.exists(),
+            locked: self.lock_state().is_none(),
+        }
+    }
+
+    /// Unlock the vault, creating it with this passphrase if it doesn't exist yet.
+    /// Deriving the key is deliberately slow, so call this off the main thread.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), AppError> {
+        if passphrase.is_empty() {
+            return Err(AppError::InvalidInput("Passphrase must not be empty".to_string()));
+        }
+
+        let unlocked = if self.path.exists() {
+            open_vault(&self.path, passphrase)?
+        } else {
+            let mut salt = vec![0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let kdf = KdfParams::default();
+            let vault = UnlockedVault {
+                key: derive_key(passphrase, &salt, &kdf)?,
+                salt,
+                kdf,
+                secrets: BTreeMap::new(),
+                last_used: Instant::now(),
+            };
+            write_vault(&self.path, &vault)?;
+            log::info!("Created secret vault at {}", self.path.display());
+            vault
+        };
+
+        *self.lock_state() = Some(unlocked);
+        log::info!("Secret vault unlocked");
+        Ok(())
+    }
+
+    /// Forget the key and all decrypted secrets
+    pub fn lock(&self) {
+        if self.lock_state().take().is_some() {
+            log::info!("Secret vault locked");
+        }
+    }
+
+    /// Lock the vault if it has not been used for `timeout`.
+    /// Returns true if it was locked by this call.
+    pub fn lock_if_idle(&self, timeout: Duration) -> bool {
+        let mut state = self.lock_state();
+        let idle = state
+            .as_ref()
+            .is_some_and(|vault| vault.last_used.elapsed() >= timeout);
+        if idle {
+            *state = None;
+            log::info!("Secret vault auto-locked after {:?} idle", timeout);
+        }
+        idle
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, Option<UnlockedVault>> {
+        self.unlocked.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` against the unlocked vault, refreshing its idle timer
+    fn with_unlocked<T>(
+        &self,
+        f: impl FnOnce(&mut UnlockedVault) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut state = self.lock_state();
+        let vault = state
+            .as_mut()
+            .ok_or(AppError::VaultLocked)?;
+        vault.last_used = Instant::now();
+        f(vault)
+    }
+}
+
+impl SecretBackend for Vault {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), AppError> {
+        self.with_unlocked(|vault| {
+            vault
+                .secrets
+                .insert(name.key().to_string(), Zeroizing::new(value.to_string()));
+            write_vault(&self.path, vault)
+        })
+    }
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, AppError> {
+        self.with_unlocked(|vault| {
+            Ok(vault.secrets.get(name.key()).map(|v| v.to_string()))
+        })
+    }
+
+    fn delete(&self, name: SecretName) -> Result<(), AppError> {
+        self.with_unlocked(|vault| {
+            if vault.secrets.remove(name.key()).is_some() {
+                write_vault(&self.path, vault)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Leaves the idle timer alone, so the UI polling for configured keys
+    /// doesn't keep the vault from auto-locking
+    fn contains(&self, name: SecretName) -> Result<bool, AppError> {
+        let state = self.lock_state();
+        let vault = state.as_ref().ok_or(AppError::VaultLocked)?;
+        Ok(vault.secrets.contains_key(name.key()))
+    }
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf: &KdfParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, AppError> {
+    let params = argon2::Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(KEY_LEN))
+        .map_err(|e| AppError::SecretStore(format!("Invalid key derivation parameters: {}", e)))?;
+    let argon = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    argon
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| AppError::SecretStore(format!("Failed to derive vault key: {}", e)))?;
+    Ok(key)
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>, AppError> {
+    BASE64
+        .decode(value)
+        .map_err(|e| AppError::SecretStore(format!("Corrupt vault file ({}): {}", field, e)))
+}
+
+fn open_vault(path: &Path, passphrase: &str) -> Result<UnlockedVault, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::SecretStore(format!("Failed to read vault file: {}", e)))?;
+    let file: VaultFile = serde_json::from_str(&content)
+        .map_err(|e| AppError::SecretStore(format!("Failed to parse vault file: {}", e)))?;
+
+    if file.version != VAULT_VERSION {
+        return Err(AppError::SecretStore(format!(
+            "Unsupported vault version {}",
+            file.version
+        )));
+    }
+
+    let salt = decode("salt", &file.salt)?;
+    let nonce = decode("nonce", &file.nonce)?;
+    let ciphertext = decode("ciphertext", &file.ciphertext)?;
+    if nonce.len() != 24 {
+        return Err(AppError::SecretStore("Corrupt vault file (nonce length)".to_string()));
+    }
+
+    file.kdf.check()?;
+    let key = derive_key(passphrase, &salt, &file.kdf)?;
+    let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: VAULT_AAD,
+                },
+            )
+            .map_err(|_| AppError::IncorrectPassphrase)?,
+    );
+
+    let secrets: BTreeMap<String, String> = serde_json::from_slice(&plaintext)
+        .map_err(|e| AppError::SecretStore(format!("Failed to parse decrypted vault: {}", e)))?;
+
+    Ok(UnlockedVault {
+        key,
+        salt,
+        kdf: file.kdf,
+        secrets: secrets
+            .into_iter()
+            .map(|(k, v)| (k, Zeroizing::new(v)))
+            .collect(),
+        last_used: Instant::now(),
+    })
+}
+
+/// Encrypt the secrets with a fresh nonce and atomically replace the vault file
+fn write_vault(path: &Path, vault: &UnlockedVault) -> Result<(), AppError> {
+    let secrets: BTreeMap<&str, &str> = vault
+        .secrets
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(&secrets).map_err(|e| AppError::SecretStore(format!("Failed to serialize vault: {}", e)))?,
+    );
+
+    let cipher = XChaCha20Poly1305::new(vault.key.as_ref().into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: VAULT_AAD,
+            },
+        )
+        .map_err(|_| AppError::SecretStore("Failed to encrypt vault".to_string()))?;
+
+    let file = VaultFile {
+        version: VAULT_VERSION,
+        kdf: vault.kdf,
+        salt: BASE64.encode(&vault.salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| AppError::SecretStore(format!("Failed to serialize vault: {}", e)))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::SecretStore(format!("Failed to create vault directory: {}", e)))?;
+    }
+    write_private_file(path, json.as_bytes())
+        .map_err(|e| AppError::SecretStore(format!("Failed to write vault: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    /// Vault path in a fresh directory under the system temp dir
+    fn temp_vault_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vault-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(VAULT_FILE_NAME)
+    }
+
+    /// A vault created with `PASSPHRASE` holding an OpenAI key, then locked
+    fn locked_vault() -> Vault {
+        let vault = Vault::new(temp_vault_path());
+        vault.unlock(PASSPHRASE).unwrap();
+        vault.set(SecretName::OpenAi, "sk-test").unwrap();
+        vault.lock();
+        vault
+    }
+
+    fn edit_file(path: &Path, edit: impl FnOnce(&mut VaultFile)) {
+        let mut file: VaultFile =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        edit(&mut file);
+        std::fs::write(path, serde_json::to_string(&file).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn secrets_survive_lock_and_unlock() {
+        let vault = locked_vault();
+        assert!(vault.status().initialized);
+        assert!(vault.status().locked);
+
+        vault.unlock(PASSPHRASE).unwrap();
+        assert!(!vault.status().locked);
+        assert_eq!(vault.get(SecretName::OpenAi).unwrap().as_deref(), Some("sk-test"));
+        assert_eq!(vault.get(SecretName::Notion).unwrap(), None);
+
+        // A second instance reads the same file
+        let reopened = Vault::new(vault.path.clone());
+        reopened.unlock(PASSPHRASE).unwrap();
+        assert_eq!(reopened.get(SecretName::OpenAi).unwrap().as_deref(), Some("sk-test"));
+
+        reopened.delete(SecretName::OpenAi).unwrap();
+        vault.lock();
+        vault.unlock(PASSPHRASE).unwrap();
+        assert_eq!(vault.get(SecretName::OpenAi).unwrap(), None);
+    }
+
+    #[test]
+    fn file_does_not_contain_secrets_in_plain_text() {
+        let vault = locked_vault();
+        let content = std::fs::read_to_string(&vault.path).unwrap();
+
+        assert!(!content.contains("sk-test"));
+        assert!(!content.contains("openai"));
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let vault = locked_vault();
+
+        assert!(matches!(
+            vault.unlock("wrong passphrase"),
+            Err(AppError::IncorrectPassphrase)
+        ));
+        assert!(vault.status().locked);
+        assert!(matches!(vault.unlock(""), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn locked_vault_refuses_access() {
+        let vault = locked_vault();
+
+        assert!(matches!(vault.get(SecretName::OpenAi), Err(AppError::VaultLocked)));
+        assert!(matches!(
+            vault.set(SecretName::OpenAi, "sk-other"),
+            Err(AppError::VaultLocked)
+        ));
+        assert!(matches!(vault.delete(SecretName::OpenAi), Err(AppError::VaultLocked)));
+        assert!(matches!(vault.contains(SecretName::OpenAi), Err(AppError::VaultLocked)));
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails_to_decrypt() {
+        let vault = locked_vault();
+        edit_file(&vault.path, |file| {
+            let mut ciphertext = BASE64.decode(&file.ciphertext).unwrap();
+            ciphertext[0] ^= 1;
+            file.ciphertext = BASE64.encode(ciphertext);
+        });
+
+        assert!(matches!(vault.unlock(PASSPHRASE), Err(AppError::IncorrectPassphrase)));
+        assert!(vault.status().locked);
+    }
+
+    #[test]
+    fn rejects_out_of_range_kdf_params() {
+        let vault = locked_vault();
+        edit_file(&vault.path, |file| file.kdf.memory_kib = 64 * 1024 * 1024);
+
+        assert!(matches!(
+            vault.unlock(PASSPHRASE),
+            Err(AppError::VaultKdfParams { ref param, .. }) if param == "memoryKib"
+        ));
+
+        let too_slow = KdfParams {
+            iterations: ITERATIONS_RANGE.1 + 1,
+            ..KdfParams::default()
+        };
+        assert!(too_slow.check().is_err());
+        let no_threads = KdfParams {
+            parallelism: 0,
+            ..KdfParams::default()
+        };
+        assert!(no_threads.check().is_err());
+        assert!(KdfParams::default().check().is_ok());
+    }
+
+    #[test]
+    fn locks_only_when_idle() {
+        let vault = locked_vault();
+        assert!(!vault.lock_if_idle(Duration::ZERO));
+
+        vault.unlock(PASSPHRASE).unwrap();
+        assert!(!vault.lock_if_idle(Duration::from_secs(3600)));
+        assert!(!vault.status().locked);
+
+        // Checking whether a secret exists doesn't count as use
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(vault.contains(SecretName::OpenAi).unwrap());
+        assert!(vault.lock_if_idle(Duration::from_millis(10)));
+        assert!(vault.status().locked);
+    }
+}
@@ -0,0 +1,1342 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+mod bundle;
+mod migrations;
+
+pub use bundle::{BundledSecret, ImportPreview, SettingsBundle};
+pub use migrations::{MigrationReport, LEGACY_SETTINGS_KEY};
+
+/// File name of the backend settings document inside the app config dir.
+/// Kept distinct from the frontend's plugin-store `settings.json`.
+pub const SETTINGS_FILE_NAME: &str = "app-settings.json";
+
+/// Current schema version of the on-disk settings document. Bump this
+/// together with a new step in `migrations::MIGRATIONS`.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A settings field holding a value the app can't use
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidSetting {
+    /// Path of the offending field as the frontend names it, e.g. `vad.paddingMs`
+    pub field: String,
+    pub message: String,
+}
+
+impl InvalidSetting {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for InvalidSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<InvalidSetting> for String {
+    fn from(err: InvalidSetting) -> Self {
+        err.message
+    }
+}
+
+const LANGUAGES: &[&str] = &["en", "de", "no", "auto"];
+const ENRICHMENT_MODES: &[&str] = &[
+    "meeting-notes",
+    "clean-transcript",
+    "action-items",
+    "summary",
+    "custom",
+];
+const OUTPUT_TARGETS: &[&str] = &["clipboard", "file", "notion", "google_drive"];
+const LLM_PROVIDERS: &[&str] = &["openai", "openrouter", "ollama"];
+
+/// Application settings, mirroring the frontend `Settings` shape.
+/// API keys are intentionally not part of this document.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub hotkey: String,
+    pub language: String,
+    pub enrichment_mode: String,
+    pub output_target: String,
+    pub retention_days: u32,
+    pub llm_provider: String,
+    pub llm_model: Option<String>,
+    pub custom_prompt: Option<String>,
+    pub auto_enrich: bool,
+    pub show_notifications: bool,
+    pub notion_default_page_id: Option<String>,
+    pub notion_default_page_name: Option<String>,
+    pub ollama_model: Option<String>,
+    pub selected_microphone: Option<String>,
+    pub whisper_path: Option<String>,
+    pub whisper_model: String,
+    pub setup_complete: bool,
+    /// Lock the secret vault after this many idle minutes (0 = never)
+    pub vault_auto_lock_minutes: u32,
+    pub network: NetworkSettings,
+    pub vad: VadSettings,
+    pub queue: QueueSettings,
+    pub whisper: WhisperOptions,
+    pub vocabulary: VocabularySettings,
+    pub models: ModelSettings,
+}
+
+/// Settings for the shared HTTP client used for model downloads, OLLAMA and Notion
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkSettings {
+    /// HTTP(S) proxy for outgoing requests, e.g. `http://proxy.corp:8080`
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts that bypass the proxy. Localhost always does.
+    pub no_proxy: Option<String>,
+    /// PEM file with additional trusted root certificates, e.g. a corporate CA
+    pub ca_bundle_path: Option<String>,
+    pub connect_timeout_secs: u64,
+    /// Maximum time to wait for data on an open connection
+    pub read_timeout_secs: u64,
+    /// Overrides the default `VoiceIntelligence/<version>` user agent
+    pub user_agent: Option<String>,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            proxy_url: None,
+            no_proxy: None,
+            ca_bundle_path: None,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 120,
+            user_agent: None,
+        }
+    }
+}
+
+impl NetworkSettings {
+    fn validate(&self) -> Result<(), InvalidSetting> {
+        if let Some(proxy) = self.proxy_url.as_deref().filter(|p| !p.trim().is_empty()) {
+            let url = reqwest::Url::parse(proxy.trim()).map_err(|e| {
+                InvalidSetting::new(
+                    "network.proxyUrl",
+                    format!("Invalid network.proxyUrl '{}': {}", proxy, e),
+                )
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(InvalidSetting::new(
+                    "network.proxyUrl",
+                    format!(
+                        "Invalid network.proxyUrl '{}'. Expected an http or https URL",
+                        proxy
+                    ),
+                ));
+            }
+        }
+        check_range(
+            "network.connectTimeoutSecs",
+            Some(self.connect_timeout_secs),
+            1,
+            300,
+        )?;
+        check_range("network.readTimeoutSecs", Some(self.read_timeout_secs), 1, 3600)?;
+        Ok(())
+    }
+}
+
+/// Voice activity detection applied to recordings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VadSettings {
+    /// Cut leading and trailing silence before running whisper
+    pub trim_silence: bool,
+    /// Audio quieter than this (dBFS) counts as silence
+    pub threshold_db: f32,
+    /// Audio kept around detected speech so word edges are not clipped
+    pub padding_ms: u32,
+    /// Stop recording after this many seconds of silence (0 = never)
+    pub auto_stop_silence_secs: u32,
+}
+
+impl Default for VadSettings {
+    fn default() -> Self {
+        Self {
+            trim_silence: true,
+            threshold_db: -45.0,
+            padding_ms: 300,
+            auto_stop_silence_secs: 0,
+        }
+    }
+}
+
+impl VadSettings {
+    fn validate(&self) -> Result<(), InvalidSetting> {
+        check_range("vad.thresholdDb", Some(self.threshold_db), -90.0, 0.0)?;
+        check_at_most("vad.paddingMs", self.padding_ms, 2000)?;
+        check_at_most("vad.autoStopSilenceSecs", self.auto_stop_silence_secs, 60)?;
+        Ok(())
+    }
+}
+
+/// whisper.cpp decoding parameters. Unset fields keep whisper's own defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WhisperOptions {
+    /// Threads per processor (`-t`)
+    pub threads: Option<u32>,
+    /// Processors the audio is split across (`-p`)
+    pub processors: Option<u32>,
+    /// Beams for beam search decoding (`-bs`)
+    pub beam_size: Option<u32>,
+    /// Candidates sampled when decoding with temperature (`-bo`)
+    pub best_of: Option<u32>,
+    /// Initial sampling temperature (`-tp`)
+    pub temperature: Option<f32>,
+    /// Temperature added on each fallback when decoding fails the thresholds
+    /// (`-tpi`); 0 disables fallback
+    pub temperature_increment: Option<f32>,
+    /// Maximum segment length in characters (`-ml`)
+    pub max_segment_length: Option<u32>,
+    /// Entropy above which decoding falls back (`-et`)
+    pub entropy_threshold: Option<f32>,
+    /// Average log probability below which decoding falls back (`-lpt`)
+    pub logprob_threshold: Option<f32>,
+}
+
+impl WhisperOptions {
+    /// These options, with unset fields taken from `fallback`
+    pub fn or(self, fallback: &WhisperOptions) -> WhisperOptions {
+        WhisperOptions {
+            threads: self.threads.or(fallback.threads),
+            processors: self.processors.or(fallback.processors),
+            beam_size: self.beam_size.or(fallback.beam_size),
+            best_of: self.best_of.or(fallback.best_of),
+            temperature: self.temperature.or(fallback.temperature),
+            temperature_increment: self.temperature_increment.or(fallback.temperature_increment),
+            max_segment_length: self.max_segment_length.or(fallback.max_segment_length),
+            entropy_threshold: self.entropy_threshold.or(fallback.entropy_threshold),
+            logprob_threshold: self.logprob_threshold.or(fallback.logprob_threshold),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidSetting> {
+        check_range("whisper.threads", self.threads, 1, 256)?;
+        check_range("whisper.processors", self.processors, 1, 16)?;
+        check_range("whisper.beamSize", self.beam_size, 1, 16)?;
+        check_range("whisper.bestOf", self.best_of, 1, 16)?;
+        check_range("whisper.temperature", self.temperature, 0.0, 1.0)?;
+        check_range("whisper.temperatureIncrement", self.temperature_increment, 0.0, 1.0)?;
+        check_range("whisper.maxSegmentLength", self.max_segment_length, 0, 1000)?;
+        check_range("whisper.entropyThreshold", self.entropy_threshold, 0.0, 10.0)?;
+        check_range("whisper.logprobThreshold", self.logprob_threshold, -10.0, 0.0)?;
+        Ok(())
+    }
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    field: &str,
+    value: Option<T>,
+    min: T,
+    max: T,
+) -> Result<(), InvalidSetting> {
+    match value {
+        Some(value) if value < min || value > max => Err(InvalidSetting::new(
+            field,
+            format!("{} must be between {} and {}, got {}", field, min, max, value),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_at_most<T: PartialOrd + std::fmt::Display>(
+    field: &str,
+    value: T,
+    max: T,
+) -> Result<(), InvalidSetting> {
+    if value > max {
+        return Err(InvalidSetting::new(
+            field,
+            format!("{} must be at most {}, got {}", field, max, value),
+        ));
+    }
+    Ok(())
+}
+
+/// Most transcription jobs the queue runs at once
+pub const MAX_PARALLEL_JOBS: u32 = 4;
+
+/// Background transcription queue
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueueSettings {
+    /// Jobs transcribed at the same time; the rest wait their turn
+    pub parallel_jobs: u32,
+}
+
+impl Default for QueueSettings {
+    fn default() -> Self {
+        Self { parallel_jobs: 1 }
+    }
+}
+
+impl QueueSettings {
+    fn validate(&self) -> Result<(), InvalidSetting> {
+        check_range("queue.parallelJobs", Some(self.parallel_jobs), 1, MAX_PARALLEL_JOBS)
+    }
+}
+
+/// Where whisper models come from and where they are kept
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelSettings {
+    /// URL or local path of a model manifest that `refresh_model_catalog`
+    /// loads in place of the bundled catalog
+    pub manifest_source: Option<String>,
+    /// Directory catalog models are downloaded to and loaded from. Defaults
+    /// to `~/.voiceintelligence/whisper`.
+    pub directory: Option<String>,
+    /// Model files registered by the user, used where they are
+    pub custom: Vec<CustomModel>,
+}
+
+/// A user-provided `ggml-*.bin` model file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomModel {
+    /// `custom:<file name without .bin>`
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Read from the model's vocabulary size when it was registered
+    pub multilingual: bool,
+}
+
+impl ModelSettings {
+    fn validate(&self) -> Result<(), InvalidSetting> {
+        if let Some(source) = &self.manifest_source {
+            if source.trim().is_empty() {
+                return Err(InvalidSetting::new(
+                    "models.manifestSource",
+                    "models.manifestSource must not be empty",
+                ));
+            }
+        }
+        if let Some(directory) = &self.directory {
+            if !Path::new(directory).is_absolute() {
+                return Err(InvalidSetting::new(
+                    "models.directory",
+                    format!("models.directory must be an absolute path, got '{}'", directory),
+                ));
+            }
+        }
+        for (i, model) in self.custom.iter().enumerate() {
+            let invalid = |message: String| Err(InvalidSetting::new("models.custom", message));
+            if model.name.trim().is_empty() {
+                return invalid(format!("Custom model '{}' needs a name", model.id));
+            }
+            if !Path::new(&model.path).is_absolute() {
+                return invalid(format!("Custom model '{}' must have an absolute path", model.id));
+            }
+            if self.custom[..i].iter().any(|m| m.id == model.id) {
+                return invalid(format!("Custom model '{}' is registered twice", model.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+const MAX_VOCABULARY_ENTRIES: usize = 500;
+const MAX_VOCABULARY_ENTRY_LEN: usize = 100;
+
+/// Domain terms whisper should recognise and fixes for the ones it still gets
+/// wrong. Kept per profile.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VocabularySettings {
+    /// Passed to whisper as its initial prompt, most important first
+    pub terms: Vec<String>,
+    /// Applied to the transcript after whisper has run
+    pub replacements: Vec<Replacement>,
+}
+
+/// A whole word or phrase to replace in transcripts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Replacement {
+    pub from: String,
+    pub to: String,
+}
+
+impl VocabularySettings {
+    fn validate(&self) -> Result<(), InvalidSetting> {
+        for (field, len) in [
+            ("vocabulary.terms", self.terms.len()),
+            ("vocabulary.replacements", self.replacements.len()),
+        ] {
+            if len > MAX_VOCABULARY_ENTRIES {
+                return Err(InvalidSetting::new(
+                    field,
+                    format!("{} can hold at most {} entries", field, MAX_VOCABULARY_ENTRIES),
+                ));
+            }
+        }
+        for term in &self.terms {
+            check_vocabulary_entry("vocabulary.terms", term)?;
+        }
+        for replacement in &self.replacements {
+            check_vocabulary_entry("vocabulary.replacements", &replacement.from)?;
+            if replacement.to.chars().count() > MAX_VOCABULARY_ENTRY_LEN
+                || replacement.to.contains(['\n', '\r'])
+            {
+                return Err(InvalidSetting::new(
+                    "vocabulary.replacements",
+                    format!(
+                        "Replacement for '{}' must be a single line of at most {} characters",
+                        replacement.from, MAX_VOCABULARY_ENTRY_LEN
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Trim entries and drop blank or duplicate ones. Duplicate terms are
+    /// compared ignoring case; for duplicate replacements the last one wins.
+    pub fn normalized(self) -> Self {
+        let mut terms: Vec<String> = Vec::new();
+        for term in self.terms {
+            let term = term.trim();
+            if !term.is_empty() && !terms.iter().any(|t| t.to_lowercase() == term.to_lowercase()) {
+                terms.push(term.to_string());
+            }
+        }
+
+        let mut replacements: Vec<Replacement> = Vec::new();
+        for replacement in self.replacements {
+            let from = replacement.from.trim().to_string();
+            if from.is_empty() {
+                continue;
+            }
+            replacements.retain(|r| r.from != from);
+            replacements.push(Replacement {
+                from,
+                to: replacement.to.trim().to_string(),
+            });
+        }
+
+        Self {
+            terms,
+            replacements,
+        }
+    }
+}
+
+fn check_vocabulary_entry(field: &str, entry: &str) -> Result<(), InvalidSetting> {
+    if entry.trim().is_empty() {
+        return Err(InvalidSetting::new(
+            field,
+            format!("{} must not contain empty entries", field),
+        ));
+    }
+    if entry.chars().count() > MAX_VOCABULARY_ENTRY_LEN || entry.contains(['\n', '\r']) {
+        return Err(InvalidSetting::new(
+            field,
+            format!(
+                "{} entry '{}' must be a single line of at most {} characters",
+                field, entry, MAX_VOCABULARY_ENTRY_LEN
+            ),
+        ));
+    }
+    Ok(())
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            hotkey: "CommandOrControl+Shift+Space".to_string(),
+            language: "en".to_string(),
+            enrichment_mode: "clean-transcript".to_string(),
+            output_target: "clipboard".to_string(),
+            retention_days: 7,
+            llm_provider: "openai".to_string(),
+            llm_model: Some("gpt-4o-mini".to_string()),
+            custom_prompt: None,
+            auto_enrich: true,
+            show_notifications: true,
+            notion_default_page_id: None,
+            notion_default_page_name: None,
+            ollama_model: None,
+            selected_microphone: None,
+            whisper_path: None,
+            whisper_model: "base".to_string(),
+            setup_complete: false,
+            vault_auto_lock_minutes: 15,
+            network: NetworkSettings::default(),
+            vad: VadSettings::default(),
+            queue: QueueSettings::default(),
+            whisper: WhisperOptions::default(),
+            vocabulary: VocabularySettings::default(),
+            models: ModelSettings::default(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// Check that all enumerated fields hold values the app understands
+    pub fn validate(&self) -> Result<(), InvalidSetting> {
+        if self.hotkey.trim().is_empty() {
+            return Err(InvalidSetting::new("hotkey", "Hotkey must not be empty"));
+        }
+        check_one_of("language", &self.language, LANGUAGES)?;
+        check_one_of("enrichmentMode", &self.enrichment_mode, ENRICHMENT_MODES)?;
+        check_one_of("outputTarget", &self.output_target, OUTPUT_TARGETS)?;
+        check_one_of("llmProvider", &self.llm_provider, LLM_PROVIDERS)?;
+        // 0 keeps no history at all
+        check_at_most("retentionDays", self.retention_days, 365)?;
+        if self.whisper_model.trim().is_empty() {
+            return Err(InvalidSetting::new("whisperModel", "whisperModel must not be empty"));
+        }
+        if self.vault_auto_lock_minutes > 24 * 60 {
+            return Err(InvalidSetting::new(
+                "vaultAutoLockMinutes",
+                "vaultAutoLockMinutes must be at most one day",
+            ));
+        }
+        self.network.validate()?;
+        self.vad.validate()?;
+        self.queue.validate()?;
+        self.whisper.validate()?;
+        self.vocabulary.validate()?;
+        self.models.validate()?;
+        Ok(())
+    }
+}
+
+fn check_one_of(field: &str, value: &str, allowed: &[&str]) -> Result<(), InvalidSetting> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(InvalidSetting::new(
+            field,
+            format!(
+                "Invalid {} '{}'. Expected one of: {}",
+                field,
+                value,
+                allowed.join(", ")
+            ),
+        ))
+    }
+}
+
+/// Name of the profile created on first run and by the legacy import
+pub const DEFAULT_PROFILE: &str = "Default";
+
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// A named set of settings, e.g. "Work" or "Offline"
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SettingsProfile {
+    pub name: String,
+    pub settings: AppSettings,
+}
+
+/// Profile names and which one is active
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub active_profile: String,
+    pub profiles: Vec<String>,
+}
+
+/// All profiles plus the active profile's name
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SettingsDocument {
+    active_profile: String,
+    profiles: Vec<SettingsProfile>,
+}
+
+impl Default for SettingsDocument {
+    fn default() -> Self {
+        Self {
+            active_profile: DEFAULT_PROFILE.to_string(),
+            profiles: vec![SettingsProfile {
+                name: DEFAULT_PROFILE.to_string(),
+                settings: AppSettings::default(),
+            }],
+        }
+    }
+}
+
+impl SettingsDocument {
+    fn validate(&self) -> Result<(), String> {
+        for (i, profile) in self.profiles.iter().enumerate() {
+            validate_profile_name(&profile.name)?;
+            if self.profiles[..i]
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(&profile.name))
+            {
+                return Err(format!("Duplicate profile name '{}'", profile.name));
+            }
+            profile
+                .settings
+                .validate()
+                .map_err(|e| format!("Profile '{}': {}", profile.name, e))?;
+        }
+        if self.find(&self.active_profile).is_none() {
+            return Err(format!("Active profile '{}' does not exist", self.active_profile));
+        }
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Option<&SettingsProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut SettingsProfile> {
+        self.profiles.iter_mut().find(|p| p.name == name)
+    }
+
+    /// Profile names are unique ignoring ASCII case, so imports match that way
+    fn find_ignore_case(&self, name: &str) -> Option<&SettingsProfile> {
+        self.profiles.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    fn active(&self) -> &SettingsProfile {
+        self.find(&self.active_profile)
+            .unwrap_or(&self.profiles[0])
+    }
+
+    fn list(&self) -> ProfileList {
+        ProfileList {
+            active_profile: self.active_profile.clone(),
+            profiles: self.profiles.iter().map(|p| p.name.clone()).collect(),
+        }
+    }
+}
+
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    if name.trim() != name {
+        return Err("Profile name must not start or end with whitespace".to_string());
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name must be at most {} characters",
+            MAX_PROFILE_NAME_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// On-disk settings document
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsFile {
+    schema_version: u32,
+    #[serde(flatten)]
+    document: SettingsDocument,
+}
+
+/// Persistent settings store held in Tauri managed state
+pub struct SettingsStore {
+    path: PathBuf,
+    document: Mutex<SettingsDocument>,
+    migration: Option<MigrationReport>,
+    /// Why saving is refused, when the settings file is there but couldn't
+    /// be read and must not be overwritten
+    read_only: Option<String>,
+}
+
+impl SettingsStore {
+    /// Load settings from `path`, falling back to defaults if the file is
+    /// missing or broken. A file that doesn't parse or validate is moved aside
+    /// so the next save doesn't overwrite it. A file that can't be read at all
+    /// is left where it is and saving is refused until the app restarts; one
+    /// from a newer version of the app is loaded, but saving is refused.
+    /// Without a settings file, settings the frontend saved to its
+    /// plugin-store file at `legacy_path` are imported.
+    pub fn load(path: PathBuf, legacy_path: &Path) -> Self {
+        let mut read_only = None;
+        let (document, migration) = match read_settings_file(&path) {
+            Ok(Some(loaded)) => {
+                log::info!("Loaded settings from {}", path.display());
+                loaded
+            }
+            Ok(None) => match import_legacy_settings(&path, legacy_path) {
+                Ok(Some((document, report))) => (document, Some(report)),
+                Ok(None) => {
+                    log::info!("No settings file at {}, using defaults", path.display());
+                    (SettingsDocument::default(), None)
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to import settings from {}: {}. Using defaults.",
+                        legacy_path.display(),
+                        e
+                    );
+                    (SettingsDocument::default(), None)
+                }
+            },
+            Err(ReadError::Invalid(e)) => {
+                log::warn!("Failed to load settings from {}: {}. Using defaults.", path.display(), e);
+                let report = set_aside_settings_file(&path, &e);
+                (SettingsDocument::default(), Some(report))
+            }
+            Err(ReadError::Newer { version, document }) => {
+                log::warn!(
+                    "Settings in {} use schema {}, newer than {}. Loading them read-only.",
+                    path.display(),
+                    version,
+                    SCHEMA_VERSION
+                );
+                read_only = Some(format!(
+                    "Settings can't be saved because a newer version of the app wrote them (schema {}). Update the app to change them.",
+                    version
+                ));
+                let mut changes = vec![format!(
+                    "Settings were written by a newer version of the app (schema {}, this version supports up to {})",
+                    version, SCHEMA_VERSION
+                )];
+                changes.push(if document.is_some() {
+                    "Loaded them read-only; changes won't be saved until the app is updated"
+                        .to_string()
+                } else {
+                    "This version can't read them, so default settings are used and won't be saved until the app is updated"
+                        .to_string()
+                });
+                let report = MigrationReport {
+                    imported_from: None,
+                    from_version: Some(version),
+                    backup_path: None,
+                    changes,
+                };
+                (document.unwrap_or_default(), Some(report))
+            }
+            Err(ReadError::Io(e)) => {
+                log::error!(
+                    "Failed to read settings from {}: {}. Using defaults without saving them.",
+                    path.display(),
+                    e
+                );
+                read_only = Some(format!(
+                    "Settings can't be saved because {} could not be read ({}). Restart the app once it is accessible.",
+                    path.display(),
+                    e
+                ));
+                let report = MigrationReport {
+                    imported_from: None,
+                    from_version: None,
+                    backup_path: None,
+                    changes: vec![
+                        format!("Settings file could not be read: {}", e),
+                        "Left it in place and started from default settings, which won't be saved"
+                            .to_string(),
+                    ],
+                };
+                (SettingsDocument::default(), Some(report))
+            }
+        };
+
+        Self {
+            path,
+            document: Mutex::new(document),
+            migration,
+            read_only,
+        }
+    }
+
+    /// Report of the legacy settings import or schema upgrade performed on
+    /// load, or of the settings file that was moved aside because it
+    /// couldn't be loaded
+    pub fn migration_report(&self) -> Option<MigrationReport> {
+        self.migration.clone()
+    }
+
+    /// Settings of the active profile
+    pub fn get(&self) -> AppSettings {
+        self.lock().active().settings.clone()
+    }
+
+    /// Validate and persist new settings for the active profile
+    pub fn save(&self, settings: AppSettings) -> Result<(), String> {
+        self.update(|doc| {
+            let active = doc.active_profile.clone();
+            if let Some(profile) = doc.find_mut(&active) {
+                profile.settings = settings;
+            }
+            Ok(())
+        })?;
+
+        log::info!("Settings saved to {}", self.path.display());
+        Ok(())
+    }
+
+    /// Names of all profiles and the active one
+    pub fn list_profiles(&self) -> ProfileList {
+        self.lock().list()
+    }
+
+    /// Create a new profile, copying the settings of `source` if given and
+    /// starting from defaults otherwise
+    pub fn create_profile(&self, name: &str, source: Option<&str>) -> Result<ProfileList, String> {
+        self.update(|doc| {
+            let settings = match source {
+                Some(source) => doc
+                    .find(source)
+                    .ok_or_else(|| format!("Profile '{}' not found", source))?
+                    .settings
+                    .clone(),
+                None => AppSettings::default(),
+            };
+            doc.profiles.push(SettingsProfile {
+                name: name.to_string(),
+                settings,
+            });
+            Ok(doc.list())
+        })
+    }
+
+    /// Make `name` the active profile and return its settings
+    pub fn switch_profile(&self, name: &str) -> Result<AppSettings, String> {
+        self.update(|doc| {
+            let settings = doc
+                .find(name)
+                .ok_or_else(|| format!("Profile '{}' not found", name))?
+                .settings
+                .clone();
+            doc.active_profile = name.to_string();
+            Ok(settings)
+        })
+    }
+
+    /// Delete a profile. The active profile cannot be deleted.
+    pub fn delete_profile(&self, name: &str) -> Result<ProfileList, String> {
+        self.update(|doc| {
+            if doc.active_profile == name {
+                return Err(format!(
+                    "Cannot delete the active profile '{}'. Switch to another profile first.",
+                    name
+                ));
+            }
+            let before = doc.profiles.len();
+            doc.profiles.retain(|p| p.name != name);
+            if doc.profiles.len() == before {
+                return Err(format!("Profile '{}' not found", name));
+            }
+            Ok(doc.list())
+        })
+    }
+
+    /// Export one profile, or all profiles if `profile` is `None`, as a bundle
+    pub fn export_bundle(&self, profile: Option<&str>) -> Result<SettingsBundle, String> {
+        let doc = self.lock();
+        let profiles = match profile {
+            Some(name) => vec![doc
+                .find(name)
+                .ok_or_else(|| format!("Profile '{}' not found", name))?
+                .clone()],
+            None => doc.profiles.clone(),
+        };
+        Ok(SettingsBundle::new(profiles))
+    }
+
+    /// Describe what importing `bundle` would change, without applying it
+    pub fn preview_import(&self, bundle: &SettingsBundle) -> ImportPreview {
+        let doc = self.lock();
+        let profiles = bundle
+            .profiles
+            .iter()
+            .map(|incoming| {
+                let existing = doc.find_ignore_case(&incoming.name);
+                let current = existing.map(|p| p.settings.clone()).unwrap_or_default();
+                let mut settings = incoming.settings.clone();
+                bundle::keep_machine_specific(&mut settings, &current);
+
+                bundle::ProfileDiff {
+                    name: existing.map_or_else(|| incoming.name.clone(), |p| p.name.clone()),
+                    is_new: existing.is_none(),
+                    changes: bundle::diff_settings(&current, &settings),
+                }
+            })
+            .collect();
+
+        ImportPreview {
+            profiles,
+            secrets: bundle.secrets.iter().map(|s| s.name).collect(),
+        }
+    }
+
+    /// Apply a bundle's profiles. Profiles with a matching name are
+    /// overwritten and the rest are added; the active profile stays active.
+    /// Bundled secrets are not handled here.
+    pub fn import_bundle(&self, bundle: SettingsBundle) -> Result<ProfileList, String> {
+        self.update(|doc| {
+            for incoming in bundle.profiles {
+                let existing = doc
+                    .profiles
+                    .iter_mut()
+                    .find(|p| p.name.eq_ignore_ascii_case(&incoming.name));
+                match existing {
+                    Some(profile) => {
+                        let mut settings = incoming.settings;
+                        bundle::keep_machine_specific(&mut settings, &profile.settings);
+                        profile.settings = settings;
+                    }
+                    None => doc.profiles.push(incoming),
+                }
+            }
+            Ok(doc.list())
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SettingsDocument> {
+        self.document.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Apply `f` to a copy of the document, then validate and persist it
+    /// before adopting it. On any error the stored document is unchanged.
+    fn update<T>(
+        &self,
+        f: impl FnOnce(&mut SettingsDocument) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if let Some(reason) = &self.read_only {
+            return Err(reason.clone());
+        }
+        let mut current = self.lock();
+        let mut doc = current.clone();
+        let result = f(&mut doc)?;
+        doc.validate()?;
+        write_settings_file(&self.path, &doc)?;
+        *current = doc;
+        Ok(result)
+    }
+}
+
+/// Why the settings file couldn't be loaded
+enum ReadError {
+    /// Reading or rewriting the file failed. The file may be fine and is
+    /// left alone.
+    Io(String),
+    /// The file doesn't parse, validate or migrate
+    Invalid(String),
+    /// The file was written by a newer version of the app. `document` holds
+    /// what this version could make of it, if anything.
+    Newer {
+        version: u32,
+        document: Option<SettingsDocument>,
+    },
+}
+
+/// Read, migrate and validate the settings document. Returns `Ok(None)` if
+/// it doesn't exist. A document from an older schema version is copied to
+/// `app-settings.v{N}.bak` before the upgraded version is written back.
+fn read_settings_file(
+    path: &Path,
+) -> Result<Option<(SettingsDocument, Option<MigrationReport>)>, ReadError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| ReadError::Io(format!("Failed to read settings file: {}", e)))?;
+    let mut doc: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| ReadError::Invalid(format!("Failed to parse settings file: {}", e)))?;
+    let version = migrations::schema_version(&doc).map_err(ReadError::Invalid)?;
+    if version > SCHEMA_VERSION {
+        // Newer schemas only add fields, as far as this version can tell;
+        // the unknown ones are ignored
+        let document = serde_json::from_value::<SettingsFile>(doc)
+            .ok()
+            .map(|file| file.document)
+            .filter(|document| document.validate().is_ok());
+        return Err(ReadError::Newer { version, document });
+    }
+    let changes = migrations::migrate(&mut doc, version).map_err(ReadError::Invalid)?;
+    let file: SettingsFile = serde_json::from_value(doc)
+        .map_err(|e| ReadError::Invalid(format!("Failed to parse settings file: {}", e)))?;
+    file.document.validate().map_err(ReadError::Invalid)?;
+
+    if version == SCHEMA_VERSION {
+        return Ok(Some((file.document, None)));
+    }
+
+    let backup_path = path.with_extension(format!("v{}.bak", version));
+    std::fs::copy(path, &backup_path).map_err(|e| {
+        ReadError::Io(format!(
+            "Failed to back up settings before migration: {}",
+            e
+        ))
+    })?;
+    write_settings_file(path, &file.document).map_err(ReadError::Io)?;
+
+    log::info!(
+        "Migrated settings from schema {} to {} (backup at {}): {:?}",
+        version,
+        SCHEMA_VERSION,
+        backup_path.display(),
+        changes
+    );
+
+    let report = MigrationReport {
+        imported_from: None,
+        from_version: Some(version),
+        backup_path: Some(backup_path.to_string_lossy().to_string()),
+        changes,
+    };
+    Ok(Some((file.document, Some(report))))
+}
+
+/// Import the frontend's plugin-store settings into the "Default" profile
+/// and write them to `path`. The plugin-store file is not modified.
+fn import_legacy_settings(
+    path: &Path,
+    legacy_path: &Path,
+) -> Result<Option<(SettingsDocument, MigrationReport)>, String> {
+    let Some((settings, changes)) = migrations::import_legacy(legacy_path)? else {
+        return Ok(None);
+    };
+    settings.validate()?;
+
+    let document = SettingsDocument {
+        active_profile: DEFAULT_PROFILE.to_string(),
+        profiles: vec![SettingsProfile {
+            name: DEFAULT_PROFILE.to_string(),
+            settings,
+        }],
+    };
+    write_settings_file(path, &document)?;
+
+    log::info!(
+        "Imported settings from {} into {}: {:?}",
+        legacy_path.display(),
+        path.display(),
+        changes
+    );
+
+    let report = MigrationReport {
+        imported_from: Some(legacy_path.to_string_lossy().to_string()),
+        from_version: None,
+        backup_path: None,
+        changes,
+    };
+    Ok(Some((document, report)))
+}
+
+/// Move a settings file that couldn't be loaded to
+/// `app-settings.json.corrupt-<timestamp>`, so saving the defaults that
+/// replace it doesn't destroy the user's profiles
+fn set_aside_settings_file(path: &Path, error: &str) -> MigrationReport {
+    let mut changes = vec![format!("Settings file could not be loaded: {}", error)];
+    let backup_path = match crate::storage::move_aside(path) {
+        Ok(corrupt_path) => {
+            log::warn!(
+                "Moved unloadable settings file to {}",
+                corrupt_path.display()
+            );
+            changes.push(format!(
+                "Moved it to {} and started from default settings",
+                corrupt_path.display()
+            ));
+            Some(corrupt_path.to_string_lossy().to_string())
+        }
+        Err(e) => {
+            log::warn!("Failed to move {} aside: {}", path.display(), e);
+            changes.push(format!("Failed to move it aside: {}", e));
+            None
+        }
+    };
+
+    MigrationReport {
+        imported_from: None,
+        from_version: None,
+        backup_path,
+        changes,
+    }
+}
+
+/// Write the settings document atomically: write a sibling temp file,
+/// sync it to disk, then rename it over the real file.
+fn write_settings_file(path: &Path, document: &SettingsDocument) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    let file = SettingsFile {
+        schema_version: SCHEMA_VERSION,
+        document: document.clone(),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let temp_path = path.with_extension("json.tmp");
+    let mut temp_file = std::fs::File::create(&temp_path)
+        .map_err(|e| format!("Failed to create temp settings file: {}", e))?;
+    temp_file
+        .write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
+    temp_file
+        .sync_all()
+        .map_err(|e| format!("Failed to sync settings: {}", e))?;
+    drop(temp_file);
+
+    std::fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to move settings into place: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory under the system temp dir
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("settings-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Load the store in `dir`, with the frontend's plugin-store file beside it
+    fn load(dir: &Path) -> SettingsStore {
+        SettingsStore::load(dir.join(SETTINGS_FILE_NAME), &dir.join("settings.json"))
+    }
+
+    #[test]
+    fn missing_file_loads_defaults() {
+        let dir = temp_dir();
+        let store = load(&dir);
+
+        assert_eq!(store.get().hotkey, AppSettings::default().hotkey);
+        assert_eq!(store.list_profiles().active_profile, DEFAULT_PROFILE);
+        assert!(store.migration_report().is_none());
+        assert!(!dir.join(SETTINGS_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn saved_settings_survive_reload() {
+        let dir = temp_dir();
+        let store = load(&dir);
+        let mut settings = store.get();
+        settings.language = "de".to_string();
+        settings.retention_days = 30;
+        settings.vocabulary.terms = vec!["Kubernetes".to_string()];
+        store.save(settings).unwrap();
+
+        let reloaded = load(&dir).get();
+        assert_eq!(reloaded.language, "de");
+        assert_eq!(reloaded.retention_days, 30);
+        assert_eq!(reloaded.vocabulary.terms, ["Kubernetes"]);
+    }
+
+    #[test]
+    fn invalid_settings_are_not_saved() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let store = load(&dir);
+        store.save(store.get()).unwrap();
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        let mut settings = store.get();
+        settings.language = "xx".to_string();
+        assert!(store.save(settings).is_err());
+
+        assert_eq!(store.get().language, AppSettings::default().language);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn validation_names_the_invalid_field() {
+        let mut settings = AppSettings::default();
+        settings.vad.padding_ms = 5000;
+
+        let err = settings.validate().unwrap_err();
+        assert_eq!(err.field, "vad.paddingMs");
+        assert_eq!(err.message, "vad.paddingMs must be at most 2000, got 5000");
+
+        let settings = AppSettings {
+            language: "xx".to_string(),
+            ..AppSettings::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().field, "language");
+
+        let settings = AppSettings {
+            queue: QueueSettings { parallel_jobs: 0 },
+            ..AppSettings::default()
+        };
+        assert_eq!(settings.validate().unwrap_err().field, "queue.parallelJobs");
+    }
+
+    #[test]
+    fn save_replaces_file_atomically() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let store = load(&dir);
+        store.save(store.get()).unwrap();
+        store.create_profile("Work", None).unwrap();
+
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, [SETTINGS_FILE_NAME]);
+
+        let file: SettingsFile =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(file.schema_version, SCHEMA_VERSION);
+        assert_eq!(file.document.profiles.len(), 2);
+    }
+
+    #[test]
+    fn unparsable_file_is_moved_aside() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let store = load(&dir);
+        assert_eq!(store.get().hotkey, AppSettings::default().hotkey);
+        assert!(!path.exists());
+
+        let report = store.migration_report().expect("broken file is reported");
+        let backup = PathBuf::from(report.backup_path.expect("file was moved aside"));
+        assert!(backup
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("app-settings.json.corrupt-"));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ not json");
+
+        // Saving the defaults leaves the broken file alone
+        store.save(store.get()).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn unreadable_file_is_left_in_place() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        // Exists but can't be read as a file, like one without permission
+        std::fs::create_dir(&path).unwrap();
+
+        let store = load(&dir);
+        assert_eq!(store.get().hotkey, AppSettings::default().hotkey);
+        let report = store.migration_report().expect("unreadable file is reported");
+        assert!(report.changes[0].starts_with("Settings file could not be read"));
+        assert_eq!(report.backup_path, None);
+        assert!(path.is_dir());
+
+        // The defaults must not replace the user's file
+        let err = store.save(store.get()).unwrap_err();
+        assert!(err.contains("could not be read"), "{}", err);
+        assert!(store.create_profile("Work", None).is_err());
+        assert!(path.is_dir());
+    }
+
+    #[test]
+    fn invalid_file_is_moved_aside() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let invalid = serde_json::json!({
+            "schemaVersion": SCHEMA_VERSION,
+            "activeProfile": "Work",
+            "profiles": [{ "name": DEFAULT_PROFILE, "settings": {} }],
+        });
+        std::fs::write(&path, invalid.to_string()).unwrap();
+
+        let report = load(&dir)
+            .migration_report()
+            .expect("invalid file is reported");
+        assert!(report.changes[0].contains("Active profile 'Work' does not exist"));
+        assert!(report.backup_path.is_some());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn imports_plugin_store_settings_once() {
+        let dir = temp_dir();
+        let legacy = include_str!("../tests/fixtures/settings/plugin-store.json");
+        std::fs::write(dir.join("settings.json"), legacy).unwrap();
+
+        let store = load(&dir);
+        let report = store.migration_report().expect("settings were imported");
+        assert!(report.imported_from.unwrap().ends_with("settings.json"));
+        assert_eq!(store.get().llm_provider, "ollama");
+        assert!(dir.join(SETTINGS_FILE_NAME).exists());
+        assert_eq!(std::fs::read_to_string(dir.join("settings.json")).unwrap(), legacy);
+
+        // The backend file wins from now on
+        let mut settings = store.get();
+        settings.llm_provider = "openai".to_string();
+        store.save(settings).unwrap();
+        let reloaded = load(&dir);
+        assert!(reloaded.migration_report().is_none());
+        assert_eq!(reloaded.get().llm_provider, "openai");
+    }
+
+    #[test]
+    fn unversioned_file_is_moved_aside() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        std::fs::write(&path, r#"{ "language": "de" }"#).unwrap();
+
+        let report = load(&dir).migration_report().expect("file is reported");
+        assert!(report.changes[0].contains("no schemaVersion"));
+        assert!(report.backup_path.is_some());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn newer_schema_is_loaded_read_only() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let newer = serde_json::json!({
+            "schemaVersion": SCHEMA_VERSION + 1,
+            "activeProfile": "Work",
+            "profiles": [
+                { "name": DEFAULT_PROFILE, "settings": {} },
+                { "name": "Work", "settings": { "language": "de", "someNewSetting": true } },
+            ],
+            "someNewSection": {},
+        })
+        .to_string();
+        std::fs::write(&path, &newer).unwrap();
+
+        let store = load(&dir);
+        assert_eq!(store.get().language, "de");
+        assert_eq!(store.list_profiles().profiles, [DEFAULT_PROFILE, "Work"]);
+        let report = store.migration_report().expect("newer file is reported");
+        assert_eq!(report.from_version, Some(SCHEMA_VERSION + 1));
+        assert_eq!(report.backup_path, None);
+
+        // Nothing this version writes may replace the newer file
+        let err = store.save(store.get()).unwrap_err();
+        assert!(err.contains("newer version"), "{}", err);
+        assert!(store.switch_profile(DEFAULT_PROFILE).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), newer);
+    }
+
+    #[test]
+    fn unreadable_newer_schema_is_kept() {
+        let dir = temp_dir();
+        let path = dir.join(SETTINGS_FILE_NAME);
+        let newer = serde_json::json!({
+            "schemaVersion": SCHEMA_VERSION + 1,
+            "profiles": { "renamed": "in a later version" },
+        })
+        .to_string();
+        std::fs::write(&path, &newer).unwrap();
+
+        let store = load(&dir);
+        assert_eq!(store.get().language, AppSettings::default().language);
+        assert!(store.migration_report().is_some());
+        assert!(store.save(store.get()).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), newer);
+    }
+}
@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{validate_profile_name, AppSettings, SettingsProfile};
+use crate::secrets::SecretName;
+
+/// Marker identifying a settings bundle file
+pub const BUNDLE_FORMAT: &str = "voice-intelligence-settings";
+
+/// Current version of the bundle layout
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Portable export of one or more settings profiles. API keys are only
+/// included when explicitly requested, and machine-specific fields are
+/// cleared on export.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format: String,
+    pub bundle_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub profiles: Vec<SettingsProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<BundledSecret>,
+}
+
+/// An API key carried in a bundle exported with secrets
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundledSecret {
+    pub name: SecretName,
+    pub value: String,
+}
+
+/// A single field that an import would change
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    pub field: String,
+    pub current: Value,
+    pub incoming: Value,
+}
+
+/// Changes an import would make to one profile
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileDiff {
+    pub name: String,
+    pub is_new: bool,
+    pub changes: Vec<SettingChange>,
+}
+
+/// Everything an import would change, shown to the user before applying
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub profiles: Vec<ProfileDiff>,
+    /// API keys the bundle would overwrite. Values are never included.
+    pub secrets: Vec<SecretName>,
+}
+
+impl SettingsBundle {
+    /// Build a bundle from profiles, dropping fields that only make sense on
+    /// the machine they were exported from
+    pub fn new(profiles: Vec<SettingsProfile>) -> Self {
+        let profiles = profiles
+            .into_iter()
+            .map(|mut profile| {
+                clear_machine_specific(&mut profile.settings);
+                profile
+            })
+            .collect();
+
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            bundle_version: BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            profiles,
+            secrets: Vec::new(),
+        }
+    }
+
+    /// Parse and validate a bundle file's contents
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let bundle: SettingsBundle = serde_json::from_str(content)
+            .map_err(|e| format!("Not a valid settings bundle: {}", e))?;
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.format != BUNDLE_FORMAT {
+            return Err(format!("Unknown bundle format '{}'", self.format));
+        }
+        if self.bundle_version > BUNDLE_VERSION {
+            return Err(format!(
+                "Bundle version {} is newer than supported ({}). Please update the app.",
+                self.bundle_version, BUNDLE_VERSION
+            ));
+        }
+        if self.profiles.is_empty() {
+            return Err("Bundle contains no profiles".to_string());
+        }
+        for (i, profile) in self.profiles.iter().enumerate() {
+            validate_profile_name(&profile.name)?;
+            if self.profiles[..i]
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(&profile.name))
+            {
+                return Err(format!("Duplicate profile '{}' in bundle", profile.name));
+            }
+            profile
+                .settings
+                .validate()
+                .map_err(|e| format!("Profile '{}': {}", profile.name, e))?;
+        }
+        for (i, secret) in self.secrets.iter().enumerate() {
+            if secret.value.trim().is_empty() {
+                return Err(format!("Secret '{}' in bundle is empty", secret.name.key()));
+            }
+            if self.secrets[..i].iter().any(|s| s.name == secret.name) {
+                return Err(format!("Duplicate secret '{}' in bundle", secret.name.key()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fields that point at local files or devices and are not exported
+pub fn clear_machine_specific(settings: &mut AppSettings) {
+    settings.whisper_path = None;
+    settings.selected_microphone = None;
+    settings.network.ca_bundle_path = None;
+    settings.models.directory = None;
+    settings.models.custom.clear();
+}
+
+/// Keep the local machine-specific values when importing over an existing profile
+pub fn keep_machine_specific(incoming: &mut AppSettings, existing: &AppSettings) {
+    if incoming.whisper_path.is_none() {
+        incoming.whisper_path = existing.whisper_path.clone();
+    }
+    if incoming.selected_microphone.is_none() {
+        incoming.selected_microphone = existing.selected_microphone.clone();
+    }
+    if incoming.network.ca_bundle_path.is_none() {
+        incoming.network.ca_bundle_path = existing.network.ca_bundle_path.clone();
+    }
+    if incoming.models.directory.is_none() {
+        incoming.models.directory = existing.models.directory.clone();
+    }
+    if incoming.models.custom.is_empty() {
+        incoming.models.custom = existing.models.custom.clone();
+    }
+}
+
+/// Field-by-field differences between two settings values
+pub fn diff_settings(current: &AppSettings, incoming: &AppSettings) -> Vec<SettingChange> {
+    let current = serde_json::to_value(current).unwrap_or(Value::Null);
+    let incoming = serde_json::to_value(incoming).unwrap_or(Value::Null);
+
+    let (Some(current), Some(incoming)) = (current.as_object(), incoming.as_object()) else {
+        return Vec::new();
+    };
+
+    incoming
+        .iter()
+        .filter_map(|(field, new_value)| {
+            let old_value = current.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != *new_value).then(|| SettingChange {
+                field: field.clone(),
+                current: old_value,
+                incoming: new_value.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::CustomModel;
+
+    /// Settings pointing at files and devices on this machine
+    fn local_settings() -> AppSettings {
+        let mut settings = AppSettings {
+            whisper_path: Some("/usr/local/bin/whisper-cli".to_string()),
+            selected_microphone: Some("USB Microphone".to_string()),
+            ..AppSettings::default()
+        };
+        settings.network.ca_bundle_path = Some("/etc/ssl/corp.pem".to_string());
+        settings.models.directory = Some("/data/models".to_string());
+        settings.models.custom = vec![CustomModel {
+            id: "custom:ggml-tuned".to_string(),
+            name: "Tuned".to_string(),
+            path: "/data/models/ggml-tuned.bin".to_string(),
+            multilingual: true,
+        }];
+        settings
+    }
+
+    fn bundle_json(bundle: &SettingsBundle) -> Value {
+        serde_json::to_value(bundle).unwrap()
+    }
+
+    fn parse_json(json: Value) -> Result<SettingsBundle, String> {
+        SettingsBundle::parse(&json.to_string())
+    }
+
+    fn profile(name: &str, settings: AppSettings) -> SettingsProfile {
+        SettingsProfile {
+            name: name.to_string(),
+            settings,
+        }
+    }
+
+    #[test]
+    fn export_clears_machine_specific_fields() {
+        let bundle = SettingsBundle::new(vec![profile("Work", local_settings())]);
+        let settings = &bundle.profiles[0].settings;
+
+        assert!(settings.whisper_path.is_none());
+        assert!(settings.selected_microphone.is_none());
+        assert!(settings.network.ca_bundle_path.is_none());
+        assert!(settings.models.directory.is_none());
+        assert!(settings.models.custom.is_empty());
+        assert!(bundle.secrets.is_empty());
+    }
+
+    #[test]
+    fn import_keeps_local_machine_specific_fields() {
+        let existing = local_settings();
+        let mut incoming = AppSettings {
+            language: "de".to_string(),
+            ..AppSettings::default()
+        };
+        keep_machine_specific(&mut incoming, &existing);
+
+        assert_eq!(incoming.whisper_path, existing.whisper_path);
+        assert_eq!(incoming.selected_microphone, existing.selected_microphone);
+        assert_eq!(incoming.network.ca_bundle_path, existing.network.ca_bundle_path);
+        assert_eq!(incoming.models.directory, existing.models.directory);
+        assert_eq!(incoming.models.custom.len(), 1);
+        assert_eq!(incoming.language, "de");
+
+        // Values carried by the bundle win over the local ones
+        let mut incoming = AppSettings {
+            whisper_path: Some("/opt/whisper-cli".to_string()),
+            ..AppSettings::default()
+        };
+        keep_machine_specific(&mut incoming, &existing);
+        assert_eq!(incoming.whisper_path.as_deref(), Some("/opt/whisper-cli"));
+    }
+
+    #[test]
+    fn diff_lists_only_changed_fields() {
+        let current = AppSettings::default();
+        let mut incoming = current.clone();
+        incoming.language = "de".to_string();
+        incoming.retention_days = 30;
+        incoming.vad.padding_ms += 100;
+
+        let changes = diff_settings(&current, &incoming);
+        let mut fields: Vec<_> = changes.iter().map(|c| c.field.as_str()).collect();
+        fields.sort_unstable();
+        assert_eq!(fields, ["language", "retentionDays", "vad"]);
+
+        let language = changes.iter().find(|c| c.field == "language").unwrap();
+        assert_eq!(language.current, Value::from(current.language.clone()));
+        assert_eq!(language.incoming, Value::from("de"));
+
+        assert!(diff_settings(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn parses_exported_bundles() {
+        let mut bundle = SettingsBundle::new(vec![
+            profile("Work", AppSettings::default()),
+            profile("Offline", AppSettings::default()),
+        ]);
+        bundle.secrets.push(BundledSecret {
+            name: SecretName::Notion,
+            value: "secret_abc".to_string(),
+        });
+
+        let parsed = parse_json(bundle_json(&bundle)).unwrap();
+        assert_eq!(parsed.profiles.len(), 2);
+        assert_eq!(parsed.secrets[0].name, SecretName::Notion);
+    }
+
+    #[test]
+    fn rejects_newer_or_foreign_bundles() {
+        let bundle = SettingsBundle::new(vec![profile("Work", AppSettings::default())]);
+
+        let mut newer = bundle_json(&bundle);
+        newer["bundleVersion"] = Value::from(BUNDLE_VERSION + 1);
+        assert!(parse_json(newer).unwrap_err().contains("newer than supported"));
+
+        let mut foreign = bundle_json(&bundle);
+        foreign["format"] = Value::from("something-else");
+        assert!(parse_json(foreign).is_err());
+    }
+
+    #[test]
+    fn rejects_bundles_with_missing_fields() {
+        let bundle = SettingsBundle::new(vec![profile("Work", AppSettings::default())]);
+
+        for field in ["format", "bundleVersion", "profiles"] {
+            let mut json = bundle_json(&bundle);
+            json.as_object_mut().unwrap().remove(field);
+            assert!(parse_json(json).is_err(), "{}", field);
+        }
+
+        let mut no_profiles = bundle_json(&bundle);
+        no_profiles["profiles"] = Value::Array(Vec::new());
+        assert!(parse_json(no_profiles).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_profiles_and_secrets() {
+        let mut bundle = SettingsBundle::new(vec![
+            profile("Work", AppSettings::default()),
+            profile("work", AppSettings::default()),
+        ]);
+        assert!(bundle.validate().unwrap_err().contains("Duplicate profile"));
+
+        bundle.profiles.pop();
+        bundle.profiles[0].settings.language = "xx".to_string();
+        assert!(bundle.validate().is_err());
+
+        bundle.profiles[0].settings.language = "en".to_string();
+        bundle.secrets.push(BundledSecret {
+            name: SecretName::OpenAi,
+            value: "  ".to_string(),
+        });
+        assert!(bundle.validate().unwrap_err().contains("is empty"));
+    }
+}
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use super::{AppSettings, SCHEMA_VERSION};
+use crate::secrets::SecretName;
+
+/// Key the frontend kept its settings under in the plugin-store file
+pub const LEGACY_SETTINGS_KEY: &str = "settings";
+
+/// An upgrade step from the schema version it is listed with to the next.
+/// Works on the raw JSON document and returns a note for every change.
+type MigrationStep = fn(&mut Value) -> Vec<String>;
+
+/// Upgrade steps in order, keyed by the schema version they upgrade from.
+/// Schema 1 is the one `app-settings.json` was introduced with; settings from
+/// before it live in the frontend's plugin-store file and go through
+/// `import_legacy` instead.
+const MIGRATIONS: &[(u32, MigrationStep)] = &[];
+
+/// Summary of the settings imported on first run, of a settings file
+/// upgraded from an older schema, or of one that couldn't be loaded
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// Plugin-store file the settings were read from. It is left in place.
+    pub imported_from: Option<String>,
+    /// Schema version the settings file was upgraded from, or the newer one
+    /// it was left at because this version of the app can't write it
+    pub from_version: Option<u32>,
+    /// Copy of the settings file from before it was upgraded, or where a
+    /// file that couldn't be loaded was moved to
+    pub backup_path: Option<String>,
+    pub changes: Vec<String>,
+}
+
+/// Schema version of a raw settings document. Every version of the app
+/// that wrote `app-settings.json` recorded it.
+pub fn schema_version(doc: &Value) -> Result<u32, String> {
+    let doc = doc
+        .as_object()
+        .ok_or("Settings document is not a JSON object")?;
+    let version = doc
+        .get("schemaVersion")
+        .ok_or("Settings document has no schemaVersion")?;
+    version
+        .as_u64()
+        .and_then(|v| u32::try_from(v).ok())
+        .filter(|v| *v >= 1)
+        .ok_or_else(|| format!("Invalid schemaVersion: {}", version))
+}
+
+/// Upgrade a raw settings document from `version` to `SCHEMA_VERSION`,
+/// returning the changes made
+pub fn migrate(doc: &mut Value, version: u32) -> Result<Vec<String>, String> {
+    let mut changes = Vec::new();
+    for current in version..SCHEMA_VERSION {
+        let (_, step) = MIGRATIONS
+            .iter()
+            .find(|(from, _)| *from == current)
+            .ok_or_else(|| format!("No migration from settings schema {}", current))?;
+        changes.extend(step(doc));
+        if let Some(doc) = doc.as_object_mut() {
+            doc.insert("schemaVersion".to_string(), Value::from(current + 1));
+        }
+    }
+    Ok(changes)
+}
+
+/// Add every `AppSettings` field missing from `settings` with its default
+fn fill_defaults(settings: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    let Ok(Value::Object(defaults)) = serde_json::to_value(AppSettings::default()) else {
+        return;
+    };
+    for (key, value) in defaults {
+        if !settings.contains_key(&key) {
+            changes.push(format!("Added '{}' with default {}", key, value));
+            settings.insert(key, value);
+        }
+    }
+}
+
+/// Read the settings the frontend kept in its plugin-store file before
+/// they moved to the backend. Returns `Ok(None)` if the file doesn't exist
+/// or never had settings saved to it.
+pub fn import_legacy(path: &Path) -> Result<Option<(AppSettings, Vec<String>)>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read legacy settings: {}", e))?;
+    let store: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse legacy settings: {}", e))?;
+    legacy_settings(&store)
+}
+
+/// Turn the `settings` entry of a plugin-store document into `AppSettings`,
+/// noting every field that was filled with a default or dropped
+fn legacy_settings(store: &Value) -> Result<Option<(AppSettings, Vec<String>)>, String> {
+    let store = store
+        .as_object()
+        .ok_or("Legacy settings store is not a JSON object")?;
+    let Some(legacy) = store.get(LEGACY_SETTINGS_KEY) else {
+        return Ok(None);
+    };
+    let legacy = legacy
+        .as_object()
+        .ok_or("Legacy settings are not a JSON object")?;
+
+    let defaults = serde_json::to_value(AppSettings::default())
+        .map_err(|e| format!("Failed to serialize default settings: {}", e))?;
+    let defaults = defaults
+        .as_object()
+        .ok_or("Default settings are not a JSON object")?;
+
+    let mut settings = Map::new();
+    let mut changes = Vec::new();
+    for (key, value) in legacy {
+        // API keys are moved into the secret store separately
+        if SecretName::ALL.iter().any(|name| name.legacy_field() == key) {
+            continue;
+        }
+        if defaults.contains_key(key) {
+            settings.insert(key.clone(), value.clone());
+        } else {
+            changes.push(format!("Dropped unknown field '{}'", key));
+        }
+    }
+    fill_defaults(&mut settings, &mut changes);
+
+    let settings: AppSettings = serde_json::from_value(Value::Object(settings))
+        .map_err(|e| format!("Failed to parse legacy settings: {}", e))?;
+    Ok(Some((settings, changes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_fixture(content: &str) -> Option<(AppSettings, Vec<String>)> {
+        let store: Value = serde_json::from_str(content).expect("fixture is valid JSON");
+        let imported = legacy_settings(&store).expect("import succeeds");
+        if let Some((settings, _)) = &imported {
+            settings.validate().expect("imported settings are valid");
+        }
+        imported
+    }
+
+    #[test]
+    fn imports_customised_settings() {
+        let (settings, changes) =
+            import_fixture(include_str!("../../tests/fixtures/settings/plugin-store.json")).unwrap();
+
+        assert_eq!(settings.hotkey, "CommandOrControl+Shift+R");
+        assert_eq!(settings.language, "de");
+        assert_eq!(settings.enrichment_mode, "meeting-notes");
+        assert_eq!(settings.output_target, "notion");
+        assert_eq!(settings.retention_days, 30);
+        assert_eq!(settings.llm_provider, "ollama");
+        assert_eq!(settings.ollama_model.as_deref(), Some("llama3.2"));
+        assert_eq!(settings.whisper_model, "small");
+        assert_eq!(settings.whisper_path.as_deref(), Some("/opt/homebrew/bin/whisper-cli"));
+        assert_eq!(settings.notion_default_page_name.as_deref(), Some("Inbox"));
+        assert_eq!(settings.selected_microphone.as_deref(), Some("USB Microphone"));
+        assert!(!settings.auto_enrich);
+        assert!(settings.setup_complete);
+        assert!(!changes.iter().any(|c| c.contains("ApiKey")));
+        assert!(changes.iter().any(|c| c.starts_with("Added 'vad'")));
+    }
+
+    #[test]
+    fn imports_default_settings() {
+        let (settings, changes) = import_fixture(include_str!(
+            "../../tests/fixtures/settings/plugin-store-defaults.json"
+        ))
+        .unwrap();
+
+        assert_eq!(settings.hotkey, AppSettings::default().hotkey);
+        assert_eq!(settings.llm_provider, "openai");
+        assert_eq!(settings.llm_model.as_deref(), Some("gpt-4o-mini"));
+        // "Never save history"
+        assert_eq!(settings.retention_days, 0);
+        assert!(settings.custom_prompt.is_none());
+        assert!(changes.iter().all(|c| c.starts_with("Added '")));
+    }
+
+    #[test]
+    fn store_without_settings_is_not_imported() {
+        let imported = import_fixture(include_str!(
+            "../../tests/fixtures/settings/plugin-store-history-only.json"
+        ));
+
+        assert!(imported.is_none());
+    }
+
+    #[test]
+    fn every_past_schema_has_a_migration() {
+        // One step from each version before the current one, in order
+        let from: Vec<u32> = MIGRATIONS.iter().map(|(from, _)| *from).collect();
+        assert_eq!(from, (1..SCHEMA_VERSION).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reads_schema_versions() {
+        assert_eq!(schema_version(&serde_json::json!({ "schemaVersion": 1 })).unwrap(), 1);
+        assert_eq!(schema_version(&serde_json::json!({ "schemaVersion": 7 })).unwrap(), 7);
+        assert!(schema_version(&serde_json::json!({ "language": "de" })).is_err());
+        assert!(schema_version(&serde_json::json!({ "schemaVersion": 0 })).is_err());
+        assert!(schema_version(&serde_json::json!({ "schemaVersion": "1" })).is_err());
+        assert!(schema_version(&serde_json::json!([])).is_err());
+    }
+
+    #[test]
+    fn current_schema_needs_no_migration() {
+        let mut doc = serde_json::json!({ "schemaVersion": SCHEMA_VERSION, "profiles": [] });
+        let before = doc.clone();
+
+        assert!(migrate(&mut doc, SCHEMA_VERSION).unwrap().is_empty());
+        assert_eq!(doc, before);
+    }
+
+    #[test]
+    fn rejects_malformed_settings() {
+        let store = serde_json::json!({ "settings": { "retentionDays": "forever" } });
+
+        assert!(legacy_settings(&store).is_err());
+    }
+}
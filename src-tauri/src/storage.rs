@@ -0,0 +1,281 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// Store file the frontend keeps the transcription history in, in the app
+/// data directory. Settings were kept here too before the backend owned them.
+pub const HISTORY_STORE_FILE_NAME: &str = "settings.json";
+
+/// Directory in the app cache directory that recordings are written to
+pub const RECORDINGS_DIR_NAME: &str = "recordings";
+
+/// Disk usage by category in bytes, as returned by `get_storage_usage`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    /// Installed catalog models and registered custom models
+    pub whisper_models: u64,
+    /// Sizes reported by OLLAMA, or None if it isn't reachable
+    pub ollama_models: Option<u64>,
+    pub history: u64,
+    /// Recordings in the app cache directory
+    pub cached_audio: u64,
+    pub logs: u64,
+    /// `.tmp` files left behind by interrupted downloads and writes
+    pub temp_files: Vec<TempFile>,
+    pub models_directory: String,
+    /// Free space on the models directory's volume
+    pub models_directory_free: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Result of `clean_temp_files`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempFileCleanup {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Rename a file that couldn't be loaded to `<name>.corrupt-<timestamp>`
+/// beside it, so whatever replaces it doesn't destroy what it held
+pub fn move_aside(path: &Path) -> std::io::Result<PathBuf> {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(
+        ".corrupt-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let corrupt_path = path.with_file_name(file_name);
+    std::fs::rename(path, &corrupt_path)?;
+    Ok(corrupt_path)
+}
+
+/// Fail with `InsufficientDiskSpace` if `dir`'s volume has less than
+/// `required` bytes free. If free space can't be determined the download
+/// goes ahead, as it did before this check existed.
+pub fn ensure_free_space(dir: &Path, required: u64) -> Result<(), AppError> {
+    match free_space(dir) {
+        Ok(available) if available < required => Err(AppError::InsufficientDiskSpace {
+            path: dir.to_string_lossy().to_string(),
+            required,
+            available,
+        }),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            log::warn!("Failed to check free space in {}: {}", dir.display(), e);
+            Ok(())
+        }
+    }
+}
+
+/// Bytes available to the app on the volume holding `path`, which need not
+/// exist yet
+pub fn free_space(path: &Path) -> std::io::Result<u64> {
+    let existing = path.ancestors().find(|p| p.exists()).unwrap_or(path);
+    volume_free_space(existing)
+}
+
+#[cfg(unix)]
+fn volume_free_space(path: &Path) -> std::io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: statvfs only writes to the struct it is given
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is NUL-terminated and outlives the call
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // Field widths differ between platforms; f_bavail excludes blocks
+    // reserved for root
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn volume_free_space(path: &Path) -> std::io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: wide is NUL-terminated and the unused out pointers may be null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+/// Total size of the files under `path`, not following symlinks. Missing
+/// or unreadable entries count as empty.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_file() {
+        return metadata.len();
+    }
+    if !metadata.is_dir() {
+        return 0;
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| dir_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// `.tmp` files directly inside `dirs`, except those in `in_use`
+pub fn find_temp_files(dirs: &[PathBuf], in_use: &[PathBuf]) -> Vec<TempFile> {
+    let mut seen = HashSet::new();
+    let mut temp_files = Vec::new();
+    for dir in dirs {
+        if !seen.insert(dir) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let is_temp = path.extension().is_some_and(|ext| ext == "tmp");
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if is_temp && metadata.is_file() && !in_use.contains(&path) {
+                temp_files.push(TempFile {
+                    path: path.to_string_lossy().to_string(),
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+    temp_files
+}
+
+/// Where OLLAMA keeps models on this machine, for a server running locally
+pub fn ollama_models_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS") {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .unwrap_or_default();
+    PathBuf::from(home).join(".ollama").join("models")
+}
+
+/// The URL points at this machine, so its disk is ours to check
+pub fn is_local_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let host = url.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("storage-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn refuses_downloads_larger_than_free_space() {
+        let dir = temp_dir();
+
+        assert!(ensure_free_space(&dir, 0).is_ok());
+        assert!(matches!(
+            ensure_free_space(&dir, u64::MAX),
+            Err(AppError::InsufficientDiskSpace {
+                required: u64::MAX,
+                ..
+            })
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn measures_free_space_of_missing_directories() {
+        let dir = temp_dir();
+        let missing = dir.join("not").join("created");
+
+        assert_eq!(free_space(&missing).unwrap(), free_space(&dir).unwrap());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn sums_file_sizes_recursively() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("a.bin"), [0; 10]).unwrap();
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested").join("b.bin"), [0; 5]).unwrap();
+
+        assert_eq!(dir_size(&dir), 15);
+        assert_eq!(dir_size(&dir.join("a.bin")), 10);
+        assert_eq!(dir_size(&dir.join("missing")), 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn finds_temp_files_not_in_use() {
+        let dir = temp_dir();
+        let orphan = dir.join("ggml-base.bin.tmp");
+        let downloading = dir.join("ggml-small.bin.tmp");
+        std::fs::write(&orphan, [0; 3]).unwrap();
+        std::fs::write(&downloading, [0; 4]).unwrap();
+        std::fs::write(dir.join("ggml-base.bin"), [0; 5]).unwrap();
+        // Only files directly inside the directories are considered
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested").join("other.tmp"), [0; 6]).unwrap();
+
+        // Listing a directory twice doesn't report its files twice
+        let temp_files = find_temp_files(&[dir.clone(), dir.clone()], &[downloading]);
+
+        assert_eq!(temp_files.len(), 1);
+        assert_eq!(temp_files[0].path, orphan.to_string_lossy());
+        assert_eq!(temp_files[0].size, 3);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn recognizes_local_urls() {
+        assert!(is_local_url("http://localhost:11434"));
+        assert!(is_local_url("http://127.0.0.1:11434/api/pull"));
+        assert!(is_local_url("http://[::1]:11434"));
+
+        assert!(!is_local_url("http://192.168.1.20:11434"));
+        assert!(!is_local_url("https://ollama.example.com"));
+        assert!(!is_local_url("not a url"));
+    }
+}
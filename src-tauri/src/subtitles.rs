@@ -0,0 +1,531 @@
+use serde::{Deserialize, Serialize};
+
+use crate::whisper::Segment;
+
+/// Shortest time a cue stays on screen, unless the next cue starts sooner
+const MIN_CUE_MS: u64 = 700;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Vtt,
+}
+
+/// Output format and the rules cues are re-flowed by
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleOptions {
+    pub format: SubtitleFormat,
+    /// Characters per line
+    pub max_line_chars: usize,
+    /// Lines per cue
+    pub max_lines: usize,
+    /// Longest time a cue stays on screen
+    pub max_cue_ms: u64,
+    /// Shift applied to every cue, may be negative. Cues pushed before zero
+    /// are clipped or dropped.
+    pub offset_ms: i64,
+}
+
+impl Default for SubtitleOptions {
+    fn default() -> Self {
+        Self {
+            format: SubtitleFormat::default(),
+            max_line_chars: 42,
+            max_lines: 2,
+            max_cue_ms: 7000,
+            offset_ms: 0,
+        }
+    }
+}
+
+impl SubtitleOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10..=200).contains(&self.max_line_chars) {
+            return Err("Maximum line length must be between 10 and 200 characters".to_string());
+        }
+        if !(1..=4).contains(&self.max_lines) {
+            return Err("Lines per cue must be between 1 and 4".to_string());
+        }
+        if !(1000..=60_000).contains(&self.max_cue_ms) {
+            return Err("Maximum cue duration must be between 1 and 60 seconds".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub lines: Vec<String>,
+}
+
+/// A word or part of one with its timing inside a segment
+struct Word {
+    text: String,
+    /// Joined to the previous word without a space (a split-up long word or
+    /// a run of text without spaces)
+    attached: bool,
+    start_ms: u64,
+    end_ms: u64,
+}
+
+/// Split the segments into cues that respect `options`. Cues never span two
+/// segments, so whisper's own sentence breaks are kept. Each cue is shown for
+/// at least `MIN_CUE_MS` and ends before the next one starts.
+pub fn build_cues(segments: &[Segment], options: &SubtitleOptions) -> Vec<Cue> {
+    let mut cues = Vec::new();
+
+    for segment in segments {
+        let mut current: Option<Cue> = None;
+        for word in timed_words(segment, options.max_line_chars) {
+            if let Some(cue) = current.as_mut() {
+                if try_append(cue, &word, options) {
+                    continue;
+                }
+                cues.extend(current.take());
+            }
+            current = Some(Cue {
+                start_ms: word.start_ms,
+                end_ms: word.end_ms,
+                lines: vec![word.text],
+            });
+        }
+        cues.extend(current);
+    }
+
+    let next_starts: Vec<Option<u64>> = cues
+        .iter()
+        .skip(1)
+        .map(|cue| Some(cue.start_ms))
+        .chain([None])
+        .collect();
+    cues.into_iter()
+        .zip(next_starts)
+        .filter_map(|(mut cue, next_start)| {
+            // A single word can outlast the limit, e.g. when whisper stretches a
+            // segment over a pause
+            cue.end_ms = cue.end_ms.min(cue.start_ms + options.max_cue_ms);
+            cue.end_ms = cue.end_ms.max(cue.start_ms + MIN_CUE_MS);
+            // Whisper's segments can overlap slightly
+            if let Some(next_start) = next_start.filter(|next| *next > cue.start_ms) {
+                cue.end_ms = cue.end_ms.min(next_start);
+            }
+            shift(cue, options.offset_ms)
+        })
+        .collect()
+}
+
+/// Add `word` to the cue if it still fits the line, line count and duration
+/// limits
+fn try_append(cue: &mut Cue, word: &Word, options: &SubtitleOptions) -> bool {
+    if word.end_ms.saturating_sub(cue.start_ms) > options.max_cue_ms {
+        return false;
+    }
+
+    let separator = if word.attached { "" } else { " " };
+    let last = cue.lines.last_mut().expect("cues start with a line");
+    if last.chars().count() + separator.len() + word.text.chars().count() <= options.max_line_chars
+    {
+        last.push_str(separator);
+        last.push_str(&word.text);
+    } else if cue.lines.len() < options.max_lines {
+        cue.lines.push(word.text.clone());
+    } else {
+        return false;
+    }
+
+    cue.end_ms = cue.end_ms.max(word.end_ms);
+    true
+}
+
+/// Break a segment into words no longer than a line, timed by whisper's
+/// token timestamps. When the tokens carry no times or don't spell out the
+/// segment text, each word gets a share of the segment proportional to its
+/// length instead.
+fn timed_words(segment: &Segment, max_chars: usize) -> Vec<Word> {
+    token_words(segment)
+        .unwrap_or_else(|| proportional_words(segment))
+        .into_iter()
+        .flat_map(|word| split_word(word, max_chars))
+        .collect()
+}
+
+/// Words assembled from whisper's tokens. A token starting with whitespace
+/// begins a new word; others continue the current one.
+fn token_words(segment: &Segment) -> Option<Vec<Word>> {
+    let mut words: Vec<Word> = Vec::new();
+    for token in &segment.tokens {
+        let (start_ms, end_ms) = (token.start_ms?, token.end_ms?);
+        let start_ms = start_ms.clamp(segment.start_ms, segment.end_ms);
+        let end_ms = end_ms.clamp(start_ms, segment.end_ms);
+        match words.last_mut() {
+            Some(word) if !token.text.starts_with(char::is_whitespace) => {
+                word.text.push_str(token.text.trim_end());
+                word.end_ms = end_ms;
+            }
+            _ if token.text.trim().is_empty() => {}
+            _ => words.push(Word {
+                text: token.text.trim().to_string(),
+                attached: false,
+                start_ms,
+                end_ms,
+            }),
+        }
+    }
+
+    let matches_text = words
+        .iter()
+        .map(|word| word.text.as_str())
+        .eq(segment.text.split_whitespace());
+    matches_text.then_some(words)
+}
+
+fn proportional_words(segment: &Segment) -> Vec<Word> {
+    let total_chars: usize = segment
+        .text
+        .split_whitespace()
+        .map(|word| word.chars().count())
+        .sum();
+    let duration = segment.end_ms.saturating_sub(segment.start_ms);
+    let at = |chars: usize| {
+        segment.start_ms + (duration as f64 * chars as f64 / total_chars.max(1) as f64) as u64
+    };
+
+    let mut elapsed = 0;
+    segment
+        .text
+        .split_whitespace()
+        .map(|text| {
+            let start_ms = at(elapsed);
+            elapsed += text.chars().count();
+            Word {
+                text: text.to_string(),
+                attached: false,
+                start_ms,
+                end_ms: at(elapsed),
+            }
+        })
+        .collect()
+}
+
+/// Split a word longer than a line into line-sized pieces, sharing its time
+/// between them by length
+fn split_word(word: Word, max_chars: usize) -> Vec<Word> {
+    let chars: Vec<char> = word.text.chars().collect();
+    if chars.len() <= max_chars {
+        return vec![word];
+    }
+
+    let duration = word.end_ms.saturating_sub(word.start_ms);
+    let at = |chars_before: usize| {
+        word.start_ms + (duration as f64 * chars_before as f64 / chars.len() as f64) as u64
+    };
+    chars
+        .chunks(max_chars)
+        .enumerate()
+        .map(|(i, chunk)| Word {
+            text: chunk.iter().collect(),
+            attached: word.attached || i > 0,
+            start_ms: at(i * max_chars),
+            end_ms: at(i * max_chars + chunk.len()),
+        })
+        .collect()
+}
+
+/// Apply the offset, dropping cues that end up entirely before zero
+fn shift(cue: Cue, offset_ms: i64) -> Option<Cue> {
+    let start = cue.start_ms as i64 + offset_ms;
+    let end = cue.end_ms as i64 + offset_ms;
+    if end <= 0 || end <= start {
+        return None;
+    }
+    Some(Cue {
+        start_ms: start.max(0) as u64,
+        end_ms: end as u64,
+        lines: cue.lines,
+    })
+}
+
+/// Render cues as an SRT or WebVTT document
+pub fn render(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if let SubtitleFormat::Vtt = format {
+        out.push_str("WEBVTT\n\n");
+    }
+
+    for (i, cue) in cues.iter().enumerate() {
+        match format {
+            SubtitleFormat::Srt => {
+                out.push_str(&format!(
+                    "{}\n{} --> {}\n",
+                    i + 1,
+                    timestamp(cue.start_ms, ','),
+                    timestamp(cue.end_ms, ',')
+                ));
+                for line in &cue.lines {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            SubtitleFormat::Vtt => {
+                out.push_str(&format!(
+                    "{} --> {}\n",
+                    timestamp(cue.start_ms, '.'),
+                    timestamp(cue.end_ms, '.')
+                ));
+                for line in &cue.lines {
+                    out.push_str(&escape_vtt(line));
+                    out.push('\n');
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// `HH:MM:SS,mmm` for SRT, `HH:MM:SS.mmm` for WebVTT
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// WebVTT cue text is HTML-like; whisper output can contain `<`, `&` or `-->`
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::Token;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            tokens: Vec::new(),
+            no_speech_prob: None,
+        }
+    }
+
+    /// Segment whose text is spelled out by tokens with their times
+    fn timed_segment(start_ms: u64, end_ms: u64, tokens: &[(&str, u64, u64)]) -> Segment {
+        let text: String = tokens.iter().map(|(text, _, _)| *text).collect();
+        Segment {
+            tokens: tokens
+                .iter()
+                .map(|(text, start, end)| Token {
+                    text: text.to_string(),
+                    probability: 0.9,
+                    start_ms: Some(*start),
+                    end_ms: Some(*end),
+                })
+                .collect(),
+            ..segment(start_ms, end_ms, text.trim())
+        }
+    }
+
+    fn options(max_line_chars: usize, max_lines: usize) -> SubtitleOptions {
+        SubtitleOptions {
+            max_line_chars,
+            max_lines,
+            ..SubtitleOptions::default()
+        }
+    }
+
+    #[test]
+    fn wraps_text_within_line_and_line_count_limits() {
+        let text = "the quick brown fox jumps over the lazy dog and keeps running far away";
+        let cues = build_cues(&[segment(0, 6000, text)], &options(12, 2));
+
+        assert!(cues.len() > 1);
+        for cue in &cues {
+            assert!(cue.lines.len() <= 2, "{:?}", cue.lines);
+            assert!(
+                cue.lines.iter().all(|l| l.chars().count() <= 12),
+                "{:?}",
+                cue.lines
+            );
+        }
+        let words: Vec<&str> = cues
+            .iter()
+            .flat_map(|cue| cue.lines.iter().flat_map(|l| l.split(' ')))
+            .collect();
+        assert_eq!(words, text.split(' ').collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn splits_words_longer_than_a_line() {
+        let cues = build_cues(
+            &[segment(0, 3000, "Donaudampfschifffahrt")],
+            &options(10, 2),
+        );
+
+        assert_eq!(cues[0].lines, vec!["Donaudampf", "schifffahr"]);
+        assert_eq!(cues[1].lines, vec!["t"]);
+    }
+
+    #[test]
+    fn caps_cue_duration() {
+        let segments = [segment(
+            0,
+            20_000,
+            "a few words stretched over a long pause",
+        )];
+        let options = SubtitleOptions {
+            max_cue_ms: 3000,
+            ..SubtitleOptions::default()
+        };
+
+        let cues = build_cues(&segments, &options);
+
+        assert!(cues.iter().all(|cue| cue.end_ms - cue.start_ms <= 3000));
+    }
+
+    #[test]
+    fn short_cues_last_the_minimum_duration() {
+        let cues = build_cues(&[segment(1000, 1100, "Hi")], &SubtitleOptions::default());
+
+        assert_eq!(
+            (cues[0].start_ms, cues[0].end_ms),
+            (1000, 1000 + MIN_CUE_MS)
+        );
+    }
+
+    #[test]
+    fn minimum_duration_stops_at_the_next_cue() {
+        let segments = [segment(1000, 1100, "Hi"), segment(1300, 2500, "there")];
+
+        let cues = build_cues(&segments, &SubtitleOptions::default());
+
+        assert_eq!(cues[0].end_ms, 1300);
+        assert_eq!(cues[1].start_ms, 1300);
+    }
+
+    #[test]
+    fn overlapping_segments_give_consecutive_cues() {
+        let segments = [
+            segment(0, 3000, "first part"),
+            segment(2500, 5000, "second part"),
+        ];
+
+        let cues = build_cues(&segments, &SubtitleOptions::default());
+
+        assert_eq!(cues.len(), 2);
+        assert!(cues[0].end_ms <= cues[1].start_ms);
+    }
+
+    #[test]
+    fn negative_offset_clips_and_drops_cues() {
+        let segments = [segment(0, 1000, "gone"), segment(2000, 4000, "kept")];
+        let options = SubtitleOptions {
+            offset_ms: -2500,
+            ..SubtitleOptions::default()
+        };
+
+        let cues = build_cues(&segments, &options);
+
+        assert_eq!(cues.len(), 1);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (0, 1500));
+        assert_eq!(cues[0].lines, vec!["kept"]);
+    }
+
+    #[test]
+    fn words_take_their_times_from_tokens() {
+        let segment = timed_segment(
+            0,
+            5000,
+            &[
+                (" Hello", 0, 400),
+                (" wor", 3000, 3300),
+                ("ld", 3300, 3600),
+                (".", 3600, 3700),
+            ],
+        );
+
+        let words = timed_words(&segment, 42);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(
+            (words[0].text.as_str(), words[0].start_ms, words[0].end_ms),
+            ("Hello", 0, 400)
+        );
+        assert_eq!(
+            (words[1].text.as_str(), words[1].start_ms, words[1].end_ms),
+            ("world.", 3000, 3700)
+        );
+    }
+
+    #[test]
+    fn words_without_token_times_share_the_segment() {
+        let mut segment = timed_segment(0, 5000, &[(" Hello", 0, 400), (" world", 3000, 3600)]);
+        segment.tokens[1].start_ms = None;
+
+        let words = timed_words(&segment, 42);
+
+        // Five characters each, so the second word starts half-way through
+        assert_eq!((words[1].start_ms, words[1].end_ms), (2500, 5000));
+    }
+
+    #[test]
+    fn tokens_that_do_not_spell_the_text_are_ignored() {
+        let mut segment = timed_segment(0, 4000, &[(" one", 0, 100), (" two", 100, 200)]);
+        segment.text = "one two three four".to_string();
+
+        let words = timed_words(&segment, 42);
+
+        assert_eq!(words.len(), 4);
+        assert_eq!(words[3].end_ms, 4000);
+    }
+
+    #[test]
+    fn renders_srt() {
+        let cues = vec![
+            Cue {
+                start_ms: 0,
+                end_ms: 1500,
+                lines: vec!["Hello".to_string(), "world".to_string()],
+            },
+            Cue {
+                start_ms: 3_723_456,
+                end_ms: 3_725_000,
+                lines: vec!["a < b".to_string()],
+            },
+        ];
+
+        assert_eq!(
+            render(&cues, SubtitleFormat::Srt),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\nworld\n\n\
+             2\n01:02:03,456 --> 01:02:05,000\na < b\n\n"
+        );
+    }
+
+    #[test]
+    fn renders_and_escapes_vtt() {
+        let cues = vec![Cue {
+            start_ms: 61_001,
+            end_ms: 62_000,
+            lines: vec!["Q&A --> <b>now</b>".to_string()],
+        }];
+
+        assert_eq!(
+            render(&cues, SubtitleFormat::Vtt),
+            "WEBVTT\n\n00:01:01.001 --> 00:01:02.000\nQ&amp;A --&gt; &lt;b&gt;now&lt;/b&gt;\n\n"
+        );
+    }
+}
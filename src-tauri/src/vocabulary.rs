@@ -0,0 +1,229 @@
+use crate::settings::Replacement;
+
+/// whisper.cpp keeps at most half its 448-token text context of prompt, and
+/// drops the start of a longer one
+const PROMPT_TOKEN_LIMIT: usize = 224;
+
+/// Join the vocabulary into an initial prompt for `--prompt`. Terms are kept
+/// in order until the estimated token count would pass whisper's limit, so the
+/// first terms in the list are the ones that always make it in.
+pub fn build_prompt(terms: &[String]) -> Option<String> {
+    let mut prompt = String::new();
+    let mut tokens = 0;
+    let mut included = 0;
+    let terms: Vec<&str> = terms
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    for term in &terms {
+        // No tokenizer here, so estimate conservatively: names and jargon
+        // split into short pieces, and the ", " separator costs a token
+        let cost = term.chars().count().div_ceil(3) + 1;
+        if tokens + cost > PROMPT_TOKEN_LIMIT {
+            break;
+        }
+        if !prompt.is_empty() {
+            prompt.push_str(", ");
+        }
+        prompt.push_str(term);
+        tokens += cost;
+        included += 1;
+    }
+
+    if included < terms.len() {
+        log::warn!(
+            "Vocabulary prompt holds {} of {} terms; the rest exceed whisper's prompt limit",
+            included,
+            terms.len()
+        );
+    }
+    (!prompt.is_empty()).then(|| prompt + ".")
+}
+
+/// Apply the replacement dictionary in one left-to-right pass. Matches are
+/// whole words or phrases, case-insensitive unless `from` contains capitals.
+/// Longer entries win over shorter ones at the same position, and replaced
+/// text is not matched again, so one entry never rewrites another's output.
+pub fn apply_replacements(text: &str, replacements: &[Replacement]) -> String {
+    let mut entries: Vec<(Vec<char>, &Replacement)> = replacements
+        .iter()
+        .filter(|r| !r.from.trim().is_empty())
+        .map(|r| (r.from.chars().collect(), r))
+        .collect();
+    if entries.is_empty() {
+        return text.to_string();
+    }
+    entries.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let at_word_start = i == 0 || !is_word_char(chars[i - 1]);
+        let found = at_word_start
+            .then(|| {
+                entries.iter().find(|(from, replacement)| {
+                    matches_at(&chars, i, from, is_case_sensitive(&replacement.from))
+                })
+            })
+            .flatten();
+
+        match found {
+            Some((from, replacement)) => {
+                let matched: String = chars[i..i + from.len()].iter().collect();
+                out.push_str(&match_case(&replacement.to, &matched));
+                i += from.len();
+            }
+            None => {
+                out.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+/// Lowercase entries match any capitalisation; entries with capitals only
+/// match exactly
+fn is_case_sensitive(from: &str) -> bool {
+    from.chars().any(char::is_uppercase)
+}
+
+/// `from` occurs at `start` and ends on a word boundary
+fn matches_at(chars: &[char], start: usize, from: &[char], case_sensitive: bool) -> bool {
+    let end = start + from.len();
+    if end > chars.len() || chars.get(end).is_some_and(|&c| is_word_char(c)) {
+        return false;
+    }
+    chars[start..end].iter().zip(from).all(|(&a, &b)| {
+        if case_sensitive {
+            a == b
+        } else {
+            a == b || a.to_lowercase().eq(b.to_lowercase())
+        }
+    })
+}
+
+/// A replacement written in lowercase follows the capitalisation of the text
+/// it replaces, e.g. at the start of a sentence. One with capitals of its own
+/// is a spelling ("iPhone", "McDonald") and is used as written.
+fn match_case(to: &str, matched: &str) -> String {
+    if to.chars().any(char::is_uppercase) {
+        return to.to_string();
+    }
+
+    let letters: Vec<char> = matched.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return to.to_uppercase();
+    }
+    if letters.first().is_some_and(|c| c.is_uppercase()) {
+        let mut chars = to.chars();
+        if let Some(first) = chars.next() {
+            return first.to_uppercase().chain(chars).collect();
+        }
+    }
+    to.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(text: &str, entries: &[(&str, &str)]) -> String {
+        let replacements: Vec<Replacement> = entries
+            .iter()
+            .map(|(from, to)| Replacement {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+            .collect();
+        apply_replacements(text, &replacements)
+    }
+
+    #[test]
+    fn replaces_whole_words_only() {
+        assert_eq!(
+            replace("cat concatenate cats cat_x cat.", &[("cat", "dog")]),
+            "dog concatenate cats cat_x dog."
+        );
+        assert_eq!(replace("don't", &[("don", "Don")]), "don't");
+    }
+
+    #[test]
+    fn lowercase_entries_follow_the_matched_case() {
+        assert_eq!(
+            replace(
+                "kubernetes, Kubernetes and KUBERNETES",
+                &[("kubernetes", "k8s")]
+            ),
+            "k8s, K8s and K8S"
+        );
+    }
+
+    #[test]
+    fn entries_with_capitals_are_exact() {
+        // `from` with capitals only matches as written
+        assert_eq!(replace("go Go GO", &[("Go", "Golang")]), "go Golang GO");
+        // `to` with capitals is a spelling and kept as written
+        assert_eq!(
+            replace("Iphone and IPHONE", &[("iphone", "iPhone")]),
+            "iPhone and iPhone"
+        );
+    }
+
+    #[test]
+    fn longer_entries_win_at_the_same_position() {
+        let entries = [("york", "Yorkshire"), ("new york", "NYC")];
+
+        assert_eq!(replace("new york and york", &entries), "NYC and Yorkshire");
+    }
+
+    #[test]
+    fn replaced_text_is_not_matched_again() {
+        let entries = [("a", "b"), ("b", "c")];
+
+        assert_eq!(replace("a b", &entries), "b c");
+    }
+
+    #[test]
+    fn blank_entries_are_ignored() {
+        assert_eq!(replace("some text", &[("  ", "x")]), "some text");
+    }
+
+    #[test]
+    fn prompt_joins_terms_in_order() {
+        let terms = vec![
+            " Kubernetes ".to_string(),
+            String::new(),
+            "PostgreSQL".to_string(),
+        ];
+
+        assert_eq!(
+            build_prompt(&terms).as_deref(),
+            Some("Kubernetes, PostgreSQL.")
+        );
+        assert_eq!(build_prompt(&[]), None);
+        assert_eq!(build_prompt(&["  ".to_string()]), None);
+    }
+
+    #[test]
+    fn prompt_stops_at_the_token_limit() {
+        // 30 characters cost 11 estimated tokens each, so 20 fit in 224
+        let terms: Vec<String> = (0..25).map(|i| format!("{:0>30}", i)).collect();
+
+        let prompt = build_prompt(&terms).unwrap();
+
+        let kept: Vec<&str> = prompt.trim_end_matches('.').split(", ").collect();
+        assert_eq!(kept.len(), 20);
+        assert_eq!(
+            kept,
+            terms[..20].iter().map(String::as_str).collect::<Vec<_>>()
+        );
+    }
+}
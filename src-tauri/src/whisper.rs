@@ -0,0 +1,593 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{oneshot, Semaphore, SemaphorePermit};
+
+use crate::error::AppError;
+use crate::settings::{WhisperOptions, MAX_PARALLEL_JOBS};
+
+/// Payload of the `transcription-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub job_id: String,
+    /// 0 – 100, in the steps whisper reports
+    pub percent: u8,
+}
+
+/// How many transcriptions decode and run whisper at the same time, however
+/// they were started. The queue's `parallelJobs` setting stays within it.
+const MAX_CONCURRENT_TRANSCRIPTIONS: usize = MAX_PARALLEL_JOBS as usize;
+
+/// Transcriptions in flight, keyed by job id. Held in Tauri managed state so
+/// `cancel_transcription` can reach them.
+pub struct TranscriptionJobs {
+    running: Mutex<HashMap<String, oneshot::Sender<()>>>,
+    /// Bounds the number of jobs doing actual work
+    slots: Semaphore,
+}
+
+impl Default for TranscriptionJobs {
+    fn default() -> Self {
+        Self {
+            running: Mutex::new(HashMap::new()),
+            slots: Semaphore::new(MAX_CONCURRENT_TRANSCRIPTIONS),
+        }
+    }
+}
+
+impl TranscriptionJobs {
+    /// Register a job. It is removed again when the returned handle drops.
+    pub fn register(&self, job_id: &str) -> Result<Job<'_>, AppError> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.contains_key(job_id) {
+            return Err(AppError::InvalidInput(format!(
+                "A transcription with job id '{}' is already running",
+                job_id
+            )));
+        }
+        let (cancel, cancelled) = oneshot::channel();
+        running.insert(job_id.to_string(), cancel);
+        Ok(Job {
+            jobs: self,
+            id: job_id.to_string(),
+            cancelled,
+        })
+    }
+
+    /// Signal a job to stop. Returns false if no such job is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        let cancel = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(job_id);
+        match cancel {
+            Some(cancel) => {
+                let _ = cancel.send(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A registered transcription
+pub struct Job<'a> {
+    jobs: &'a TranscriptionJobs,
+    pub id: String,
+    /// Resolves with `Ok` once the job is cancelled
+    pub cancelled: oneshot::Receiver<()>,
+}
+
+impl<'a> Job<'a> {
+    /// Wait until fewer than `MAX_CONCURRENT_TRANSCRIPTIONS` jobs are working.
+    /// Fails with `Cancelled` if the job is cancelled while waiting.
+    pub async fn wait_for_slot(&mut self) -> Result<SemaphorePermit<'a>, AppError> {
+        let jobs = self.jobs;
+        tokio::select! {
+            permit = jobs.slots.acquire() => {
+                permit.map_err(|e| AppError::Internal(format!("Transcription slots closed: {}", e)))
+            }
+            Ok(()) = &mut self.cancelled => Err(AppError::Cancelled),
+        }
+    }
+}
+
+impl Drop for Job<'_> {
+    fn drop(&mut self) {
+        self.jobs
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+    }
+}
+
+/// Captured output of a successful whisper run
+pub struct WhisperRun {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run whisper.cpp for `job`, turning its `--print-progress` lines into
+/// `transcription-progress` events. The process is killed if the job is
+/// cancelled.
+pub async fn run(
+    app: &AppHandle,
+    mut cmd: Command,
+    job: &mut Job<'_>,
+) -> Result<WhisperRun, AppError> {
+    if job.cancelled.try_recv().is_ok() {
+        return Err(AppError::Cancelled);
+    }
+
+    cmd.arg("--print-progress")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let cmd_str = format!("{:?}", cmd.as_std());
+    log::info!("Running whisper command: {}", cmd_str);
+
+    let failed = |exit_code: Option<i32>, stderr: String, stdout: String| AppError::WhisperFailed {
+        exit_code,
+        command: cmd_str.clone(),
+        stderr,
+        stdout,
+    };
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| failed(None, e.to_string(), String::new()))?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    let job_id = job.id.clone();
+    let read_stdout = async {
+        let mut buf = Vec::new();
+        BufReader::new(stdout).read_to_end(&mut buf).await?;
+        Ok::<_, std::io::Error>(String::from_utf8_lossy(&buf).to_string())
+    };
+
+    // Progress lines are turned into events; everything else is kept for
+    // language detection and error reports
+    let read_stderr = async {
+        let mut lines = BufReader::new(stderr).split(b'\n');
+        let mut log = String::new();
+        while let Some(line) = lines.next_segment().await? {
+            let line = String::from_utf8_lossy(&line);
+            match parse_progress(&line) {
+                Some(percent) => {
+                    let _ = app.emit(
+                        "transcription-progress",
+                        TranscriptionProgress {
+                            job_id: job_id.clone(),
+                            percent,
+                        },
+                    );
+                }
+                None => {
+                    log.push_str(&line);
+                    log.push('\n');
+                }
+            }
+        }
+        Ok::<_, std::io::Error>(log)
+    };
+
+    let outcome = tokio::select! {
+        result = async { tokio::try_join!(read_stdout, read_stderr, child.wait()) } => Some(result),
+        Ok(()) = &mut job.cancelled => None,
+    };
+
+    let (stdout, stderr, status) = match outcome {
+        Some(result) => result.map_err(|e| failed(None, e.to_string(), String::new()))?,
+        None => {
+            log::info!("Transcription {} cancelled, stopping whisper", job.id);
+            if let Err(e) = child.kill().await {
+                log::warn!("Failed to stop whisper: {}", e);
+            }
+            return Err(AppError::Cancelled);
+        }
+    };
+
+    log::info!("Whisper stdout: {}", stdout);
+    log::info!("Whisper stderr: {}", stderr);
+    log::info!("Whisper exit code: {:?}", status.code());
+
+    if !status.success() {
+        return Err(failed(status.code(), stderr, stdout));
+    }
+
+    Ok(WhisperRun { stdout, stderr })
+}
+
+/// Pass the decoding parameters that are set on to whisper.cpp
+pub fn apply_options(cmd: &mut Command, options: &WhisperOptions) {
+    let mut flag = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            cmd.arg(name).arg(value);
+        }
+    };
+    flag("-t", options.threads.map(|v| v.to_string()));
+    flag("-p", options.processors.map(|v| v.to_string()));
+    flag("-bs", options.beam_size.map(|v| v.to_string()));
+    flag("-bo", options.best_of.map(|v| v.to_string()));
+    flag("-tp", options.temperature.map(|v| v.to_string()));
+    flag("-tpi", options.temperature_increment.map(|v| v.to_string()));
+    flag("-ml", options.max_segment_length.map(|v| v.to_string()));
+    flag("-et", options.entropy_threshold.map(|v| v.to_string()));
+    flag("-lpt", options.logprob_threshold.map(|v| v.to_string()));
+}
+
+/// Parse `whisper_print_progress_callback: progress =  40%`
+fn parse_progress(line: &str) -> Option<u8> {
+    let rest = line.split("progress =").nth(1)?;
+    let percent = rest.trim().strip_suffix('%')?.trim().parse().ok()?;
+    (percent <= 100).then_some(percent)
+}
+
+/// A timed stretch of the transcript, as whisper segmented it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    /// Start in milliseconds from the beginning of the original audio
+    pub start_ms: u64,
+    /// End in milliseconds from the beginning of the original audio
+    pub end_ms: u64,
+    pub text: String,
+    /// Text tokens with whisper's probability for each, special tokens omitted
+    pub tokens: Vec<Token>,
+    /// Probability that the segment holds no speech. Only reported by recent
+    /// whisper.cpp builds.
+    pub no_speech_prob: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    pub text: String,
+    /// 0.0 – 1.0
+    pub probability: f64,
+    /// Start in milliseconds from the beginning of the original audio, when
+    /// whisper reported token times
+    pub start_ms: Option<u64>,
+    pub end_ms: Option<u64>,
+}
+
+/// What whisper.cpp wrote to its `-ojf` JSON file
+pub struct WhisperOutput {
+    /// Language whisper transcribed in, detected or requested
+    pub language: Option<String>,
+    /// The full transcript
+    pub text: String,
+    pub segments: Vec<Segment>,
+}
+
+#[derive(Deserialize)]
+struct RawOutput {
+    #[serde(default)]
+    result: Option<RawResult>,
+    #[serde(default)]
+    transcription: Vec<RawSegment>,
+}
+
+#[derive(Deserialize)]
+struct RawResult {
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSegment {
+    offsets: RawOffsets,
+    text: String,
+    #[serde(default)]
+    tokens: Vec<RawToken>,
+    #[serde(default, alias = "no_speech_probability")]
+    no_speech_prob: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawOffsets {
+    from: u64,
+    to: u64,
+}
+
+#[derive(Deserialize)]
+struct RawToken {
+    text: String,
+    p: f64,
+    #[serde(default)]
+    offsets: Option<RawOffsets>,
+}
+
+/// Output location passed to whisper.cpp with `-of`. The JSON file whisper
+/// writes next to it is removed on drop.
+pub struct OutputFile {
+    base: PathBuf,
+}
+
+impl OutputFile {
+    pub fn new() -> Self {
+        Self {
+            base: std::env::temp_dir().join(format!(
+                "voice-intelligence-whisper-{}",
+                uuid::Uuid::new_v4()
+            )),
+        }
+    }
+
+    /// Value for `-of`; whisper appends the extension itself
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    pub fn json_path(&self) -> PathBuf {
+        let mut path = self.base.clone().into_os_string();
+        path.push(".json");
+        PathBuf::from(path)
+    }
+
+    /// Read and parse the JSON whisper wrote. `offset_ms` is added to every
+    /// timestamp, e.g. to account for silence trimmed from the start.
+    pub fn read(&self, offset_ms: u64) -> Result<WhisperOutput, AppError> {
+        let path = self.json_path();
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io("Failed to read whisper's JSON output", e))?;
+        parse_json_output(&json, offset_ms)
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        let path = self.json_path();
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove whisper output {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Parse whisper.cpp's `-ojf` JSON
+pub fn parse_json_output(json: &str, offset_ms: u64) -> Result<WhisperOutput, AppError> {
+    let raw: RawOutput = serde_json::from_str(json)
+        .map_err(|e| AppError::Internal(format!("Unreadable whisper JSON output: {}", e)))?;
+
+    // Segment texts carry their own leading space where the language uses
+    // one, so they are joined as-is. Blank segments would double it.
+    let text = raw
+        .transcription
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .filter(|text| !text.trim().is_empty())
+        .collect::<String>()
+        .trim()
+        .to_string();
+
+    let segments = raw
+        .transcription
+        .into_iter()
+        .map(|segment| Segment {
+            start_ms: segment.offsets.from + offset_ms,
+            end_ms: segment.offsets.to + offset_ms,
+            text: segment.text.trim().to_string(),
+            tokens: segment
+                .tokens
+                .into_iter()
+                .filter(|token| !is_special_token(&token.text))
+                .map(|token| Token {
+                    text: token.text,
+                    probability: token.p,
+                    start_ms: token.offsets.as_ref().map(|o| o.from + offset_ms),
+                    end_ms: token.offsets.as_ref().map(|o| o.to + offset_ms),
+                })
+                .collect(),
+            no_speech_prob: segment.no_speech_prob,
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+
+    Ok(WhisperOutput {
+        language: raw.result.and_then(|r| r.language),
+        text,
+        segments,
+    })
+}
+
+/// Timestamp and control tokens such as `[_BEG_]`, `[_TT_150]` or `<|en|>`
+fn is_special_token(text: &str) -> bool {
+    (text.starts_with("[_") && text.ends_with(']'))
+        || (text.starts_with("<|") && text.ends_with("|>"))
+}
+
+/// Parse whisper.cpp's `auto-detected language: de (p = 0.973421)` log line
+pub fn parse_detected_language(output: &str) -> Option<(String, f64)> {
+    let rest = output.split("auto-detected language:").nth(1)?;
+    let (code, rest) = rest.trim_start().split_once(char::is_whitespace)?;
+    let probability = rest
+        .trim_start()
+        .strip_prefix("(p =")?
+        .split(')')
+        .next()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some((code.to_string(), probability))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// stderr of `whisper-cli -l auto`, trimmed to the lines around detection
+    const AUTO_STDERR: &str = "\
+whisper_init_from_file_with_params_no_state: loading model from 'ggml-base.bin'
+whisper_model_load: n_vocab       = 51865
+main: processing 'audio.wav' (160000 samples, 10.0 sec), 4 threads, 1 processors, 5 beams + best of 5, lang = auto, task = transcribe, timestamps = 1 ...
+
+whisper_full_with_state: auto-detected language: de (p = 0.973421)
+";
+
+    #[test]
+    fn parses_detected_language() {
+        let cases: &[(&str, Option<(&str, f64)>)] = &[
+            (AUTO_STDERR, Some(("de", 0.973421))),
+            (
+                "whisper_full_with_state: auto-detected language: en (p = 0.512000)",
+                Some(("en", 0.512)),
+            ),
+            (
+                "whisper_full_with_state: auto-detected language: zh (p = 1.000000)\n",
+                Some(("zh", 1.0)),
+            ),
+            // Only the request for detection, no result
+            (
+                "main: processing 'audio.wav' (16000 samples, 1.0 sec), 4 threads, 1 processors, \
+                 5 beams + best of 5, lang = auto, task = transcribe, timestamps = 1 ...",
+                None,
+            ),
+            // Missing or unreadable probability
+            ("whisper_full_with_state: auto-detected language: fr", None),
+            (
+                "whisper_full_with_state: auto-detected language: fr\n",
+                None,
+            ),
+            (
+                "whisper_full_with_state: auto-detected language: fr (p = )",
+                None,
+            ),
+            (
+                "whisper_full_with_state: auto-detected language: fr (p = nope)",
+                None,
+            ),
+            ("", None),
+        ];
+
+        for (stderr, expected) in cases {
+            let parsed = parse_detected_language(stderr);
+            let parsed = parsed.as_ref().map(|(code, p)| (code.as_str(), *p));
+            assert_eq!(parsed, *expected, "stderr: {:?}", stderr);
+        }
+    }
+
+    const FULL_JSON: &str = include_str!("../tests/fixtures/whisper/output-full.json");
+
+    #[test]
+    fn parses_full_json_output() {
+        let output = parse_json_output(FULL_JSON, 0).unwrap();
+
+        assert_eq!(output.language.as_deref(), Some("en"));
+        assert_eq!(output.text, "Hello world. Goodbye.");
+        // The segment holding only whitespace is dropped
+        assert_eq!(output.segments.len(), 2);
+
+        let hello = &output.segments[0];
+        assert_eq!((hello.start_ms, hello.end_ms), (0, 2480));
+        assert_eq!(hello.text, "Hello world.");
+        assert_eq!(hello.no_speech_prob, None);
+        let tokens: Vec<_> = hello.tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(tokens, [" Hello", " world", "."]);
+        assert_eq!(hello.tokens[1].probability, 0.874512);
+        assert_eq!(
+            (hello.tokens[1].start_ms, hello.tokens[1].end_ms),
+            (Some(720), Some(1900))
+        );
+
+        let goodbye = &output.segments[1];
+        assert_eq!(goodbye.text, "Goodbye.");
+        let tokens: Vec<_> = goodbye.tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(tokens, [" Goodbye", "."]);
+    }
+
+    #[test]
+    fn applies_offset_to_segments_and_tokens() {
+        let output = parse_json_output(FULL_JSON, 1500).unwrap();
+
+        let goodbye = &output.segments[1];
+        assert_eq!((goodbye.start_ms, goodbye.end_ms), (4500, 5700));
+        assert_eq!(
+            (goodbye.tokens[0].start_ms, goodbye.tokens[0].end_ms),
+            (Some(4500), Some(5400))
+        );
+    }
+
+    #[test]
+    fn parses_minimal_json_output() {
+        // Older builds omit the result, token times and no-speech probability
+        let json = r#"{"transcription": [{
+            "offsets": {"from": 0, "to": 1000},
+            "text": " Hi",
+            "tokens": [{"text": " Hi", "p": 0.5}],
+            "no_speech_probability": 0.25
+        }]}"#;
+
+        let output = parse_json_output(json, 0).unwrap();
+
+        assert_eq!(output.language, None);
+        assert_eq!(output.text, "Hi");
+        let segment = &output.segments[0];
+        assert_eq!(segment.no_speech_prob, Some(0.25));
+        assert_eq!(segment.tokens[0].start_ms, None);
+    }
+
+    #[test]
+    fn rejects_malformed_json_output() {
+        for json in ["", "{", "null", r#"{"transcription": [{"text": " Hi"}]}"#] {
+            assert!(
+                matches!(parse_json_output(json, 0), Err(AppError::Internal(_))),
+                "json: {:?}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn recognizes_special_tokens() {
+        for token in ["[_BEG_]", "[_TT_124]", "[_SOT_]", "<|en|>", "<|endoftext|>"] {
+            assert!(is_special_token(token), "{}", token);
+        }
+        for token in [" Hello", ".", "[", " [laughs]", "<", " <b>"] {
+            assert!(!is_special_token(token), "{}", token);
+        }
+    }
+
+    #[test]
+    fn parses_progress_lines() {
+        let cases: &[(&str, Option<u8>)] = &[
+            ("whisper_print_progress_callback: progress =   5%", Some(5)),
+            ("whisper_print_progress_callback: progress =  40%", Some(40)),
+            (
+                "whisper_print_progress_callback: progress = 100%\r",
+                Some(100),
+            ),
+            // Out of range
+            ("whisper_print_progress_callback: progress = 101%", None),
+            ("whisper_print_progress_callback: progress = 300%", None),
+            ("whisper_print_progress_callback: progress = -5%", None),
+            // Unrelated or incomplete lines
+            ("whisper_print_progress_callback: progress = 40", None),
+            ("whisper_print_progress_callback: progress = %", None),
+            (
+                "whisper_full_with_state: auto-detected language: de (p = 0.973421)",
+                None,
+            ),
+            (
+                "main: processing 'audio.wav' (160000 samples, 10.0 sec)",
+                None,
+            ),
+            ("", None),
+        ];
+
+        for (line, expected) in cases {
+            assert_eq!(parse_progress(line), *expected, "line: {:?}", line);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 import type { Metadata } from 'next';
 import localFont from 'next/font/local';
 import './globals.css';
+import { VaultUnlockDialog } from '../components/VaultUnlockDialog';
 
 const geistSans = localFont({
   src: './fonts/GeistVF.woff',
@@ -28,6 +29,7 @@ export default function RootLayout({
     <html lang="en" className="dark">
       <body className={`${geistSans.variable} ${geistMono.variable} font-sans bg-background text-text min-h-screen antialiased`}>
         {children}
+        <VaultUnlockDialog />
       </body>
     </html>
   );
@@ -1,6 +1,6 @@
 'use client';
 
-import { useState, useEffect, useCallback } from 'react';
+import { useState, useEffect, useCallback, useRef } from 'react';
 import { RecordingOverlay } from '../components/RecordingOverlay';
 import { EnrichmentModeSelector } from '../components/EnrichmentModeSelector';
 import { OutputRouter } from '../components/OutputRouter';
@@ -8,10 +8,19 @@ import { SettingsPanel } from '../components/SettingsPanel';
 import { useSettings } from '../hooks/useSettings';
 import { useRecording } from '../hooks/useRecording';
 import { useHotkey } from '../hooks/useHotkey';
+import { useAudioImport } from '../hooks/useAudioImport';
+import { useTranscriptionQueue } from '../hooks/useTranscriptionQueue';
 import { useLLM } from '../hooks/useLLM';
-import type { EnrichmentMode, OutputTarget } from '../types';
-import { addToHistory, getHistory, getAppVersion, type HistoryItem } from '../lib/api';
-import { getSTTService } from '../services/stt';
+import type { EnrichmentMode, ImportedAudioFile, OutputTarget, TranscriptionJob } from '../types';
+import {
+  addToHistory,
+  getHistory,
+  getAppVersion,
+  onRecordingAutoStopped,
+  type HistoryItem,
+} from '../lib/api';
+import { errorMessage } from '../lib/errors';
+import { getSTTService, LocalWhisperProvider } from '../services/stt';
 import { SetupWizard } from '../components/SetupWizard';
 
 export default function Home() {
@@ -47,24 +56,16 @@ export default function Home() {
       setSettingsInitialized(true);
       llm.setLanguage(settings.language);
 
-      // Configure LLM provider with API key
-      if (settings.llmProvider === 'openai' && settings.openaiApiKey) {
-        llm.configureProvider('openai', settings.openaiApiKey, settings.llmModel);
-      } else if (settings.llmProvider === 'openrouter' && settings.openrouterApiKey) {
-        llm.configureProvider('openrouter', settings.openrouterApiKey, settings.llmModel);
+      // Configure the LLM provider; hosted providers read their key from the secret store
+      if (settings.llmProvider === 'openai' || settings.llmProvider === 'openrouter') {
+        llm.configureProvider(settings.llmProvider, settings.llmModel);
       } else if (settings.llmProvider === 'ollama' && settings.ollamaModel) {
-        // Ollama doesn't need an API key (local service), just configure the model
-        llm.configureProvider('ollama', '', settings.ollamaModel);
+        llm.configureProvider('ollama', settings.ollamaModel);
       }
 
       // Check if STT is configured
       const checkSTT = async () => {
         const sttService = getSTTService();
-        // Use dedicated Whisper API key, fallback to OpenAI key for backwards compatibility
-        const whisperApiKey = settings.openaiWhisperApiKey || settings.openaiApiKey;
-        if (whisperApiKey) {
-          sttService.configureOpenAI(whisperApiKey);
-        }
         if (settings.whisperPath) {
           sttService.configureWhisperPath(settings.whisperPath);
         }
@@ -89,15 +90,22 @@ export default function Home() {
     getAppVersion().then(setAppVersion);
   }, []);
 
+  // Duration and language of the transcript being worked on, for history
+  const transcriptionMetaRef = useRef<{
+    duration: number;
+    language?: string;
+    languageProbability?: number;
+  } | null>(null);
+
   // Handle enrichment (defined early for use in handleStopRecording)
-  const handleEnrich = useCallback(async (text?: string) => {
+  const handleEnrich = useCallback(async (text?: string, mode: EnrichmentMode = enrichmentMode) => {
     const textToEnrich = text || transcript;
     if (!textToEnrich) return;
 
     setIsEnriching(true);
     try {
-      const options = enrichmentMode === 'custom' ? { customPrompt } : undefined;
-      const result = await llm.enrich(textToEnrich, enrichmentMode, options);
+      const options = mode === 'custom' ? { customPrompt } : undefined;
+      const result = await llm.enrich(textToEnrich, mode, options);
       setEnrichedContent(result);
 
       // Save to history
@@ -106,8 +114,10 @@ export default function Home() {
         timestamp: Date.now(),
         rawTranscript: textToEnrich,
         enrichedContent: result,
-        enrichmentMode,
-        duration: recording.duration,
+        enrichmentMode: mode,
+        duration: transcriptionMetaRef.current?.duration ?? recording.duration,
+        language: transcriptionMetaRef.current?.language,
+        languageProbability: transcriptionMetaRef.current?.languageProbability,
       });
     } catch (error) {
       console.error('Enrichment failed:', error);
@@ -131,45 +141,147 @@ export default function Home() {
     }
   }, [transcript]);
 
+  // STT service with the current whisper model applied
+  const getConfiguredSTTService = useCallback(() => {
+    const sttService = getSTTService();
+
+    // Configure whisper model if set
+    if (settings.whisperModel) {
+      sttService.configureWhisperModel(settings.whisperModel);
+    }
+
+    return sttService;
+  }, [settings.whisperModel]);
+
+  // Show the result of a background transcription job
+  const handleTranscriptionJobFinished = useCallback(async (job: TranscriptionJob) => {
+    const source = job.deleteAudio ? 'Recording' : job.audioPath.split(/[\\/]/).pop();
+    if (job.state !== 'completed' || !job.result) {
+      setTranscriptionError(`${source}: ${errorMessage(job.error, 'Transcription failed')}`);
+      return;
+    }
+
+    setShowTranscriptModal(false);
+    setEnrichedContent(null);
+    setHasCompletedAction(false);
+    setTranscriptionError(null);
+    transcriptionMetaRef.current = {
+      duration: job.result.duration,
+      language: job.result.language,
+      languageProbability: job.result.languageProbability,
+    };
+    // Apply the choices made when the job was queued
+    setEnrichmentMode(job.enrichmentMode);
+    setOutputTarget(job.outputTarget);
+    setTranscript(job.result.text);
+
+    if (settings.autoEnrich) {
+      await handleEnrich(job.result.text, job.enrichmentMode);
+    }
+  }, [settings.autoEnrich, handleEnrich]);
+
+  const transcriptionQueue = useTranscriptionQueue(handleTranscriptionJobFinished);
+
+  // Local whisper transcriptions go through the persistent backend queue, so
+  // back-to-back recordings and imports wait their turn and survive a crash.
+  // Returns false when another provider is in use.
+  const queueLocalTranscription = useCallback(async (audioPath: string, deleteAudio: boolean) => {
+    const provider = await getConfiguredSTTService().getAvailableProvider();
+    if (!(provider instanceof LocalWhisperProvider)) return false;
+
+    await transcriptionQueue.enqueue({
+      audioPath,
+      language: settings.language,
+      model: provider.getModel(),
+      whisperPath: provider.getWhisperPath(),
+      enrichmentMode,
+      outputTarget,
+      deleteAudio,
+    });
+    return true;
+  }, [getConfiguredSTTService, transcriptionQueue, settings.language, enrichmentMode, outputTarget]);
+
   // Handle stop recording
   const handleStopRecording = useCallback(async () => {
-    const audioBlob = await recording.stopRecording();
-    if (!audioBlob) return;
+    const recorded = await recording.stopRecordingToFile();
+    if (!recorded) return;
 
-    setIsTranscribing(true);
     setTranscriptionError(null);
+    try {
+      if (await queueLocalTranscription(recorded.path, true)) return;
+    } catch (error) {
+      console.error('Failed to queue transcription:', error);
+      setTranscriptionError(errorMessage(error, 'Failed to queue transcription'));
+      return;
+    }
 
+    setIsTranscribing(true);
     try {
       // Use the STT service for actual transcription
-      const sttService = getSTTService();
+      const result = await getConfiguredSTTService().transcribeFile(recorded.path, settings.language);
+      transcriptionMetaRef.current = {
+        duration: result.duration || recorded.durationSecs,
+        language: result.language,
+        languageProbability: result.languageProbability,
+      };
+      setTranscript(result.text);
 
-      // Configure OpenAI Whisper API key if available (use dedicated key, fallback to general OpenAI key)
-      const whisperApiKey = settings.openaiWhisperApiKey || settings.openaiApiKey;
-      if (whisperApiKey) {
-        sttService.configureOpenAI(whisperApiKey);
+      // Auto-enrich if enabled
+      if (settings.autoEnrich) {
+        await handleEnrich(result.text);
       }
+    } catch (error) {
+      console.error('Transcription failed:', error);
+      setTranscriptionError(
+        error instanceof Error ? error.message : 'Transcription failed'
+      );
+    } finally {
+      setIsTranscribing(false);
+      const { remove } = await import('@tauri-apps/plugin-fs');
+      await remove(recorded.path).catch(() => {});
+    }
+  }, [recording, settings.autoEnrich, settings.language, getConfiguredSTTService, queueLocalTranscription, handleEnrich]);
 
-      // Configure whisper model if set
-      if (settings.whisperModel) {
-        sttService.configureWhisperModel(settings.whisperModel);
-      }
+  // Transcribe (and enrich) imported audio files, one at a time
+  const handleImportedFile = useCallback(async (file: ImportedAudioFile) => {
+    try {
+      if (await queueLocalTranscription(file.path, false)) return;
+    } catch (error) {
+      console.error(`Failed to queue ${file.fileName}:`, error);
+      setTranscriptionError(`${file.fileName}: ${errorMessage(error, 'Failed to queue transcription')}`);
+      return;
+    }
+
+    setShowTranscriptModal(false);
+    setTranscript(null);
+    setEnrichedContent(null);
+    setHasCompletedAction(false);
+    setIsTranscribing(true);
+    setTranscriptionError(null);
 
-      const result = await sttService.transcribe(audioBlob, settings.language);
+    try {
+      const result = await getConfiguredSTTService().transcribeFile(file.path, settings.language);
+      transcriptionMetaRef.current = {
+        duration: result.duration,
+        language: result.language,
+        languageProbability: result.languageProbability,
+      };
       setTranscript(result.text);
 
-      // Auto-enrich if enabled
       if (settings.autoEnrich) {
         await handleEnrich(result.text);
       }
     } catch (error) {
-      console.error('Transcription failed:', error);
+      console.error(`Transcription of ${file.fileName} failed:`, error);
       setTranscriptionError(
-        error instanceof Error ? error.message : 'Transcription failed'
+        `${file.fileName}: ${error instanceof Error ? error.message : 'Transcription failed'}`
       );
     } finally {
       setIsTranscribing(false);
     }
-  }, [recording, settings.autoEnrich, settings.language, settings.openaiApiKey, handleEnrich]);
+  }, [settings.autoEnrich, settings.language, getConfiguredSTTService, queueLocalTranscription, handleEnrich]);
+
+  const audioImport = useAudioImport(handleImportedFile);
 
   // Hotkey handlers for push-to-talk
   const handleHotkeyPress = useCallback(() => {
@@ -192,6 +304,19 @@ export default function Home() {
     }
   }, [recording.state, handleStopRecording]);
 
+  // Finish the recording when the backend stops it after a stretch of silence
+  const handleStopRecordingRef = useRef(handleStopRecording);
+  handleStopRecordingRef.current = handleStopRecording;
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onRecordingAutoStopped(() => {
+      handleStopRecordingRef.current();
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
   // Register hotkey (push-to-talk: hold to record, release to stop)
   const hotkey = useHotkey(settings.hotkey, handleHotkeyPress, handleHotkeyRelease);
 
@@ -536,6 +661,42 @@ export default function Home() {
           </div>
         </div>
 
+        {/* Background transcription queue */}
+        {transcriptionQueue.active.length > 0 && (
+          <div className="max-w-md mx-auto mb-8 p-4 bg-secondary/50 rounded-lg space-y-3">
+            {transcriptionQueue.active.map((job) => {
+              const percent = transcriptionQueue.progress[job.id] ?? 0;
+              return (
+                <div key={job.id}>
+                  <div className="flex items-center gap-3">
+                    <span className="flex-1 text-sm text-text truncate">
+                      {job.deleteAudio
+                        ? `Recording from ${new Date(job.createdAt).toLocaleTimeString()}`
+                        : job.audioPath.split(/[\\/]/).pop()}
+                    </span>
+                    <span className="text-xs text-text-muted">
+                      {job.state === 'running' ? `${percent}%` : 'Queued'}
+                    </span>
+                    <button
+                      onClick={() => transcriptionQueue.cancel(job.id).catch((error) => {
+                        console.error('Failed to cancel transcription:', error);
+                      })}
+                      className="text-xs text-text-muted hover:text-error"
+                    >
+                      Cancel
+                    </button>
+                  </div>
+                  {job.state === 'running' && (
+                    <div className="h-1 mt-2 bg-secondary rounded-full overflow-hidden">
+                      <div className="h-full bg-primary transition-all" style={{ width: `${percent}%` }} />
+                    </div>
+                  )}
+                </div>
+              );
+            })}
+          </div>
+        )}
+
         {/* Recording Button */}
         {recording.state === 'idle' && !transcript && !isTranscribing && (
           <div className="flex flex-col items-center py-16">
@@ -557,6 +718,16 @@ export default function Home() {
                 {settings.hotkey.replace('CommandOrControl', 'Ctrl')}
               </kbd>
             </p>
+            <button
+              onClick={audioImport.pickFiles}
+              className="mt-4 text-sm text-primary hover:underline"
+            >
+              Transcribe an audio file…
+            </button>
+            <p className="text-xs text-text-muted mt-1">or drop audio files on this window</p>
+            {audioImport.error && (
+              <p className="text-sm text-error mt-2 whitespace-pre-line">{audioImport.error}</p>
+            )}
           </div>
         )}
 
@@ -569,7 +740,14 @@ export default function Home() {
                 <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4zm2 5.291A7.962 7.962 0 014 12H0c0 3.042 1.135 5.824 3 7.938l3-2.647z" />
               </svg>
             </div>
-            <p className="mt-6 text-text">Transcribing audio...</p>
+            <p className="mt-6 text-text">
+              {audioImport.current ? `Transcribing ${audioImport.current.fileName}...` : 'Transcribing audio...'}
+            </p>
+            {audioImport.queue.length > 0 && (
+              <p className="text-sm text-text-muted mt-1">
+                {audioImport.queue.length} more file{audioImport.queue.length === 1 ? '' : 's'} queued
+              </p>
+            )}
             <p className="text-sm text-text-muted mt-2">Using whisper for speech-to-text</p>
             <p className="text-xs text-text-muted mt-4">This may take a moment, even for short recordings</p>
           </div>
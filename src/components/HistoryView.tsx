@@ -3,7 +3,7 @@
 import { useState, useEffect, useRef } from 'react';
 import type { EnrichmentMode } from '../types';
 import { ENRICHMENT_MODE_LABELS } from '../types';
-import { getHistory, deleteHistoryItem, clearHistory, copyToClipboard, saveAsPdf, exportToNotion, getSettings, NotionError, type HistoryItem } from '../lib/api';
+import { getHistory, deleteHistoryItem, clearHistory, copyToClipboard, saveAsPdf, exportToNotion, hasSecret, NotionError, type HistoryItem } from '../lib/api';
 
 interface HistoryViewProps {
   onReprocess?: (item: HistoryItem, mode: EnrichmentMode) => void;
@@ -126,8 +126,7 @@ export function HistoryView({ onReprocess }: HistoryViewProps) {
     setExportMessage('Exporting to Notion...');
 
     try {
-      const settings = await getSettings();
-      if (!settings?.notionApiKey) {
+      if (!(await hasSecret('notion'))) {
         setExportStatus('error');
         setExportMessage('Notion API key not configured. Please add it in Settings.');
         setTimeout(() => {
@@ -138,7 +137,7 @@ export function HistoryView({ onReprocess }: HistoryViewProps) {
       }
 
       const title = `Transcript - ${formatDate(item.timestamp)}`;
-      await exportToNotion(item.enrichedContent, settings.notionApiKey, { title });
+      await exportToNotion(item.enrichedContent, { title });
 
       setExportStatus('success');
       setExportMessage('Exported to Notion!');
@@ -256,6 +255,7 @@ export function HistoryView({ onReprocess }: HistoryViewProps) {
                 </h3>
                 <p className="text-sm text-text-muted">
                   Duration: {formatDuration(selectedItem.duration)} |{' '}
+                  {selectedItem.language && <>Language: {selectedItem.language} |{' '}</>}
                   Mode: {ENRICHMENT_MODE_LABELS[selectedItem.enrichmentMode as EnrichmentMode]}
                 </p>
               </div>
@@ -1,6 +1,7 @@
 'use client';
 
 import { useState, useEffect, useCallback } from 'react';
+import { listInputDevices, testMicrophone as testNativeMicrophone } from '../lib/api';
 
 interface AudioDevice {
   deviceId: string;
@@ -25,16 +26,11 @@ export function MicrophoneSelector({ value, onChange, disabled }: MicrophoneSele
     setError(null);
 
     try {
-      // Request permission first
-      await navigator.mediaDevices.getUserMedia({ audio: true });
-
-      const devices = await navigator.mediaDevices.enumerateDevices();
-      const audioInputs = devices
-        .filter(device => device.kind === 'audioinput')
-        .map(device => ({
-          deviceId: device.deviceId,
-          label: device.label || `Microphone ${device.deviceId.slice(0, 8)}`,
-        }));
+      const devices = await listInputDevices();
+      const audioInputs = devices.map(device => ({
+        deviceId: device.id,
+        label: device.name,
+      }));
 
       setMicrophones(audioInputs);
 
@@ -44,7 +40,7 @@ export function MicrophoneSelector({ value, onChange, disabled }: MicrophoneSele
       }
     } catch (err) {
       console.error('Failed to load microphones:', err);
-      setError('Failed to access microphones. Please check permissions.');
+      setError('Failed to list microphones. Please check your audio devices.');
     } finally {
       setIsLoading(false);
     }
@@ -61,33 +57,7 @@ export function MicrophoneSelector({ value, onChange, disabled }: MicrophoneSele
     setMicTestResult(null);
 
     try {
-      const stream = await navigator.mediaDevices.getUserMedia({
-        audio: { deviceId: { exact: value } }
-      });
-
-      const audioContext = new AudioContext();
-      const analyser = audioContext.createAnalyser();
-      const source = audioContext.createMediaStreamSource(stream);
-      source.connect(analyser);
-
-      analyser.fftSize = 256;
-      const dataArray = new Uint8Array(analyser.frequencyBinCount);
-
-      let hasSound = false;
-      const checkInterval = setInterval(() => {
-        analyser.getByteFrequencyData(dataArray);
-        const average = dataArray.reduce((a, b) => a + b) / dataArray.length;
-        if (average > 10) {
-          hasSound = true;
-        }
-      }, 100);
-
-      await new Promise(resolve => setTimeout(resolve, 2000));
-      clearInterval(checkInterval);
-
-      stream.getTracks().forEach(track => track.stop());
-      audioContext.close();
-
+      const hasSound = await testNativeMicrophone(value);
       setMicTestResult(hasSound ? 'success' : 'error');
     } catch (err) {
       console.error('Mic test failed:', err);
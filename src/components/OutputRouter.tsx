@@ -3,7 +3,7 @@
 import { useState } from 'react';
 import type { OutputTarget } from '../types';
 import { OUTPUT_TARGETS, FILE_FORMATS, type FileFormat } from '../lib/config';
-import { copyToClipboard, saveToFile, saveAsPdf, exportToNotion, getSettings } from '../lib/api';
+import { copyToClipboard, saveToFile, saveAsPdf, exportToNotion, hasSecret } from '../lib/api';
 
 interface OutputRouterProps {
   value: OutputTarget;
@@ -64,9 +64,7 @@ export function OutputRouter({
           }
         }
       } else if (value === 'notion') {
-        // Get Notion API key from settings
-        const settings = await getSettings();
-        if (!settings?.notionApiKey) {
+        if (!(await hasSecret('notion'))) {
           setStatus('error');
           setStatusMessage('Please configure your Notion API key in Settings');
           setTimeout(() => {
@@ -76,7 +74,7 @@ export function OutputRouter({
           return;
         }
 
-        const result = await exportToNotion(content, settings.notionApiKey, {
+        const result = await exportToNotion(content, {
           title: `Transcript - ${new Date().toLocaleString()}`,
         });
 
@@ -2,7 +2,7 @@
 
 import { useState, useEffect, useCallback } from 'react';
 import { useRouter } from 'next/navigation';
-import type { Settings, EnrichmentMode, WhisperModel, DownloadProgress, NotionPage } from '../types';
+import type { Settings, EnrichmentMode, WhisperModel, DownloadProgress, NotionPage, StorageUsage, InstallResult } from '../types';
 import {
   COMMON_HOTKEYS,
   ENRICHMENT_MODES,
@@ -11,8 +11,10 @@ import {
   RETENTION_OPTIONS,
 } from '../lib/config';
 import { MicrophoneSelector } from './MicrophoneSelector';
+import { VocabularyEditor } from './VocabularyEditor';
 import { OPENROUTER_MODELS } from '../providers/openrouter';
-import { getAppVersion, getAvailableModels, downloadWhisperModel, deleteWhisperModel, onDownloadProgress, checkOllamaAvailable, getOllamaModels, pullOllamaModel, onOllamaPullProgress, notionTestConnection, notionSearchPages } from '../lib/api';
+import { getAppVersion, getAvailableModels, refreshModelCatalog, downloadWhisperModel, cancelDownload, deleteWhisperModel, pickAndRegisterCustomModel, unregisterCustomModel, onDownloadProgress, checkOllamaAvailable, getOllamaModels, pullOllamaModel, onOllamaPullProgress, getStorageUsage, cleanTempFiles, notionTestConnection, notionSearchPages, getSecretStatus, setSecret, deleteSecret, onVaultStatusChanged, requestVaultUnlock, type SecretName } from '../lib/api';
+import { errorMessage, errorCode } from '../lib/errors';
 import type { OllamaServiceStatus, OllamaModel, OllamaPullProgress } from '../types/llm';
 
 // OpenAI models
@@ -22,6 +24,11 @@ const OPENAI_MODELS = [
   { id: 'gpt-4-turbo', name: 'GPT-4 Turbo', description: 'Previous flagship' },
 ];
 
+function formatBytes(bytes: number): string {
+  const gb = bytes / (1024 * 1024 * 1024);
+  return gb >= 1 ? `${gb.toFixed(1)} GB` : `${(bytes / (1024 * 1024)).toFixed(0)} MB`;
+}
+
 interface WhisperCheckResult {
   available: boolean;
   path: string | null;
@@ -40,12 +47,16 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
   const [localSettings, setLocalSettings] = useState(settings);
   const [isSaving, setIsSaving] = useState(false);
   const [saveSuccess, setSaveSuccess] = useState(false);
-  const [apiKeys, setApiKeys] = useState<Record<string, string>>({
-    openai: settings.openaiApiKey || '',
-    openrouter: settings.openrouterApiKey || '',
-    openaiWhisper: settings.openaiWhisperApiKey || '',
-    notion: settings.notionApiKey || '',
+  // Keys typed since the last save; saved keys are never read back
+  const [apiKeys, setApiKeys] = useState<Record<SecretName, string>>({
+    openai: '',
+    openrouter: '',
+    openaiWhisper: '',
+    notion: '',
   });
+  const [storedKeys, setStoredKeys] = useState<Partial<Record<SecretName, boolean>>>({});
+  const [secretsLocked, setSecretsLocked] = useState(false);
+  const [keyError, setKeyError] = useState<string | null>(null);
 
   // Track if user has made any changes
   const [hasUserMadeChanges, setHasUserMadeChanges] = useState(false);
@@ -71,6 +82,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
   const [downloadingModels, setDownloadingModels] = useState<Record<string, number>>({});
   const [downloadErrors, setDownloadErrors] = useState<Record<string, string>>({});
   const [deletingModels, setDeletingModels] = useState<Record<string, boolean>>({});
+  const [isRefreshingCatalog, setIsRefreshingCatalog] = useState(false);
+  const [catalogStatus, setCatalogStatus] = useState<string | null>(null);
+  const [catalogError, setCatalogError] = useState<string | null>(null);
+  const [isAddingModel, setIsAddingModel] = useState(false);
+  const [customModelError, setCustomModelError] = useState<string | null>(null);
+
+  // Storage state
+  const [storageUsage, setStorageUsage] = useState<StorageUsage | null>(null);
+  const [isCleaningTempFiles, setIsCleaningTempFiles] = useState(false);
 
   // OLLAMA state
   const [ollamaStatus, setOllamaStatus] = useState<OllamaServiceStatus | null>(null);
@@ -90,6 +110,53 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
   const [notionDefaultPageId, setNotionDefaultPageId] = useState<string>(settings.notionDefaultPageId || '');
   const [notionDefaultPageName, setNotionDefaultPageName] = useState<string>(settings.notionDefaultPageName || '');
 
+  // Show the other profile's values when it is switched from the tray
+  useEffect(() => {
+    setLocalSettings(settings);
+    setWhisperPath(settings.whisperPath || '');
+    setNotionDefaultPageId(settings.notionDefaultPageId || '');
+    setNotionDefaultPageName(settings.notionDefaultPageName || '');
+    setHasUserMadeChanges(false);
+  }, [settings]);
+
+  // Which keys are already in the secret store
+  const refreshStoredKeys = useCallback(async () => {
+    try {
+      const statuses = await getSecretStatus();
+      setStoredKeys(
+        Object.fromEntries(statuses.map((s) => [s.name, s.configured || s.locked]))
+      );
+      setSecretsLocked(statuses.some((s) => s.locked));
+    } catch {
+      setStoredKeys({});
+    }
+  }, []);
+
+  useEffect(() => {
+    refreshStoredKeys();
+    let unlisten: (() => void) | undefined;
+    onVaultStatusChanged(() => {
+      refreshStoredKeys();
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [refreshStoredKeys]);
+
+  const removeStoredKey = async (name: SecretName) => {
+    setKeyError(null);
+    try {
+      await deleteSecret(name);
+      if (name === 'notion') {
+        setNotionTestResult(null);
+        setNotionPages([]);
+      }
+    } catch (error) {
+      setKeyError(error instanceof Error ? error.message : 'Failed to remove the API key');
+    }
+    await refreshStoredKeys();
+  };
+
   // Fetch app version
   useEffect(() => {
     getAppVersion().then(setAppVersion);
@@ -132,6 +199,18 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     loadModels();
   }, [loadModels]);
 
+  const loadStorageUsage = useCallback(async () => {
+    try {
+      setStorageUsage(await getStorageUsage());
+    } catch (error) {
+      console.error('Failed to load storage usage:', errorMessage(error));
+    }
+  }, []);
+
+  useEffect(() => {
+    loadStorageUsage();
+  }, [loadStorageUsage]);
+
   // Check OLLAMA availability and load models
   const checkOllamaStatus = useCallback(async () => {
     setIsLoadingOllama(true);
@@ -181,6 +260,12 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
         });
         // Reload models to get updated installed status
         loadModels();
+      } else if (progress.status === 'cancelled') {
+        setDownloadingModels((prev) => {
+          const newState = { ...prev };
+          delete newState[progress.modelId];
+          return newState;
+        });
       } else if (progress.status === 'error') {
         setDownloadingModels((prev) => {
           const newState = { ...prev };
@@ -229,6 +314,12 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
         setOllamaPullModelName('');
         // Reload OLLAMA models to get updated list
         checkOllamaStatus();
+      } else if (progress.status === 'cancelled') {
+        setOllamaPullingModels((prev) => {
+          const newState = { ...prev };
+          delete newState[progress.modelId];
+          return newState;
+        });
       } else if (progress.status === 'error') {
         setOllamaPullingModels((prev) => {
           const newState = { ...prev };
@@ -257,21 +348,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
 
     try {
       const { invoke } = await import('@tauri-apps/api/core');
-      const result = await invoke<{ success: boolean; message: string; path: string | null }>('install_whisper');
+      const result = await invoke<InstallResult>('install_whisper');
 
-      if (result.success) {
-        setWhisperInstallStatus('Installation complete!');
-        setWhisperAvailable(true);
-        if (result.path) {
-          setWhisperPath(result.path);
-          // Save the path immediately
-          handleChange('whisperPath', result.path);
-        }
-      } else {
-        setWhisperInstallStatus(result.message);
-      }
+      setWhisperInstallStatus('Installation complete!');
+      setWhisperAvailable(true);
+      setWhisperPath(result.path);
+      // Save the path immediately
+      handleChange('whisperPath', result.path);
     } catch (error) {
-      setWhisperInstallStatus(`Error: ${error instanceof Error ? error.message : 'Installation failed'}`);
+      setWhisperInstallStatus(`Error: ${errorMessage(error, 'Installation failed')}`);
     } finally {
       setIsInstallingWhisper(false);
     }
@@ -334,24 +419,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     }));
 
     try {
-      const result = await downloadWhisperModel(modelId);
-      if (!result.success) {
+      await downloadWhisperModel(modelId);
+      // The download progress listener handles the rest
+    } catch (error) {
+      if (errorCode(error) !== 'CANCELLED') {
         setDownloadErrors((prev) => ({
           ...prev,
-          [modelId]: result.message || 'Download failed. Please try again.',
+          [modelId]: errorMessage(error, 'Download failed. Please try again.'),
         }));
-        setDownloadingModels((prev) => {
-          const newState = { ...prev };
-          delete newState[modelId];
-          return newState;
-        });
       }
-      // If successful, the download progress listener will handle the rest
-    } catch (error) {
-      setDownloadErrors((prev) => ({
-        ...prev,
-        [modelId]: error instanceof Error ? error.message : 'Download failed. Please try again.',
-      }));
       setDownloadingModels((prev) => {
         const newState = { ...prev };
         delete newState[modelId];
@@ -360,6 +436,81 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     }
   };
 
+  const handleRefreshCatalog = async () => {
+    setIsRefreshingCatalog(true);
+    setCatalogStatus(null);
+    setCatalogError(null);
+    try {
+      // Use the source as typed, so it can be tried before settings are saved
+      const source = localSettings.models?.manifestSource?.trim();
+      const models = await refreshModelCatalog(source || undefined);
+      setAvailableModels(models);
+      setCatalogStatus(`Catalog updated: ${models.filter((m) => !m.custom).length} models`);
+    } catch (error) {
+      setCatalogError(errorMessage(error, 'Failed to refresh the model catalog'));
+    } finally {
+      setIsRefreshingCatalog(false);
+    }
+  };
+
+  const handleAddCustomModel = async () => {
+    setIsAddingModel(true);
+    setCustomModelError(null);
+    try {
+      const model = await pickAndRegisterCustomModel();
+      if (!model) return;
+      setAvailableModels((prev) => [...prev.filter((m) => m.id !== model.id), model]);
+      // Registered by the backend; keep the panel's copy in step so saving the panel doesn't drop it
+      setLocalSettings((prev) => ({
+        ...prev,
+        models: {
+          ...prev.models,
+          custom: [
+            ...(prev.models?.custom ?? []).filter((m) => m.id !== model.id),
+            { id: model.id, name: model.name, path: model.installedPath ?? '', multilingual: model.isMultilingual },
+          ],
+        },
+      }));
+    } catch (error) {
+      setCustomModelError(errorMessage(error, 'Failed to add the model file'));
+    } finally {
+      setIsAddingModel(false);
+    }
+  };
+
+  const selectModelsDirectory = async () => {
+    try {
+      const { open } = await import('@tauri-apps/plugin-dialog');
+      const selected = await open({ directory: true, multiple: false });
+      if (selected && typeof selected === 'string') {
+        handleChange('models', { ...localSettings.models, directory: selected });
+      }
+    } catch (error) {
+      console.error('Failed to open folder dialog:', error);
+    }
+  };
+
+  const handleCancelDownload = async (downloadId: string) => {
+    try {
+      await cancelDownload(downloadId);
+    } catch (error) {
+      // The download finished or failed in the meantime
+      console.error('Failed to cancel download:', errorMessage(error));
+    }
+  };
+
+  const handleCleanTempFiles = async () => {
+    setIsCleaningTempFiles(true);
+    try {
+      await cleanTempFiles();
+    } catch (error) {
+      console.error('Failed to clean up temporary files:', errorMessage(error));
+    } finally {
+      setIsCleaningTempFiles(false);
+      loadStorageUsage();
+    }
+  };
+
   const handleDeleteModel = async (modelId: string) => {
     setDeletingModels((prev) => ({
       ...prev,
@@ -367,6 +518,18 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     }));
 
     try {
+      // Custom models are only unregistered; their files stay where they are
+      if (availableModels.find((m) => m.id === modelId)?.custom) {
+        await unregisterCustomModel(modelId);
+        setAvailableModels((prev) => prev.filter((m) => m.id !== modelId));
+        // Keep the panel's copy in step so saving the panel doesn't register it again
+        setLocalSettings((prev) => ({
+          ...prev,
+          models: { ...prev.models, custom: prev.models?.custom?.filter((m) => m.id !== modelId) },
+        }));
+        return;
+      }
+
       const success = await deleteWhisperModel(modelId);
       if (success) {
         // Update the model list to reflect deletion
@@ -411,24 +574,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     }));
 
     try {
-      const result = await pullOllamaModel(normalizedName);
-      if (!result.success) {
+      await pullOllamaModel(normalizedName);
+      // The pull progress listener handles the rest
+    } catch (error) {
+      if (errorCode(error) !== 'CANCELLED') {
         setOllamaPullErrors((prev) => ({
           ...prev,
-          [normalizedName]: result.message || 'Pull failed. Please try again.',
+          [normalizedName]: errorMessage(error, 'Pull failed. Please try again.'),
         }));
-        setOllamaPullingModels((prev) => {
-          const newState = { ...prev };
-          delete newState[normalizedName];
-          return newState;
-        });
       }
-      // If successful, the pull progress listener will handle the rest
-    } catch (error) {
-      setOllamaPullErrors((prev) => ({
-        ...prev,
-        [normalizedName]: error instanceof Error ? error.message : 'Pull failed. Please try again.',
-      }));
       setOllamaPullingModels((prev) => {
         const newState = { ...prev };
         delete newState[normalizedName];
@@ -439,7 +593,8 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
 
   // Test Notion connection and load pages
   const testNotionAndLoadPages = async () => {
-    if (!apiKeys.notion) {
+    const typedKey = apiKeys.notion.trim();
+    if (!typedKey && !storedKeys.notion) {
       setNotionTestResult('error');
       setNotionTestError('Please enter an API key first');
       return;
@@ -451,14 +606,21 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
     setNotionPages([]);
 
     try {
-      const testResult = await notionTestConnection(apiKeys.notion);
+      // A typed key is tested before it is saved; otherwise the saved key is used
+      const testResult = await notionTestConnection(typedKey || undefined);
       if (!testResult.success) {
         setNotionTestResult('error');
         setNotionTestError(testResult.message);
         return;
       }
 
-      const searchResult = await notionSearchPages(apiKeys.notion);
+      if (typedKey) {
+        await setSecret('notion', typedKey);
+        setApiKeys((prev) => ({ ...prev, notion: '' }));
+        await refreshStoredKeys();
+      }
+
+      const searchResult = await notionSearchPages();
       if (!searchResult.success) {
         setNotionTestResult('error');
         setNotionTestError(searchResult.error || 'Failed to search pages');
@@ -531,21 +693,18 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
   const handleSave = async () => {
     setIsSaving(true);
     setSaveSuccess(false);
+    setKeyError(null);
     try {
-      // Include API keys in the settings if provided
-      const settingsToSave: Partial<Settings> = { ...localSettings };
-      if (apiKeys.openai) {
-        settingsToSave.openaiApiKey = apiKeys.openai;
-      }
-      if (apiKeys.openrouter) {
-        settingsToSave.openrouterApiKey = apiKeys.openrouter;
-      }
-      if (apiKeys.openaiWhisper) {
-        settingsToSave.openaiWhisperApiKey = apiKeys.openaiWhisper;
-      }
-      if (apiKeys.notion) {
-        settingsToSave.notionApiKey = apiKeys.notion;
+      // API keys go to the secret store, never into the settings file
+      for (const [name, value] of Object.entries(apiKeys) as [SecretName, string][]) {
+        if (value.trim()) {
+          await setSecret(name, value.trim());
+        }
       }
+      setApiKeys({ openai: '', openrouter: '', openaiWhisper: '', notion: '' });
+      await refreshStoredKeys();
+
+      const settingsToSave: Partial<Settings> = { ...localSettings };
       // Save Notion default page settings
       if (notionDefaultPageId) {
         settingsToSave.notionDefaultPageId = notionDefaultPageId;
@@ -561,6 +720,8 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
           router.push('/');
         }
       }, 500);
+    } catch (error) {
+      setKeyError(error instanceof Error ? error.message : 'Failed to save settings');
     } finally {
       setIsSaving(false);
     }
@@ -615,6 +776,21 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
       )}
 
       <div className="p-6 space-y-8">
+      {secretsLocked && (
+        <div className="p-3 rounded-lg bg-warning/10 border border-warning flex items-center justify-between gap-3">
+          <span className="text-sm text-warning">
+            Your API keys are locked. Unlock them to use or change saved keys.
+          </span>
+          <button
+            type="button"
+            onClick={() => requestVaultUnlock()}
+            className="btn-secondary text-sm py-1 px-3"
+          >
+            Unlock…
+          </button>
+        </div>
+      )}
+
       {/* Microphone Settings */}
       <section className="card">
         <h3 className="text-lg font-semibold text-primary mb-4">Microphone</h3>
@@ -843,7 +1019,7 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
             <div className="flex items-center justify-between mb-2">
               <div className="flex items-center gap-2">
                 <span className="font-medium text-text">Cloud Transcription (OpenAI Whisper)</span>
-                {apiKeys.openaiWhisper && (
+                {storedKeys.openaiWhisper && (
                   <span className="text-xs bg-success text-black px-2 py-0.5 rounded">Configured</span>
                 )}
               </div>
@@ -866,7 +1042,7 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                       openaiWhisper: e.target.value,
                     }));
                   }}
-                  placeholder="Enter your OpenAI API key for transcription"
+                  placeholder={storedKeys.openaiWhisper ? 'Saved – enter a new key to replace it' : 'Enter your OpenAI API key for transcription'}
                   className="input w-full pr-20"
                 />
                 <button
@@ -882,9 +1058,20 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                   {showApiKey.openaiWhisper ? 'Hide' : 'Show'}
                 </button>
               </div>
-              <p className="text-xs text-text-muted mt-1">
-                Your API key is stored securely in your system keychain
-              </p>
+              <div className="flex items-center justify-between mt-1">
+                <p className="text-xs text-text-muted">
+                  Your API key is stored securely in your system keychain
+                </p>
+                {storedKeys['openaiWhisper'] && (
+                  <button
+                    type="button"
+                    onClick={() => removeStoredKey('openaiWhisper')}
+                    className="text-xs text-error hover:underline"
+                  >
+                    Remove saved key
+                  </button>
+                )}
+              </div>
             </div>
           </div>
 
@@ -944,6 +1131,9 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                           </div>
                           <div className="text-xs text-text-muted mt-1">
                             Size: {model.size}
+                            {model.recommendedRamMb > 0 && ` · RAM: ${(model.recommendedRamMb / 1024).toFixed(0)} GB+`}
+                            {model.quantization && ` · Quantized (${model.quantization})`}
+                            {model.custom && ` · ${model.installedPath}`}
                           </div>
                         </div>
 
@@ -960,7 +1150,7 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                                 onClick={() => handleDeleteModel(model.id)}
                                 disabled={isDeleting}
                                 className="text-text-muted hover:text-error text-xs flex items-center gap-1 transition-colors"
-                                title="Remove model"
+                                title={model.custom ? 'Unregister model (keeps the file)' : 'Remove model'}
                               >
                                 {isDeleting ? (
                                   <svg className="animate-spin h-3 w-3" viewBox="0 0 24 24">
@@ -981,7 +1171,22 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                                 <path className="opacity-75" fill="currentColor" d="M4 12a8 8 0 018-8V0C5.373 0 0 5.373 0 12h4z" />
                               </svg>
                               Downloading...
+                              <button
+                                onClick={() => handleCancelDownload(`whisper:${model.id}`)}
+                                className="text-text-muted hover:text-error transition-colors"
+                              >
+                                Cancel
+                              </button>
                             </span>
+                          ) : model.custom ? (
+                            <button
+                              onClick={() => handleDeleteModel(model.id)}
+                              disabled={isDeleting}
+                              className="text-error text-xs"
+                              title="The model file is missing"
+                            >
+                              Missing · Unregister
+                            </button>
                           ) : (
                             <button
                               onClick={() => handleDownloadModel(model.id)}
@@ -1007,6 +1212,79 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
               </div>
             )}
 
+            <div className="mt-3">
+              <button
+                onClick={handleAddCustomModel}
+                disabled={isAddingModel}
+                className="btn-secondary text-sm py-2 px-4"
+              >
+                {isAddingModel ? 'Adding...' : 'Add Model File...'}
+              </button>
+              <p className="text-xs text-text-muted mt-1">
+                Use a ggml-*.bin model you already have, such as a fine-tuned or quantized one. The file stays where it is.
+              </p>
+              {customModelError && (
+                <div className="mt-2 p-3 rounded-lg bg-error/10 border border-error/20 text-sm text-error">
+                  {customModelError}
+                </div>
+              )}
+            </div>
+
+            <div className="mt-4">
+              <label className="block text-sm font-medium text-text mb-2">
+                Models Folder
+              </label>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={localSettings.models?.directory || ''}
+                  onChange={(e) => handleChange('models', { ...localSettings.models, directory: e.target.value || null })}
+                  placeholder="~/.voiceintelligence/whisper"
+                  className="input flex-1 font-mono text-sm"
+                />
+                <button
+                  onClick={selectModelsDirectory}
+                  className="btn-secondary px-4 py-2 text-sm"
+                >
+                  Browse
+                </button>
+              </div>
+              <p className="text-xs text-text-muted mt-1">
+                Where models are downloaded to. Takes effect when settings are saved; models already downloaded are not moved.
+              </p>
+            </div>
+
+            <div className="mt-4">
+              <label className="block text-sm font-medium text-text mb-2">
+                Model Catalog
+              </label>
+              <div className="flex gap-2">
+                <input
+                  type="text"
+                  value={localSettings.models?.manifestSource || ''}
+                  onChange={(e) => handleChange('models', { ...localSettings.models, manifestSource: e.target.value || null })}
+                  placeholder="URL or path of a model manifest (optional)"
+                  className="input flex-1 font-mono text-sm"
+                />
+                <button
+                  onClick={handleRefreshCatalog}
+                  disabled={isRefreshingCatalog || !localSettings.models?.manifestSource?.trim()}
+                  className="btn-secondary px-4 py-2 text-sm"
+                >
+                  {isRefreshingCatalog ? 'Refreshing...' : 'Refresh'}
+                </button>
+              </div>
+              <p className="text-xs text-text-muted mt-1">
+                Replaces the built-in list of models with the manifest at this location. The refreshed list is kept until the next refresh.
+              </p>
+              {catalogStatus && <p className="text-success text-xs mt-1">{catalogStatus}</p>}
+              {catalogError && (
+                <div className="mt-2 p-3 rounded-lg bg-error/10 border border-error/20 text-sm text-error">
+                  {catalogError}
+                </div>
+              )}
+            </div>
+
             <details className="mt-4">
               <summary className="cursor-pointer text-xs text-text-muted hover:text-text flex items-center gap-1">
                 <svg className="w-3 h-3" fill="none" viewBox="0 0 24 24" stroke="currentColor">
@@ -1027,6 +1305,16 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
         </div>
       </section>
 
+      {/* Vocabulary prompt and replacement dictionary */}
+      <section className="card">
+        <h3 className="text-lg font-semibold text-primary mb-4">Vocabulary</h3>
+        {/* Saved on its own; keep the panel's copy in step so saving the panel doesn't restore the old vocabulary */}
+        <VocabularyEditor
+          onSaved={(vocabulary) => setLocalSettings((prev) => ({ ...prev, vocabulary }))}
+          disabled={isLoading}
+        />
+      </section>
+
       {/* LLM Provider Settings */}
       <section className="card">
         <h3 className="text-lg font-semibold text-primary mb-4">LLM Provider</h3>
@@ -1171,7 +1459,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                       <div key={modelId} className="mt-3 p-2 rounded bg-secondary/50">
                         <div className="flex items-center justify-between mb-1">
                           <span className="text-sm text-text font-medium">Pulling {modelId}...</span>
-                          <span className="text-xs text-text-muted">{progress.toFixed(0)}%</span>
+                          <span className="text-xs text-text-muted flex items-center gap-2">
+                            {progress.toFixed(0)}%
+                            <button
+                              onClick={() => handleCancelDownload(`ollama:${modelId}`)}
+                              className="hover:text-error transition-colors"
+                            >
+                              Cancel
+                            </button>
+                          </span>
                         </div>
                         <div className="w-full h-2 bg-secondary rounded-full overflow-hidden">
                           <div
@@ -1233,7 +1529,11 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                       [localSettings.llmProvider]: e.target.value,
                     }));
                   }}
-                  placeholder={`Enter your ${localSettings.llmProvider === 'openai' ? 'OpenAI' : 'OpenRouter'} API key`}
+                  placeholder={
+                    storedKeys[localSettings.llmProvider]
+                      ? 'Saved – enter a new key to replace it'
+                      : `Enter your ${localSettings.llmProvider === 'openai' ? 'OpenAI' : 'OpenRouter'} API key`
+                  }
                   className="input w-full pr-20"
                 />
                 <button
@@ -1249,9 +1549,20 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                   {showApiKey[localSettings.llmProvider] ? 'Hide' : 'Show'}
                 </button>
               </div>
-              <p className="text-xs text-text-muted mt-1">
-                Your API key is stored securely in your system keychain
-              </p>
+              <div className="flex items-center justify-between mt-1">
+                <p className="text-xs text-text-muted">
+                  Your API key is stored securely in your system keychain
+                </p>
+                {storedKeys[localSettings.llmProvider] && (
+                  <button
+                    type="button"
+                    onClick={() => removeStoredKey(localSettings.llmProvider as SecretName)}
+                    className="text-xs text-error hover:underline"
+                  >
+                    Remove saved key
+                  </button>
+                )}
+              </div>
             </div>
           )}
 
@@ -1407,7 +1718,7 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                     setNotionTestResult(null);
                     setNotionPages([]);
                   }}
-                  placeholder="Enter your Notion integration token"
+                  placeholder={storedKeys.notion ? 'Saved – enter a new token to replace it' : 'Enter your Notion integration token'}
                   className="input w-full pr-16"
                 />
                 <button
@@ -1435,6 +1746,15 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                 </a>
                 {' '}and share pages with it.
               </p>
+              {storedKeys.notion && (
+                <button
+                  type="button"
+                  onClick={() => removeStoredKey('notion')}
+                  className="text-xs text-error hover:underline mt-1"
+                >
+                  Remove saved key
+                </button>
+              )}
             </div>
 
             {/* Test Connection Button */}
@@ -1442,7 +1762,7 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
               <button
                 type="button"
                 onClick={testNotionAndLoadPages}
-                disabled={isTestingNotion || !apiKeys.notion}
+                disabled={isTestingNotion || !(apiKeys.notion.trim() || storedKeys.notion)}
                 className="btn-secondary text-sm py-2 px-4"
               >
                 {isTestingNotion ? (
@@ -1596,6 +1916,52 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
         </div>
       </section>
 
+      {/* Storage */}
+      <section className="card">
+        <h3 className="text-lg font-semibold text-primary mb-4">Storage</h3>
+        {storageUsage ? (
+          <div className="space-y-2 text-sm">
+            {([
+              ['Whisper models', storageUsage.whisperModels],
+              ['Ollama models', storageUsage.ollamaModels],
+              ['History', storageUsage.history],
+              ['Cached audio', storageUsage.cachedAudio],
+              ['Logs', storageUsage.logs],
+            ] as [string, number | null][]).map(([label, bytes]) => (
+              <div key={label} className="flex items-center justify-between">
+                <span className="text-text">{label}</span>
+                <span className="text-text-muted">
+                  {bytes === null ? 'Ollama not running' : formatBytes(bytes)}
+                </span>
+              </div>
+            ))}
+            <p className="text-xs text-text-muted pt-1">
+              Models in {storageUsage.modelsDirectory}
+              {storageUsage.modelsDirectoryFree !== null &&
+                ` · ${formatBytes(storageUsage.modelsDirectoryFree)} free`}
+            </p>
+            {storageUsage.tempFiles.length > 0 && (
+              <div className="flex items-center justify-between pt-2">
+                <span className="text-text-muted">
+                  {storageUsage.tempFiles.length} leftover temporary{' '}
+                  {storageUsage.tempFiles.length === 1 ? 'file' : 'files'} (
+                  {formatBytes(storageUsage.tempFiles.reduce((sum, f) => sum + f.size, 0))})
+                </span>
+                <button
+                  onClick={handleCleanTempFiles}
+                  disabled={isCleaningTempFiles}
+                  className="btn-secondary text-xs py-1.5 px-3"
+                >
+                  {isCleaningTempFiles ? 'Cleaning...' : 'Clean up'}
+                </button>
+              </div>
+            )}
+          </div>
+        ) : (
+          <p className="text-sm text-text-muted">Calculating storage usage...</p>
+        )}
+      </section>
+
       {/* About Section */}
       <section className="card">
         <h3 className="text-lg font-semibold text-primary mb-4">About</h3>
@@ -1630,7 +1996,6 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
                 <li><strong>Tailwind CSS</strong> - Utility-first CSS framework (MIT)</li>
                 <li><strong>whisper.cpp</strong> - Speech-to-text engine (MIT)</li>
                 <li><strong>jsPDF</strong> - PDF generation library (MIT)</li>
-                <li><strong>OpenAI SDK</strong> - API client (MIT)</li>
               </ul>
               <p className="mt-3 pt-2 border-t border-secondary">
                 For LLM processing: OpenAI API, OpenRouter, or Ollama (local)
@@ -1640,6 +2005,12 @@ export function SettingsPanel({ settings, onSave, isLoading, onClose, showHeader
         </div>
       </section>
 
+      {keyError && (
+        <div className="p-3 rounded-lg bg-error/10 border border-error/20 text-sm text-error">
+          {keyError}
+        </div>
+      )}
+
       {/* Save Button */}
       <div className="flex justify-end gap-4">
         <button
@@ -1,7 +1,8 @@
 'use client';
 
 import { useState, useEffect, useCallback } from 'react';
-import type { Settings } from '../types';
+import type { Settings, InstallResult } from '../types';
+import { errorMessage } from '../lib/errors';
 
 interface SetupWizardProps {
   settings: Settings;
@@ -25,7 +26,8 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
   const [step, setStep] = useState<Step>('welcome');
   const [microphones, setMicrophones] = useState<AudioDevice[]>([]);
   const [selectedMic, setSelectedMic] = useState<string>(settings.selectedMicrophone || '');
-  const [openaiKey, setOpenaiKey] = useState(settings.openaiApiKey || '');
+  const [openaiKey, setOpenaiKey] = useState('');
+  const [keyError, setKeyError] = useState<string | null>(null);
   const [isTestingMic, setIsTestingMic] = useState(false);
   const [micTestResult, setMicTestResult] = useState<'success' | 'error' | null>(null);
   const [isInstallingWhisper, setIsInstallingWhisper] = useState(false);
@@ -36,16 +38,12 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
 
   const loadMicrophones = useCallback(async () => {
     try {
-      // Request permission first
-      await navigator.mediaDevices.getUserMedia({ audio: true });
-
-      const devices = await navigator.mediaDevices.enumerateDevices();
-      const audioInputs = devices
-        .filter(device => device.kind === 'audioinput')
-        .map(device => ({
-          deviceId: device.deviceId,
-          label: device.label || `Microphone ${device.deviceId.slice(0, 8)}`,
-        }));
+      const { listInputDevices } = await import('../lib/api');
+      const devices = await listInputDevices();
+      const audioInputs = devices.map(device => ({
+        deviceId: device.id,
+        label: device.name,
+      }));
 
       setMicrophones(audioInputs);
       if (audioInputs.length > 0 && !selectedMic) {
@@ -82,36 +80,9 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
     setMicTestResult(null);
 
     try {
-      const stream = await navigator.mediaDevices.getUserMedia({
-        audio: { deviceId: selectedMic ? { exact: selectedMic } : undefined }
-      });
-
-      // Create audio context to analyze
-      const audioContext = new AudioContext();
-      const analyser = audioContext.createAnalyser();
-      const source = audioContext.createMediaStreamSource(stream);
-      source.connect(analyser);
-
-      analyser.fftSize = 256;
-      const dataArray = new Uint8Array(analyser.frequencyBinCount);
-
-      // Check for audio input over 2 seconds
-      let hasSound = false;
-      const checkInterval = setInterval(() => {
-        analyser.getByteFrequencyData(dataArray);
-        const average = dataArray.reduce((a, b) => a + b) / dataArray.length;
-        if (average > 10) {
-          hasSound = true;
-        }
-      }, 100);
-
-      await new Promise(resolve => setTimeout(resolve, 2000));
-      clearInterval(checkInterval);
-
-      // Cleanup
-      stream.getTracks().forEach(track => track.stop());
-      audioContext.close();
-
+      // Record natively for 2 seconds and check whether any sound came in
+      const { testMicrophone: testNativeMicrophone } = await import('../lib/api');
+      const hasSound = await testNativeMicrophone(selectedMic);
       setMicTestResult(hasSound ? 'success' : 'error');
     } catch (error) {
       console.error('Mic test failed:', error);
@@ -129,19 +100,13 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
       const { invoke } = await import('@tauri-apps/api/core');
 
       setWhisperInstallStatus('Downloading whisper.cpp...');
-      const result = await invoke<{ success: boolean; message: string; path: string | null }>('install_whisper');
-
-      if (result.success) {
-        setWhisperInstallStatus('Installation complete!');
-        setWhisperAvailable(true);
-        if (result.path) {
-          setWhisperPath(result.path);
-        }
-      } else {
-        setWhisperInstallStatus(`Installation failed: ${result.message}`);
-      }
+      const result = await invoke<InstallResult>('install_whisper');
+
+      setWhisperInstallStatus('Installation complete!');
+      setWhisperAvailable(true);
+      setWhisperPath(result.path);
     } catch (error) {
-      setWhisperInstallStatus(`Error: ${error instanceof Error ? error.message : 'Installation failed'}`);
+      setWhisperInstallStatus(`Installation failed: ${errorMessage(error, 'Unknown error')}`);
     } finally {
       setIsInstallingWhisper(false);
     }
@@ -149,13 +114,19 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
 
   const handleComplete = async () => {
     setIsSaving(true);
+    setKeyError(null);
     try {
+      if (openaiKey.trim()) {
+        const { setSecret } = await import('../lib/api');
+        await setSecret('openai', openaiKey.trim());
+      }
       await onComplete({
         selectedMicrophone: selectedMic,
-        openaiApiKey: openaiKey || undefined,
         whisperPath: whisperPath || undefined,
         setupComplete: true,
       });
+    } catch (err) {
+      setKeyError(errorMessage(err, 'Failed to save the API key'));
     } finally {
       setIsSaving(false);
     }
@@ -457,6 +428,12 @@ export function SetupWizard({ settings, onComplete, onSkip }: SetupWizardProps)
               </ul>
             </div>
 
+            {keyError && (
+              <div className="p-3 bg-error/10 border border-error rounded-lg text-error text-sm mb-4">
+                {keyError}
+              </div>
+            )}
+
             <button
               onClick={handleComplete}
               disabled={isSaving}
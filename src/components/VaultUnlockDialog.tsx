@@ -0,0 +1,134 @@
+'use client';
+
+import { useState, useEffect } from 'react';
+import { getVaultStatus, unlockVault, onVaultUnlockRequested, type VaultStatus } from '../lib/api';
+import { errorCode, errorMessage } from '../lib/errors';
+
+/**
+ * Passphrase prompt for the secret vault. Opens when the tray
+ * "Secrets Locked – Unlock…" item is clicked or requestVaultUnlock is called.
+ * The first unlock creates the vault, so the passphrase is asked for twice.
+ */
+export function VaultUnlockDialog() {
+  const [isOpen, setIsOpen] = useState(false);
+  const [status, setStatus] = useState<VaultStatus | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmation, setConfirmation] = useState('');
+  const [isUnlocking, setIsUnlocking] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onVaultUnlockRequested(async () => {
+      try {
+        const current = await getVaultStatus();
+        // Secrets are in the OS keyring, or the vault is already open
+        if (!current || !current.locked) return;
+        setStatus(current);
+        setPassphrase('');
+        setConfirmation('');
+        setError(null);
+        setIsOpen(true);
+      } catch (err) {
+        console.error('Failed to get vault status:', err);
+      }
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  if (!isOpen || !status) return null;
+
+  const isNewVault = !status.initialized;
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!passphrase) {
+      setError('Please enter your passphrase');
+      return;
+    }
+    if (isNewVault && passphrase !== confirmation) {
+      setError('Passphrases do not match');
+      return;
+    }
+
+    setIsUnlocking(true);
+    setError(null);
+    try {
+      await unlockVault(passphrase);
+      setIsOpen(false);
+    } catch (err) {
+      setError(
+        errorCode(err) === 'INCORRECT_PASSPHRASE'
+          ? 'Incorrect passphrase'
+          : errorMessage(err, 'Failed to unlock secrets')
+      );
+    } finally {
+      setPassphrase('');
+      setConfirmation('');
+      setIsUnlocking(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
+      <form
+        onSubmit={handleSubmit}
+        className="bg-background border border-secondary rounded-lg p-6 max-w-md w-full mx-4 shadow-xl"
+      >
+        <h3 className="text-lg font-semibold text-text mb-3">
+          {isNewVault ? 'Protect Your API Keys' : 'Unlock Secrets'}
+        </h3>
+        <p className="text-text-muted text-sm mb-4">
+          {isNewVault
+            ? 'Choose a passphrase to encrypt your API keys. It is needed each time the secrets are unlocked and cannot be recovered.'
+            : 'Enter your passphrase to use the stored API keys.'}
+        </p>
+
+        <div className="space-y-3 mb-4">
+          <input
+            type="password"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            placeholder="Passphrase"
+            className="input w-full"
+            autoFocus
+          />
+          {isNewVault && (
+            <input
+              type="password"
+              value={confirmation}
+              onChange={(e) => setConfirmation(e.target.value)}
+              placeholder="Confirm passphrase"
+              className="input w-full"
+            />
+          )}
+        </div>
+
+        {error && (
+          <div className="mb-4 p-3 rounded-lg bg-error/10 border border-error/20 text-sm text-error">
+            {error}
+          </div>
+        )}
+
+        <div className="flex justify-end gap-3">
+          <button
+            type="button"
+            onClick={() => setIsOpen(false)}
+            className="btn-secondary px-4 py-2 text-sm"
+          >
+            Cancel
+          </button>
+          <button
+            type="submit"
+            disabled={isUnlocking}
+            className="btn-primary px-4 py-2 text-sm"
+          >
+            {isUnlocking ? 'Unlocking...' : isNewVault ? 'Create' : 'Unlock'}
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+}
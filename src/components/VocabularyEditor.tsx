@@ -0,0 +1,167 @@
+'use client';
+
+import { useState, useEffect } from 'react';
+import { getVocabulary, setVocabulary } from '../lib/api';
+import { errorMessage } from '../lib/errors';
+import type { VocabularyReplacement, VocabularySettings } from '../types';
+
+interface VocabularyEditorProps {
+  // Called with the vocabulary as stored by the backend
+  onSaved?: (vocabulary: VocabularySettings) => void;
+  disabled?: boolean;
+}
+
+/**
+ * Edits the vocabulary prompt and replacement dictionary of the active
+ * profile. Saved on its own with set_vocabulary, which trims the entries and
+ * drops duplicates.
+ */
+export function VocabularyEditor({ onSaved, disabled }: VocabularyEditorProps) {
+  const [terms, setTerms] = useState('');
+  const [replacements, setReplacements] = useState<VocabularyReplacement[]>([]);
+  const [isLoading, setIsLoading] = useState(true);
+  const [isSaving, setIsSaving] = useState(false);
+  const [isDirty, setIsDirty] = useState(false);
+  const [saveSuccess, setSaveSuccess] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const showVocabulary = (vocabulary: VocabularySettings) => {
+    setTerms(vocabulary.terms.join('\n'));
+    setReplacements(vocabulary.replacements);
+    setIsDirty(false);
+  };
+
+  useEffect(() => {
+    getVocabulary()
+      .then(showVocabulary)
+      .catch((err) => setError(errorMessage(err, 'Failed to load vocabulary')))
+      .finally(() => setIsLoading(false));
+  }, []);
+
+  const edited = () => {
+    setIsDirty(true);
+    setSaveSuccess(false);
+  };
+
+  const updateReplacement = (index: number, change: Partial<VocabularyReplacement>) => {
+    setReplacements((prev) => prev.map((r, i) => (i === index ? { ...r, ...change } : r)));
+    edited();
+  };
+
+  const removeReplacement = (index: number) => {
+    setReplacements((prev) => prev.filter((_, i) => i !== index));
+    edited();
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      const saved = await setVocabulary({
+        terms: terms.split('\n'),
+        // Rows left empty are dropped by the backend
+        replacements,
+      });
+      showVocabulary(saved);
+      setSaveSuccess(true);
+      onSaved?.(saved);
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to save vocabulary'));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  if (isLoading) {
+    return <p className="text-sm text-text-muted">Loading vocabulary...</p>;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <label className="block text-sm font-medium text-text mb-2">
+          Vocabulary
+        </label>
+        <textarea
+          value={terms}
+          onChange={(e) => {
+            setTerms(e.target.value);
+            edited();
+          }}
+          placeholder={'One name or term per line, e.g.\nKubernetes\nPostgreSQL'}
+          className="input w-full h-28 resize-none font-mono text-sm"
+          disabled={disabled}
+        />
+        <p className="text-xs text-text-muted mt-1">
+          Helps local Whisper spell names and jargon. Put the most important terms first; whisper only reads the start of a long list.
+        </p>
+      </div>
+
+      <div>
+        <label className="block text-sm font-medium text-text mb-2">
+          Replacements
+        </label>
+        <div className="space-y-2">
+          {replacements.map((replacement, index) => (
+            <div key={index} className="flex items-center gap-2">
+              <input
+                type="text"
+                value={replacement.from}
+                onChange={(e) => updateReplacement(index, { from: e.target.value })}
+                placeholder="Heard as"
+                className="input flex-1"
+                disabled={disabled}
+              />
+              <span className="text-text-muted">→</span>
+              <input
+                type="text"
+                value={replacement.to}
+                onChange={(e) => updateReplacement(index, { to: e.target.value })}
+                placeholder="Replace with"
+                className="input flex-1"
+                disabled={disabled}
+              />
+              <button
+                onClick={() => removeReplacement(index)}
+                className="btn-secondary px-3 py-2 text-sm"
+                disabled={disabled}
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+        <button
+          onClick={() => {
+            setReplacements((prev) => [...prev, { from: '', to: '' }]);
+            edited();
+          }}
+          className="btn-secondary px-3 py-2 text-sm mt-2"
+          disabled={disabled}
+        >
+          Add Replacement
+        </button>
+        <p className="text-xs text-text-muted mt-1">
+          Whole words or phrases. A lowercase entry matches any capitalisation.
+        </p>
+      </div>
+
+      {error && (
+        <div className="p-3 rounded-lg bg-error/10 border border-error/20 text-sm text-error">
+          {error}
+        </div>
+      )}
+
+      <div className="flex items-center justify-end gap-3">
+        {saveSuccess && <span className="text-sm text-success">Vocabulary saved</span>}
+        <button
+          onClick={handleSave}
+          disabled={disabled || isSaving || !isDirty}
+          className="btn-primary px-4 py-2 text-sm"
+        >
+          {isSaving ? 'Saving...' : 'Save Vocabulary'}
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -5,3 +5,5 @@ export { SettingsPanel } from './SettingsPanel';
 export { HistoryView } from './HistoryView';
 export { SetupWizard } from './SetupWizard';
 export { MicrophoneSelector } from './MicrophoneSelector';
+export { VaultUnlockDialog } from './VaultUnlockDialog';
+export { VocabularyEditor } from './VocabularyEditor';
@@ -2,3 +2,5 @@ export { useSettings } from './useSettings';
 export { useHotkey } from './useHotkey';
 export { useLLM } from './useLLM';
 export { useRecording } from './useRecording';
+export { useAudioImport } from './useAudioImport';
+export { useTranscriptionQueue } from './useTranscriptionQueue';
@@ -0,0 +1,107 @@
+'use client';
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import type { ImportedAudioFile } from '../types';
+import { importAudioFiles, pickAndImportAudioFiles, onAudioImportQueued } from '../lib/api';
+import { errorMessage } from '../lib/errors';
+
+interface UseAudioImportReturn {
+  queue: ImportedAudioFile[];
+  current: ImportedAudioFile | null;
+  error: string | null;
+  pickFiles: () => Promise<void>;
+}
+
+/**
+ * Queue of imported audio files, fed by the file picker, files dropped on the
+ * window and the tray "Transcribe File…" item. Files are handed to
+ * `processFile` one at a time.
+ */
+export function useAudioImport(
+  processFile: (file: ImportedAudioFile) => Promise<void>
+): UseAudioImportReturn {
+  const [queue, setQueue] = useState<ImportedAudioFile[]>([]);
+  const [current, setCurrent] = useState<ImportedAudioFile | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const processFileRef = useRef(processFile);
+
+  // Keep callback ref updated
+  useEffect(() => {
+    processFileRef.current = processFile;
+  }, [processFile]);
+
+  const reportRejected = useCallback((rejected: { path: string; error: unknown }[]) => {
+    if (rejected.length > 0) {
+      setError(rejected.map((r) => `${r.path}: ${errorMessage(r.error)}`).join('\n'));
+    }
+  }, []);
+
+  // Accepted files arrive through the event, whoever imported them
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onAudioImportQueued((files) => {
+      setQueue((prev) => [...prev, ...files]);
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  // Files dropped on the window
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    import('@tauri-apps/api/webview')
+      .then(({ getCurrentWebview }) =>
+        getCurrentWebview().onDragDropEvent(async (event) => {
+          if (event.payload.type !== 'drop' || event.payload.paths.length === 0) return;
+          try {
+            setError(null);
+            const result = await importAudioFiles(event.payload.paths);
+            reportRejected(result.rejected);
+          } catch (err) {
+            setError(errorMessage(err, 'Failed to import audio files'));
+          }
+        })
+      )
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch(() => {
+        // Not running inside Tauri
+      });
+    return () => unlisten?.();
+  }, [reportRejected]);
+
+  // Work through the queue one file at a time
+  useEffect(() => {
+    if (current || queue.length === 0) return;
+
+    const [next, ...rest] = queue;
+    setQueue(rest);
+    setCurrent(next);
+    processFileRef.current(next)
+      .catch((err) => {
+        console.error(`Failed to process ${next.path}:`, err);
+      })
+      .finally(() => setCurrent(null));
+  }, [queue, current]);
+
+  const pickFiles = useCallback(async () => {
+    try {
+      setError(null);
+      const result = await pickAndImportAudioFiles();
+      if (result) {
+        reportRejected(result.rejected);
+      }
+    } catch (err) {
+      setError(errorMessage(err, 'Failed to import audio files'));
+    }
+  }, [reportRejected]);
+
+  return {
+    queue,
+    current,
+    error,
+    pickFiles,
+  };
+}
@@ -14,7 +14,7 @@ interface UseLLMReturn {
     mode: EnrichmentMode,
     options?: EnrichmentOptions
   ) => Promise<string>;
-  configureProvider: (provider: LLMProvider, apiKey: string, model?: string) => void;
+  configureProvider: (provider: LLMProvider, model?: string) => void;
   setLanguage: (language: Language) => void;
   clearError: () => void;
 }
@@ -57,6 +57,9 @@ export function useLLM(defaultProvider?: LLMProvider): UseLLMReturn {
             case 'INVALID_API_KEY':
               errorMessage = `Invalid API key for ${err.provider}. Please check your key in Settings`;
               break;
+            case 'VAULT_LOCKED':
+              errorMessage = 'Your API keys are locked. Unlock them with your passphrase and try again';
+              break;
             case 'RATE_LIMITED':
               errorMessage = 'Rate limit exceeded. Please wait a moment and try again';
               break;
@@ -78,9 +81,9 @@ export function useLLM(defaultProvider?: LLMProvider): UseLLMReturn {
   );
 
   const configureProvider = useCallback(
-    (provider: LLMProvider, apiKey: string, model?: string) => {
+    (provider: LLMProvider, model?: string) => {
       try {
-        routerRef.current.configureProvider(provider, apiKey, model);
+        routerRef.current.configureProvider(provider, model);
         setError(null);
       } catch (err) {
         const message = err instanceof Error ? err.message : 'Failed to configure provider';
@@ -3,7 +3,8 @@
 import { useState, useEffect, useCallback } from 'react';
 import type { Settings } from '../types';
 import { DEFAULT_SETTINGS } from '../lib/config';
-import { getSettings, saveSettings, cleanupOldHistory } from '../lib/api';
+import { getSettings, saveSettings, cleanupOldHistory, onProfileSwitched } from '../lib/api';
+import { errorMessage } from '../lib/errors';
 
 interface UseSettingsReturn {
   settings: Settings;
@@ -42,6 +43,18 @@ export function useSettings(): UseSettingsReturn {
     loadSettings();
   }, []);
 
+  // Reload when the profile changes, from the settings page or the tray
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onProfileSwitched((_profile, switched) => {
+      setError(null);
+      setSettings({ ...DEFAULT_SETTINGS, ...switched });
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
   const updateSettings = useCallback(async (updates: Partial<Settings>) => {
     try {
       setError(null);
@@ -49,8 +62,10 @@ export function useSettings(): UseSettingsReturn {
       setSettings(newSettings);
       await saveSettings(newSettings);
     } catch (err) {
+      // The backend rejected the values; keep showing what is stored
+      setSettings(settings);
       console.error('Failed to save settings:', err);
-      setError('Failed to save settings');
+      setError(errorMessage(err));
       throw err;
     }
   }, [settings]);
@@ -58,8 +73,10 @@ export function useSettings(): UseSettingsReturn {
   const resetSettings = useCallback(async () => {
     try {
       setError(null);
-      setSettings(DEFAULT_SETTINGS);
       await saveSettings(DEFAULT_SETTINGS);
+      // Sections without frontend defaults are filled in by the backend
+      const savedSettings = await getSettings();
+      setSettings({ ...DEFAULT_SETTINGS, ...savedSettings });
     } catch (err) {
       console.error('Failed to reset settings:', err);
       setError('Failed to reset settings');
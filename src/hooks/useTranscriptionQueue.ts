@@ -0,0 +1,111 @@
+'use client';
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import type { TranscriptionJob, TranscriptionJobRequest } from '../types';
+import {
+  enqueueTranscription,
+  listTranscriptionJobs,
+  removeTranscriptionJob,
+  cancelTranscription,
+  onTranscriptionJobChanged,
+  onTranscriptionProgress,
+} from '../lib/api';
+
+interface UseTranscriptionQueueReturn {
+  // Queued and running jobs, oldest first
+  active: TranscriptionJob[];
+  // Last reported whisper progress per running job id
+  progress: Record<string, number>;
+  enqueue: (request: TranscriptionJobRequest) => Promise<TranscriptionJob>;
+  cancel: (jobId: string) => Promise<void>;
+}
+
+const isActive = (job: TranscriptionJob) => job.state === 'queued' || job.state === 'running';
+
+/**
+ * Mirror of the backend transcription queue. Completed and failed jobs are
+ * handed to `handleFinished` one at a time and removed from the queue once it
+ * resolves, including jobs that finished while the app was not running.
+ */
+export function useTranscriptionQueue(
+  handleFinished: (job: TranscriptionJob) => Promise<void>
+): UseTranscriptionQueueReturn {
+  const [jobs, setJobs] = useState<TranscriptionJob[]>([]);
+  const [progress, setProgress] = useState<Record<string, number>>({});
+  const [finished, setFinished] = useState<TranscriptionJob[]>([]);
+  const [handling, setHandling] = useState<string | null>(null);
+  const handleFinishedRef = useRef(handleFinished);
+  const seenFinishedRef = useRef(new Set<string>());
+
+  // Keep callback ref updated
+  useEffect(() => {
+    handleFinishedRef.current = handleFinished;
+  }, [handleFinished]);
+
+  const receive = useCallback((job: TranscriptionJob) => {
+    setJobs((prev) => {
+      const rest = prev.filter((j) => j.id !== job.id);
+      return isActive(job) ? [...rest, job].sort((a, b) => a.createdAt - b.createdAt) : rest;
+    });
+    if (!isActive(job)) {
+      setProgress((prev) => Object.fromEntries(Object.entries(prev).filter(([id]) => id !== job.id)));
+    }
+
+    if (job.state === 'cancelled') {
+      removeTranscriptionJob(job.id).catch(() => {});
+    } else if (!isActive(job) && !seenFinishedRef.current.has(job.id)) {
+      seenFinishedRef.current.add(job.id);
+      setFinished((prev) => [...prev, job]);
+    }
+  }, []);
+
+  // Pick up where the last session left off, then follow state changes
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onTranscriptionJobChanged(receive).then((fn) => {
+      unlisten = fn;
+    });
+    listTranscriptionJobs()
+      .then((existing) => existing.forEach(receive))
+      .catch((err) => console.error('Failed to load transcription queue:', err));
+    return () => unlisten?.();
+  }, [receive]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    onTranscriptionProgress(({ jobId, percent }) => {
+      setProgress((prev) => ({ ...prev, [jobId]: percent }));
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  // Hand finished jobs over one at a time
+  useEffect(() => {
+    if (handling || finished.length === 0) return;
+
+    const [next, ...rest] = finished;
+    setFinished(rest);
+    setHandling(next.id);
+    handleFinishedRef.current(next)
+      .catch((err) => {
+        console.error(`Failed to handle transcription job ${next.id}:`, err);
+      })
+      .finally(() => {
+        removeTranscriptionJob(next.id).catch(() => {});
+        setHandling(null);
+      });
+  }, [finished, handling]);
+
+  const enqueue = useCallback((request: TranscriptionJobRequest) => enqueueTranscription(request), []);
+
+  const cancel = useCallback((jobId: string) => cancelTranscription(jobId), []);
+
+  return {
+    active: jobs,
+    progress,
+    enqueue,
+    cancel,
+  };
+}
@@ -1,13 +1,37 @@
 // Tauri IPC Wrapper - Centralizes all communication with Rust backend
 
 import { invoke } from '@tauri-apps/api/core';
-import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { emit, listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { writeText, readText } from '@tauri-apps/plugin-clipboard-manager';
 import { register, unregister, isRegistered } from '@tauri-apps/plugin-global-shortcut';
 import { Store } from '@tauri-apps/plugin-store';
-import { save } from '@tauri-apps/plugin-dialog';
+import { save, open } from '@tauri-apps/plugin-dialog';
 import { writeTextFile, writeFile, readTextFile, exists, mkdir, BaseDirectory } from '@tauri-apps/plugin-fs';
-import type { Settings, WhisperModel, DownloadProgress, DownloadResult } from '../types';
+import type {
+  Settings,
+  WhisperModel,
+  DownloadProgress,
+  DownloadResult,
+  OllamaPullResult,
+  DownloadInfo,
+  StorageUsage,
+  TempFileCleanup,
+  AppErrorCode,
+  InputDevice,
+  AudioLevel,
+  RecordingResult,
+  RecordingStatus,
+  ImportedAudioFile,
+  AudioImportResult,
+  SubtitleOptions,
+  SubtitleExport,
+  WhisperOptions,
+  VocabularySettings,
+  TranscriptionProgress,
+  TranscriptionJob,
+  TranscriptionJobRequest,
+} from '../types';
+import { errorMessage, errorCode } from './errors';
 import { generatePdfAsBytes, type PdfOptions } from './pdf';
 import {
   NotionError,
@@ -18,9 +42,15 @@ import {
 // Re-export Notion types and classes for external use
 export { NotionError };
 export type { CreatePageOptions, CreatePageResult };
-import type { OllamaServiceStatus, OllamaModel, OllamaPullProgress } from '../types/llm';
-
-// Store instance for non-sensitive settings
+import type {
+  OllamaServiceStatus,
+  OllamaModel,
+  OllamaPullProgress,
+  LLMCompletionRequest,
+  LLMCompletionResponse,
+} from '../types/llm';
+
+// Store instance for the transcription history
 let store: Store | null = null;
 
 async function getStore(): Promise<Store> {
@@ -34,22 +64,25 @@ async function getStore(): Promise<Store> {
 // Settings Management
 // ============================================
 
+/**
+ * Settings of the active profile, from the backend settings store
+ */
 export async function getSettings(): Promise<Settings | null> {
   try {
-    const s = await getStore();
-    const settings = await s.get<Settings>('settings');
-    return settings || null;
+    return await invoke<Settings>('get_settings');
   } catch (error) {
     console.error('Failed to get settings:', error);
     return null;
   }
 }
 
+/**
+ * Validate and save the settings of the active profile. Rejects with the
+ * backend's error if a value is invalid.
+ */
 export async function saveSettings(settings: Settings): Promise<void> {
   try {
-    const s = await getStore();
-    await s.set('settings', settings);
-    await s.save();
+    await invoke('save_settings', { settings });
   } catch (error) {
     console.error('Failed to save settings:', error);
     throw error;
@@ -57,161 +90,289 @@ export async function saveSettings(settings: Settings): Promise<void> {
 }
 
 // ============================================
-// Hotkey Management
+// Tauri Commands (IPC to Rust)
 // ============================================
 
-export async function registerHotkey(
-  shortcut: string,
-  onPress: () => void,
-  onRelease?: () => void
-): Promise<void> {
+export async function getAppVersion(): Promise<string> {
   try {
-    // In Tauri v2, the callback receives an event with state ("Pressed" or "Released")
-    // Push-to-talk: onPress starts, onRelease stops
-    await register(shortcut, (event) => {
-      if (event.state === 'Pressed') {
-        onPress();
-      } else if (event.state === 'Released' && onRelease) {
-        onRelease();
-      }
-    });
+    return await invoke<string>('get_app_version');
   } catch (error) {
-    console.error('Failed to register hotkey:', error);
-    throw error;
+    console.error('Failed to get app version:', error);
+    return '0.0.0';
   }
 }
 
-export async function unregisterHotkey(shortcut: string): Promise<void> {
-  try {
-    await unregister(shortcut);
-  } catch (error) {
-    console.error('Failed to unregister hotkey:', error);
-    throw error;
-  }
+/**
+ * Vocabulary prompt and replacement dictionary of the active profile
+ */
+export async function getVocabulary(): Promise<VocabularySettings> {
+  return invoke<VocabularySettings>('get_vocabulary');
+}
+
+/**
+ * Replace the vocabulary of the active profile. Resolves to the stored
+ * version, trimmed and without duplicates.
+ */
+export async function setVocabulary(vocabulary: VocabularySettings): Promise<VocabularySettings> {
+  return invoke<VocabularySettings>('set_vocabulary', { vocabulary });
+}
+
+// ============================================
+// Secret Storage (API keys kept in the backend)
+// ============================================
+
+export type SecretName = 'openai' | 'openrouter' | 'openaiWhisper' | 'notion';
+
+export interface SecretStatus {
+  name: SecretName;
+  configured: boolean;
+  backend: string;
+  locked: boolean;
+}
+
+export interface VaultStatus {
+  initialized: boolean;
+  locked: boolean;
+}
+
+export async function setSecret(name: SecretName, value: string): Promise<SecretStatus> {
+  return invoke<SecretStatus>('set_secret', { name, value });
+}
+
+export async function getSecretStatus(name?: SecretName): Promise<SecretStatus[]> {
+  return invoke<SecretStatus[]>('get_secret_status', { name });
+}
+
+export async function deleteSecret(name: SecretName): Promise<SecretStatus> {
+  return invoke<SecretStatus>('delete_secret', { name });
 }
 
-export async function isHotkeyRegistered(shortcut: string): Promise<boolean> {
+/**
+ * Whether a key is stored. A key in a locked vault counts, since using it
+ * prompts for the passphrase.
+ */
+export async function hasSecret(name: SecretName): Promise<boolean> {
   try {
-    return await isRegistered(shortcut);
-  } catch (error) {
-    console.error('Failed to check hotkey registration:', error);
+    const [status] = await getSecretStatus(name);
+    return status.configured || status.locked;
+  } catch {
     return false;
   }
 }
 
+/**
+ * Vault status, or null when secrets are kept in the OS keyring
+ */
+export async function getVaultStatus(): Promise<VaultStatus | null> {
+  return invoke<VaultStatus | null>('get_vault_status');
+}
+
+export async function unlockVault(passphrase: string): Promise<VaultStatus> {
+  return invoke<VaultStatus>('unlock_vault', { passphrase });
+}
+
+export async function lockVault(): Promise<VaultStatus> {
+  return invoke<VaultStatus>('lock_vault');
+}
+
 // ============================================
-// Clipboard Operations
+// Hosted LLM and Whisper API (keys stay in the backend)
 // ============================================
 
-export async function copyToClipboard(text: string): Promise<void> {
-  try {
-    await writeText(text);
-  } catch (error) {
-    console.error('Failed to copy to clipboard:', error);
-    throw error;
-  }
+export type HostedLLMProvider = 'openai' | 'openrouter';
+
+export async function llmChatCompletion(
+  provider: HostedLLMProvider,
+  request: LLMCompletionRequest & { model: string }
+): Promise<LLMCompletionResponse> {
+  return invoke<LLMCompletionResponse>('llm_chat_completion', { provider, request });
 }
 
-export async function readFromClipboard(): Promise<string> {
-  try {
-    const text = await readText();
-    return text || '';
-  } catch (error) {
-    console.error('Failed to read from clipboard:', error);
-    throw error;
-  }
+export interface CloudTranscription {
+  text: string;
+  language: string;
+  duration: number;
+}
+
+/**
+ * Transcribe a file with the OpenAI Whisper API, using the stored Whisper
+ * API key or the OpenAI key
+ */
+export async function transcribeWithOpenAI(
+  audioPath: string,
+  language: string
+): Promise<CloudTranscription> {
+  return invoke<CloudTranscription>('transcribe_with_openai', { audioPath, language });
 }
 
 // ============================================
-// File Operations
+// Native Audio Capture
 // ============================================
 
-export async function saveToFile(
-  content: string,
-  suggestedName?: string
-): Promise<string | null> {
-  try {
-    const filePath = await save({
-      defaultPath: suggestedName || `transcript-${Date.now()}.md`,
-      filters: [
-        { name: 'Markdown', extensions: ['md'] },
-        { name: 'Text', extensions: ['txt'] },
-        { name: 'All Files', extensions: ['*'] },
-      ],
-    });
+export async function listInputDevices(): Promise<InputDevice[]> {
+  return invoke<InputDevice[]>('list_input_devices');
+}
 
-    if (filePath) {
-      await writeTextFile(filePath, content);
-      return filePath;
-    }
-    return null;
-  } catch (error) {
-    console.error('Failed to save file:', error);
-    throw error;
-  }
+/**
+ * Start recording natively. Uses the microphone from settings unless a device id is given.
+ */
+export async function startRecording(deviceId?: string): Promise<RecordingStatus> {
+  return invoke<RecordingStatus>('start_recording', { deviceId });
 }
 
-export async function saveToAppData(
-  filename: string,
-  content: string
-): Promise<void> {
-  try {
-    // Ensure directory exists
-    const dirExists = await exists('transcripts', { baseDir: BaseDirectory.AppData });
-    if (!dirExists) {
-      await mkdir('transcripts', { baseDir: BaseDirectory.AppData, recursive: true });
-    }
+/**
+ * Stop recording. The result points at a 16 kHz mono WAV in the app cache.
+ */
+export async function stopRecording(): Promise<RecordingResult> {
+  return invoke<RecordingResult>('stop_recording');
+}
 
-    await writeTextFile(`transcripts/${filename}`, content, {
-      baseDir: BaseDirectory.AppData,
-    });
-  } catch (error) {
-    console.error('Failed to save to app data:', error);
-    throw error;
-  }
+export async function cancelRecording(): Promise<void> {
+  return invoke<void>('cancel_recording');
+}
+
+export async function getRecordingStatus(): Promise<RecordingStatus> {
+  return invoke<RecordingStatus>('get_recording_status');
 }
 
-export async function readFromAppData(filename: string): Promise<string | null> {
+/**
+ * Record from a device for a short while and report whether any sound was picked up
+ */
+export async function testMicrophone(deviceId: string, durationMs = 2000): Promise<boolean> {
+  let heardSound = false;
+  const unlisten = await onAudioLevel((level) => {
+    if (level.peak > 0.05) {
+      heardSound = true;
+    }
+  });
+
   try {
-    const content = await readTextFile(`transcripts/${filename}`, {
-      baseDir: BaseDirectory.AppData,
-    });
-    return content;
-  } catch (error) {
-    console.error('Failed to read from app data:', error);
-    return null;
+    await startRecording(deviceId || undefined);
+    await new Promise((resolve) => setTimeout(resolve, durationMs));
+    await cancelRecording();
+  } finally {
+    unlisten();
   }
+  return heardSound;
 }
 
 // ============================================
-// Tauri Commands (IPC to Rust)
+// Audio File Import
 // ============================================
 
-export async function getAppVersion(): Promise<string> {
-  try {
-    return await invoke<string>('get_app_version');
-  } catch (error) {
-    console.error('Failed to get app version:', error);
-    return '0.0.0';
-  }
+// Keep in sync with SUPPORTED_EXTENSIONS in src-tauri/src/audio.rs
+export const AUDIO_FILE_EXTENSIONS = [
+  'wav', 'mp3', 'm4a', 'mp4', 'aac', 'flac', 'ogg', 'oga', 'opus', 'webm', 'mkv', 'caf', 'aif', 'aiff',
+];
+
+/**
+ * Validate audio files and queue them for transcription. Accepted files are
+ * also announced through `onAudioImportQueued`.
+ */
+export async function importAudioFiles(paths: string[]): Promise<AudioImportResult> {
+  return invoke<AudioImportResult>('import_audio_files', { paths });
 }
 
-export async function invokeGetSettings(): Promise<Settings | null> {
-  try {
-    return await invoke<Settings>('get_settings');
-  } catch (error) {
-    console.error('Failed to invoke get_settings:', error);
-    return null;
-  }
+/**
+ * Let the user pick audio files and queue them. Returns null if the dialog was cancelled.
+ */
+export async function pickAndImportAudioFiles(): Promise<AudioImportResult | null> {
+  const selected = await open({
+    title: 'Transcribe Audio Files',
+    multiple: true,
+    filters: [{ name: 'Audio', extensions: AUDIO_FILE_EXTENSIONS }],
+  });
+  if (!selected || selected.length === 0) return null;
+  return importAudioFiles(selected);
 }
 
-export async function invokeSaveSettings(settings: Settings): Promise<void> {
-  try {
-    await invoke('save_settings', { settings });
-  } catch (error) {
-    console.error('Failed to invoke save_settings:', error);
-    throw error;
-  }
+// ============================================
+// Transcription Jobs
+// ============================================
+
+/**
+ * Stop a running local transcription, or a queued job, with the given job id
+ */
+export async function cancelTranscription(jobId: string): Promise<void> {
+  return invoke('cancel_transcription', { jobId });
+}
+
+/**
+ * Add a local whisper transcription to the persistent background queue
+ */
+export async function enqueueTranscription(request: TranscriptionJobRequest): Promise<TranscriptionJob> {
+  return invoke<TranscriptionJob>('enqueue_transcription', { request });
+}
+
+/**
+ * All queued, running and finished jobs, oldest first
+ */
+export async function listTranscriptionJobs(): Promise<TranscriptionJob[]> {
+  return invoke<TranscriptionJob[]>('list_transcription_jobs');
+}
+
+/**
+ * Forget a finished job once its result has been handled
+ */
+export async function removeTranscriptionJob(jobId: string): Promise<void> {
+  return invoke('remove_transcription_job', { jobId });
+}
+
+// ============================================
+// Subtitle Export
+// ============================================
+
+export interface SubtitleTranscription {
+  language: string;
+  model: string;
+  whisperPath?: string | null;
+  // Lets the export be followed with onTranscriptionProgress and cancelled
+  jobId?: string;
+  // Overrides the whisper options in settings for this export
+  whisperOptions?: WhisperOptions;
+}
+
+/**
+ * Transcribe an audio file with local whisper and write SRT or WebVTT subtitles
+ */
+export async function exportSubtitles(
+  audioPath: string,
+  outputPath: string,
+  transcription: SubtitleTranscription,
+  options: SubtitleOptions = {}
+): Promise<SubtitleExport> {
+  return invoke<SubtitleExport>('export_subtitles', {
+    audioPath,
+    outputPath,
+    language: transcription.language,
+    model: transcription.model,
+    whisperPath: transcription.whisperPath ?? null,
+    options,
+    jobId: transcription.jobId ?? null,
+    whisperOptions: transcription.whisperOptions ?? null,
+  });
+}
+
+/**
+ * Ask where to save, then export subtitles. Returns null if the dialog was cancelled.
+ */
+export async function saveSubtitles(
+  audioPath: string,
+  transcription: SubtitleTranscription,
+  options: SubtitleOptions = {}
+): Promise<SubtitleExport | null> {
+  const format = options.format ?? 'srt';
+  const baseName = audioPath.split(/[\\/]/).pop()?.replace(/\.[^.]+$/, '') || 'subtitles';
+  const outputPath = await save({
+    defaultPath: `${baseName}.${format}`,
+    filters: [
+      format === 'srt'
+        ? { name: 'SubRip Subtitles', extensions: ['srt'] }
+        : { name: 'WebVTT Subtitles', extensions: ['vtt'] },
+    ],
+  });
+  if (!outputPath) return null;
+  return exportSubtitles(audioPath, outputPath, transcription, { ...options, format });
 }
 
 // ============================================
@@ -256,6 +417,88 @@ export async function onError(
   });
 }
 
+export async function onAudioLevel(
+  callback: (level: AudioLevel) => void
+): Promise<UnlistenFn> {
+  return listen<AudioLevel>('audio-level', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onRecordingStateChanged(
+  callback: (status: RecordingStatus) => void
+): Promise<UnlistenFn> {
+  return listen<RecordingStatus>('recording-state-changed', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Fired when a recording stopped itself after the configured silence.
+ * Collect the audio with `stopRecording`.
+ */
+export async function onRecordingAutoStopped(
+  callback: (status: RecordingStatus) => void
+): Promise<UnlistenFn> {
+  return listen<RecordingStatus>('recording-auto-stopped', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onAudioImportQueued(
+  callback: (files: ImportedAudioFile[]) => void
+): Promise<UnlistenFn> {
+  return listen<ImportedAudioFile[]>('audio-import-queued', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onTranscriptionProgress(
+  callback: (progress: TranscriptionProgress) => void
+): Promise<UnlistenFn> {
+  return listen<TranscriptionProgress>('transcription-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onTranscriptionJobChanged(
+  callback: (job: TranscriptionJob) => void
+): Promise<UnlistenFn> {
+  return listen<TranscriptionJob>('transcription-job-changed', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onVaultStatusChanged(
+  callback: (status: VaultStatus) => void
+): Promise<UnlistenFn> {
+  return listen<VaultStatus>('vault-status-changed', (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Fired by the tray "Unlock…" item, or by requestVaultUnlock, to show the
+ * passphrase prompt
+ */
+export async function onVaultUnlockRequested(callback: () => void): Promise<UnlistenFn> {
+  return listen('vault-unlock-requested', () => {
+    callback();
+  });
+}
+
+export async function requestVaultUnlock(): Promise<void> {
+  await emit('vault-unlock-requested');
+}
+
+export async function onProfileSwitched(
+  callback: (profile: string, settings: Settings) => void
+): Promise<UnlistenFn> {
+  return listen<{ profile: string; settings: Settings }>('profile-switched', (event) => {
+    callback(event.payload.profile, event.payload.settings);
+  });
+}
+
 // ============================================
 // History Management
 // ============================================
@@ -267,6 +510,9 @@ export interface HistoryItem {
   enrichedContent: string;
   enrichmentMode: string;
   duration: number;
+  // Spoken language, detected by whisper when transcribing with "auto"
+  language?: string;
+  languageProbability?: number;
 }
 
 export async function getHistory(): Promise<HistoryItem[]> {
@@ -348,16 +594,75 @@ export async function getAvailableModels(): Promise<WhisperModel[]> {
   }
 }
 
+/**
+ * Download a whisper model. Rejects with an AppError, with code 'CANCELLED'
+ * if the download was stopped with cancelDownload.
+ */
 export async function downloadWhisperModel(modelId: string): Promise<DownloadResult> {
-  try {
-    return await invoke<DownloadResult>('download_whisper_model', { modelId });
-  } catch (error) {
-    console.error('Failed to download whisper model:', error);
-    return {
-      success: false,
-      message: error instanceof Error ? error.message : 'Unknown error occurred',
-    };
-  }
+  return invoke<DownloadResult>('download_whisper_model', { modelId });
+}
+
+/**
+ * Stop a whisper model download or OLLAMA pull. Its progress listener gets a
+ * final 'cancelled' status.
+ */
+export async function cancelDownload(downloadId: string): Promise<void> {
+  return invoke('cancel_download', { downloadId });
+}
+
+export async function listDownloads(): Promise<DownloadInfo[]> {
+  return invoke<DownloadInfo[]>('list_downloads');
+}
+
+/**
+ * Replace the model catalog with the manifest at `source` (a URL or local
+ * path), or at the configured manifest source. Resolves to the new model list.
+ */
+export async function refreshModelCatalog(source?: string): Promise<WhisperModel[]> {
+  return invoke<WhisperModel[]>('refresh_model_catalog', { source: source ?? null });
+}
+
+/**
+ * Register a ggml-*.bin file as a model under `name`. The file is used where
+ * it is; registering it again renames it.
+ */
+export async function registerCustomModel(path: string, name: string): Promise<WhisperModel> {
+  return invoke<WhisperModel>('register_custom_model', { path, name });
+}
+
+/**
+ * Let the user pick a ggml-*.bin file and register it, named after the file.
+ * Returns null if the dialog was cancelled.
+ */
+export async function pickAndRegisterCustomModel(): Promise<WhisperModel | null> {
+  const selected = await open({
+    title: 'Add Whisper Model',
+    multiple: false,
+    filters: [{ name: 'Whisper model', extensions: ['bin'] }],
+  });
+  if (!selected) return null;
+  // ggml-large-v3-turbo-q5_0.bin -> large-v3-turbo-q5_0
+  const fileName = selected.split(/[\\/]/).pop() ?? selected;
+  const name = fileName.replace(/^ggml-/, '').replace(/\.bin$/, '');
+  return registerCustomModel(selected, name);
+}
+
+/** Forget a custom model without deleting its file */
+export async function unregisterCustomModel(modelId: string): Promise<void> {
+  return invoke('unregister_custom_model', { modelId });
+}
+
+/**
+ * Disk space used by models, history, cached audio and logs, plus leftover
+ * .tmp files. OLLAMA models are counted only if OLLAMA is reachable.
+ */
+export async function getStorageUsage(baseUrl?: string): Promise<StorageUsage> {
+  return invoke<StorageUsage>('get_storage_usage', { baseUrl: baseUrl ?? null });
+}
+
+/** Delete leftover .tmp files; partial downloads among them start over */
+export async function cleanTempFiles(): Promise<TempFileCleanup> {
+  return invoke<TempFileCleanup>('clean_temp_files');
 }
 
 export async function deleteWhisperModel(modelId: string): Promise<boolean> {
@@ -404,22 +709,18 @@ export async function getOllamaModels(): Promise<OllamaModel[]> {
   }
 }
 
-export async function pullOllamaModel(modelName: string): Promise<{ success: boolean; message?: string }> {
-  try {
-    return await invoke<{ success: boolean; message?: string }>('pull_ollama_model', { modelName });
-  } catch (error) {
-    console.error('Failed to pull OLLAMA model:', error);
-    return {
-      success: false,
-      message: error instanceof Error ? error.message : 'Unknown error occurred',
-    };
-  }
+/**
+ * Pull an OLLAMA model. Rejects with an AppError, with code 'CANCELLED' if
+ * the pull was stopped with cancelDownload.
+ */
+export async function pullOllamaModel(modelName: string): Promise<OllamaPullResult> {
+  return invoke<OllamaPullResult>('pull_ollama_model', { model: modelName });
 }
 
 export async function deleteOllamaModel(modelName: string): Promise<boolean> {
   try {
-    const result = await invoke<{ success: boolean }>('delete_ollama_model', { modelName });
-    return result.success;
+    await invoke('delete_ollama_model', { model: modelName });
+    return true;
   } catch (error) {
     console.error('Failed to delete OLLAMA model:', error);
     return false;
@@ -441,6 +742,7 @@ export async function onOllamaPullProgress(
 export interface NotionTestResult {
   success: boolean;
   message: string;
+  code?: AppErrorCode;
 }
 
 export interface NotionPage {
@@ -453,6 +755,7 @@ export interface NotionSearchResult {
   success: boolean;
   pages: NotionPage[];
   error?: string;
+  code?: AppErrorCode;
 }
 
 export interface NotionCreatePageResult {
@@ -460,39 +763,54 @@ export interface NotionCreatePageResult {
   pageId?: string;
   url?: string;
   error?: string;
+  code?: AppErrorCode;
 }
 
 /**
- * Test Notion API connection
+ * Test Notion API connection with the stored key. Pass apiKey only to check
+ * a key before it is saved with setSecret.
  */
-export async function notionTestConnection(apiKey: string): Promise<NotionTestResult> {
-  return invoke<NotionTestResult>('notion_test_connection', { apiKey });
+export async function notionTestConnection(apiKey?: string): Promise<NotionTestResult> {
+  try {
+    const result = await invoke<{ message: string }>('notion_test_connection', { apiKey });
+    return { success: true, message: result.message };
+  } catch (error) {
+    return { success: false, message: errorMessage(error), code: errorCode(error) };
+  }
 }
 
 /**
- * Search for accessible pages and databases in Notion
+ * Search for accessible pages and databases in Notion with the stored key
  */
-export async function notionSearchPages(apiKey: string): Promise<NotionSearchResult> {
-  return invoke<NotionSearchResult>('notion_search_pages', { apiKey });
+export async function notionSearchPages(): Promise<NotionSearchResult> {
+  try {
+    const result = await invoke<{ pages: NotionPage[] }>('notion_search_pages');
+    return { success: true, pages: result.pages };
+  } catch (error) {
+    return { success: false, pages: [], error: errorMessage(error), code: errorCode(error) };
+  }
 }
 
 /**
- * Create a new page in Notion
+ * Create a new page in Notion with the stored key
  */
 export async function notionCreatePage(
-  apiKey: string,
   parentId: string,
   parentType: string,
   title: string,
   content: string
 ): Promise<NotionCreatePageResult> {
-  return invoke<NotionCreatePageResult>('notion_create_page', {
-    apiKey,
-    parentId,
-    parentType,
-    title,
-    content,
-  });
+  try {
+    const result = await invoke<{ pageId?: string; url?: string }>('notion_create_page', {
+      parentId,
+      parentType,
+      title,
+      content,
+    });
+    return { success: true, ...result };
+  } catch (error) {
+    return { success: false, error: errorMessage(error), code: errorCode(error) };
+  }
 }
 
 // ============================================
@@ -511,7 +829,6 @@ export interface ExportToNotionOptions {
  */
 export async function exportToNotion(
   content: string,
-  apiKey: string,
   options: ExportToNotionOptions = {}
 ): Promise<{ success: boolean; url?: string; error?: string }> {
   const { title = 'Transcript', parentPageId, databaseId } = options;
@@ -536,7 +853,7 @@ export async function exportToNotion(
     };
   }
 
-  const result = await notionCreatePage(apiKey, targetId, targetType, title, content);
+  const result = await notionCreatePage(targetId, targetType, title, content);
   return result;
 }
 
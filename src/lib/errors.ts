@@ -0,0 +1,32 @@
+// Backend Errors - Helpers for the typed errors returned by Tauri commands
+
+import type { AppError, AppErrorCode } from '../types';
+
+/**
+ * Check whether a rejected invoke carries a typed backend error
+ */
+export function isAppError(error: unknown): error is AppError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as AppError).code === 'string' &&
+    typeof (error as AppError).message === 'string'
+  );
+}
+
+/**
+ * Readable message for any error thrown by invoke or the frontend
+ */
+export function errorMessage(error: unknown, fallback = 'Unknown error occurred'): string {
+  if (isAppError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  if (typeof error === 'string') return error;
+  return fallback;
+}
+
+/**
+ * Backend error code, if the error came from a backend command
+ */
+export function errorCode(error: unknown): AppErrorCode | undefined {
+  return isAppError(error) ? error.code : undefined;
+}
@@ -0,0 +1,58 @@
+// Shared error handling for hosted providers whose requests the backend
+// makes with the API key from the secret store
+
+import { LLMProviderError } from '../types/llm';
+import { isAppError, errorMessage } from '../lib/errors';
+
+/**
+ * Message from an OpenAI-style `{ error: { message } }` response body
+ */
+function responseErrorMessage(body: unknown): string | undefined {
+  if (typeof body !== 'string') return undefined;
+  try {
+    return JSON.parse(body).error?.message;
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Map a rejected backend call to an LLMProviderError
+ */
+export function toProviderError(error: unknown, provider: string, label: string): LLMProviderError {
+  if (error instanceof LLMProviderError) {
+    return error;
+  }
+
+  const fail = (code: string, message: string, retryable: boolean) =>
+    new LLMProviderError({ code, message, provider, retryable });
+
+  if (!isAppError(error)) {
+    return fail('API_ERROR', errorMessage(error, `Unknown ${label} API error`), true);
+  }
+
+  switch (error.code) {
+    case 'SECRET_MISSING':
+      return fail('NOT_CONFIGURED', `${label} API key is not configured`, false);
+    case 'VAULT_LOCKED':
+      return fail('VAULT_LOCKED', error.message, false);
+    case 'NETWORK_UNAVAILABLE':
+      return fail('NETWORK_ERROR', error.message, true);
+    case 'HTTP_ERROR': {
+      const status = Number(error.details?.status);
+      if (status === 401) {
+        return fail('INVALID_API_KEY', `Invalid ${label} API key`, false);
+      }
+      if (status === 429) {
+        return fail('RATE_LIMITED', `${label} rate limit exceeded. Please try again later.`, true);
+      }
+      return fail(
+        'API_ERROR',
+        responseErrorMessage(error.details?.body) || `${label} API error: ${status}`,
+        status >= 500
+      );
+    }
+    default:
+      return fail('API_ERROR', error.message, false);
+  }
+}
@@ -114,29 +114,19 @@ export class LLMRouter {
     return provider.complete(request);
   }
 
-  // Configure a provider with API key
-  configureProvider(
-    name: LLMProvider,
-    apiKey: string,
-    model?: string
-  ): void {
+  // Configure a provider's model. API keys stay in the backend secret store.
+  configureProvider(name: LLMProvider, model?: string): void {
     const provider = this.providers.get(name);
     if (!provider) {
       throw new Error(`Provider ${name} is not registered`);
     }
 
     // Type-safe configuration based on provider
-    if (name === 'openai' && provider instanceof OpenAIProvider) {
-      provider.setApiKey(apiKey);
-      if (model) provider.setModel(model);
-      provider.setLanguage(this.language);
-    } else if (name === 'openrouter' && provider instanceof OpenRouterProvider) {
-      provider.setApiKey(apiKey);
-      if (model) provider.setModel(model);
-      provider.setLanguage(this.language);
-    } else if (name === 'ollama' && provider instanceof OllamaProvider) {
-      // OLLAMA doesn't require an API key (local service)
-      // The apiKey parameter is ignored for OLLAMA
+    if (
+      (name === 'openai' && provider instanceof OpenAIProvider) ||
+      (name === 'openrouter' && provider instanceof OpenRouterProvider) ||
+      (name === 'ollama' && provider instanceof OllamaProvider)
+    ) {
       if (model) provider.setModel(model);
       provider.setLanguage(this.language);
     }
@@ -1,10 +1,15 @@
 // Speech-to-Text Service - whisper.cpp integration with Whisper API fallback
 
-import type { Language, TranscriptionResult } from '../types';
+import type { Language, SilenceTrim, TranscriptionResult, TranscriptionSegment } from '../types';
+import { errorMessage as describeError, isAppError } from '../lib/errors';
+import { hasSecret, transcribeWithOpenAI } from '../lib/api';
 
 export interface STTProvider {
   name: string;
-  transcribe(audioBlob: Blob, language: Language): Promise<TranscriptionResult>;
+  // `jobId` lets providers that support it report progress and be cancelled
+  transcribe(audioBlob: Blob, language: Language, jobId?: string): Promise<TranscriptionResult>;
+  // Transcribe a file on disk without loading it into the webview
+  transcribeFile?(audioPath: string, language: Language, jobId?: string): Promise<TranscriptionResult>;
   isAvailable(): Promise<boolean>;
 }
 
@@ -65,6 +70,10 @@ export class LocalWhisperProvider implements STTProvider {
     this.whisperPath = path;
   }
 
+  getWhisperPath(): string | null {
+    return this.whisperPath;
+  }
+
   setModel(model: string): void {
     this.modelPath = model;
   }
@@ -92,7 +101,43 @@ export class LocalWhisperProvider implements STTProvider {
     }
   }
 
-  async transcribe(audioBlob: Blob, language: Language): Promise<TranscriptionResult> {
+  async transcribeFile(audioPath: string, language: Language, jobId?: string): Promise<TranscriptionResult> {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      // The backend decodes any supported container itself
+      const result = await invoke<{
+        text: string;
+        language: string;
+        languageProbability: number | null;
+        duration: number;
+        trimmedSilence: SilenceTrim;
+        segments: TranscriptionSegment[];
+      }>('transcribe_audio', {
+        audioPath,
+        language,
+        model: this.modelPath,
+        whisperPath: this.whisperPath,
+        jobId: jobId ?? null,
+      });
+
+      return {
+        text: result.text.trim(),
+        language: result.language as Language,
+        languageProbability: result.languageProbability ?? undefined,
+        duration: result.duration,
+        trimmedSilence: result.trimmedSilence,
+        segments: result.segments,
+      };
+    } catch (error) {
+      throw new STTError(
+        isAppError(error) ? error.code : 'LOCAL_TRANSCRIPTION_FAILED',
+        `Failed to transcribe ${audioPath}: ${describeError(error)}`,
+        true
+      );
+    }
+  }
+
+  async transcribe(audioBlob: Blob, language: Language, jobId?: string): Promise<TranscriptionResult> {
     const steps: string[] = [];
 
     try {
@@ -114,33 +159,42 @@ export class LocalWhisperProvider implements STTProvider {
       await writeFile(audioPath, new Uint8Array(arrayBuffer));
 
       steps.push(`Calling whisper transcribe_audio (whisperPath: ${this.whisperPath || 'auto-detect'})...`);
-      // Call whisper via Tauri command
-      const result = await invoke<{
+      // Call whisper via Tauri command, removing the temp file even if it
+      // fails or the job is cancelled
+      let result: {
         text: string;
         language: string;
+        languageProbability: number | null;
         duration: number;
-      }>('transcribe_audio', {
-        audioPath,
-        language,
-        model: this.modelPath,
-        whisperPath: this.whisperPath,
-      });
-
-      steps.push('Cleaning up temp file...');
-      // Clean up temp file
+        trimmedSilence: SilenceTrim;
+        segments: TranscriptionSegment[];
+      };
       try {
-        await remove(audioPath);
-      } catch {
-        // Ignore cleanup errors
+        result = await invoke('transcribe_audio', {
+          audioPath,
+          language,
+          model: this.modelPath,
+          whisperPath: this.whisperPath,
+          jobId: jobId ?? null,
+        });
+      } finally {
+        try {
+          await remove(audioPath);
+        } catch {
+          // Ignore cleanup errors
+        }
       }
 
       return {
         text: result.text.trim(),
         language: result.language as Language,
+        languageProbability: result.languageProbability ?? undefined,
         duration: result.duration,
+        trimmedSilence: result.trimmedSilence,
+        segments: result.segments,
       };
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : String(error);
+      const errorMessage = describeError(error);
       const detailedMessage = `Local transcription failed at step: ${steps[steps.length - 1] || 'initialization'}\n\nError: ${errorMessage}\n\nSteps completed:\n${steps.map((s, i) => `${i + 1}. ${s}`).join('\n')}`;
 
       console.error('Transcription error details:', {
@@ -150,7 +204,7 @@ export class LocalWhisperProvider implements STTProvider {
       });
 
       throw new STTError(
-        'LOCAL_TRANSCRIPTION_FAILED',
+        isAppError(error) ? error.code : 'LOCAL_TRANSCRIPTION_FAILED',
         detailedMessage,
         true
       );
@@ -160,81 +214,62 @@ export class LocalWhisperProvider implements STTProvider {
 
 /**
  * OpenAI Whisper API Provider
- * Cloud-based fallback when local whisper is not available
+ * Cloud-based fallback when local whisper is not available. The backend
+ * uploads the file with the key from the secret store.
  */
 export class OpenAIWhisperProvider implements STTProvider {
   name = 'openai-whisper';
-  private apiKey: string | null = null;
-
-  setApiKey(apiKey: string): void {
-    this.apiKey = apiKey;
-  }
 
   async isAvailable(): Promise<boolean> {
-    return this.apiKey !== null && this.apiKey.length > 0;
+    // The backend falls back to the OpenAI key without a dedicated one
+    return (await hasSecret('openaiWhisper')) || (await hasSecret('openai'));
   }
 
-  async transcribe(audioBlob: Blob, language: Language): Promise<TranscriptionResult> {
-    if (!this.apiKey) {
-      throw new STTError('NOT_CONFIGURED', 'OpenAI API key is not configured', false);
-    }
-
+  async transcribeFile(audioPath: string, language: Language): Promise<TranscriptionResult> {
     try {
-      // Convert to format Whisper API accepts
-      const audioFile = new File([audioBlob], 'recording.webm', {
-        type: audioBlob.type || 'audio/webm',
-      });
-
-      const formData = new FormData();
-      formData.append('file', audioFile);
-      formData.append('model', 'whisper-1');
-      formData.append('language', language);
-      formData.append('response_format', 'verbose_json');
-
-      const response = await fetch('https://api.openai.com/v1/audio/transcriptions', {
-        method: 'POST',
-        headers: {
-          'Authorization': `Bearer ${this.apiKey}`,
-        },
-        body: formData,
-      });
-
-      if (!response.ok) {
-        const errorData = await response.json().catch(() => ({}));
-
-        if (response.status === 401) {
-          throw new STTError('INVALID_API_KEY', 'Invalid OpenAI API key', false);
-        }
-
-        throw new STTError(
-          'API_ERROR',
-          errorData.error?.message || `Whisper API error: ${response.status}`,
-          response.status >= 500
-        );
-      }
-
-      const data = await response.json();
-
+      const result = await transcribeWithOpenAI(audioPath, language);
       return {
-        text: data.text?.trim() || '',
-        language: (data.language as Language) || language,
-        duration: data.duration || 0,
-        confidence: data.segments?.[0]?.avg_logprob
-          ? Math.exp(data.segments[0].avg_logprob)
-          : undefined,
+        text: result.text,
+        language: result.language as Language,
+        duration: result.duration,
       };
     } catch (error) {
-      if (error instanceof STTError) {
-        throw error;
+      if (!isAppError(error)) {
+        throw new STTError('TRANSCRIPTION_FAILED', describeError(error, 'Transcription failed'), true);
+      }
+      const status = Number(error.details?.status);
+      if (error.code === 'SECRET_MISSING') {
+        throw new STTError('NOT_CONFIGURED', 'OpenAI API key is not configured', false);
+      }
+      if (error.code === 'HTTP_ERROR' && status === 401) {
+        throw new STTError('INVALID_API_KEY', 'Invalid OpenAI API key', false);
       }
-
       throw new STTError(
-        'TRANSCRIPTION_FAILED',
-        error instanceof Error ? error.message : 'Transcription failed',
-        true
+        error.code,
+        error.message,
+        error.code === 'NETWORK_UNAVAILABLE' || status >= 500
       );
     }
   }
+
+  async transcribe(audioBlob: Blob, language: Language): Promise<TranscriptionResult> {
+    const { writeFile, remove } = await import('@tauri-apps/plugin-fs');
+    const { tempDir } = await import('@tauri-apps/api/path');
+
+    // The Whisper API picks the decoder from the file extension
+    const extension = audioBlob.type.includes('wav') ? 'wav' : 'webm';
+    const audioPath = `${await tempDir()}recording-${Date.now()}.${extension}`;
+    await writeFile(audioPath, new Uint8Array(await audioBlob.arrayBuffer()));
+    try {
+      return await this.transcribeFile(audioPath, language);
+    } finally {
+      try {
+        await remove(audioPath);
+      } catch {
+        // Ignore cleanup errors
+      }
+    }
+  }
 }
 
 /**
@@ -286,13 +321,6 @@ export class STTService {
     this.preferredProvider = name;
   }
 
-  configureOpenAI(apiKey: string): void {
-    const provider = this.providers.get('openai-whisper');
-    if (provider && provider instanceof OpenAIWhisperProvider) {
-      provider.setApiKey(apiKey);
-    }
-  }
-
   configureWhisperPath(path: string | undefined): void {
     const provider = this.providers.get('local-whisper');
     if (provider && provider instanceof LocalWhisperProvider) {
@@ -336,7 +364,7 @@ export class STTService {
     return null;
   }
 
-  async transcribe(audioBlob: Blob, language: Language): Promise<TranscriptionResult> {
+  async transcribe(audioBlob: Blob, language: Language, jobId?: string): Promise<TranscriptionResult> {
     const provider = await this.getAvailableProvider();
 
     if (!provider) {
@@ -348,7 +376,30 @@ export class STTService {
     }
 
     console.log(`Using STT provider: ${provider.name}`);
-    return provider.transcribe(audioBlob, language);
+    return provider.transcribe(audioBlob, language, jobId);
+  }
+
+  async transcribeFile(audioPath: string, language: Language, jobId?: string): Promise<TranscriptionResult> {
+    const provider = await this.getAvailableProvider();
+
+    if (!provider) {
+      throw new STTError(
+        'NO_PROVIDER',
+        'No speech-to-text provider is available. Please configure an API key or enable local transcription.',
+        false
+      );
+    }
+
+    console.log(`Using STT provider: ${provider.name}`);
+    // Files are read by the backend; the webview has no access to arbitrary paths
+    if (!provider.transcribeFile) {
+      throw new STTError(
+        'NOT_SUPPORTED',
+        `${provider.name} cannot transcribe audio files`,
+        false
+      );
+    }
+    return provider.transcribeFile(audioPath, language, jobId);
   }
 
   getProviderNames(): string[] {
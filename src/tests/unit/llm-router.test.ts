@@ -1,4 +1,5 @@
-import { describe, it, expect, beforeEach } from 'vitest';
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { invoke } from '@tauri-apps/api/core';
 import { LLMRouter, getLLMRouter, resetLLMRouter } from '../../services/llm-router';
 import { LLMProviderError } from '../../types/llm';
 
@@ -83,17 +84,26 @@ describe('LLM Router', () => {
       expect(isConfigured).toBe(false);
     });
 
-    it('should configure provider with API key', () => {
+    it('should treat a key in the secret store as configured', async () => {
+      vi.mocked(invoke).mockResolvedValueOnce([
+        { name: 'openai', configured: true, backend: 'keyring', locked: false },
+      ]);
+      const router = new LLMRouter();
+      expect(await router.isProviderConfigured('openai')).toBe(true);
+      expect(invoke).toHaveBeenCalledWith('get_secret_status', { name: 'openai' });
+    });
+
+    it('should configure provider model', () => {
       const router = new LLMRouter();
       expect(() => {
-        router.configureProvider('openai', 'test-api-key', 'gpt-4o-mini');
+        router.configureProvider('openai', 'gpt-4o-mini');
       }).not.toThrow();
     });
 
-    it('should configure OLLAMA provider without API key', () => {
+    it('should configure OLLAMA provider model', () => {
       const router = new LLMRouter();
       expect(() => {
-        router.configureProvider('ollama', '', 'llama3.2');
+        router.configureProvider('ollama', 'llama3.2');
       }).not.toThrow();
     });
 
@@ -43,11 +43,7 @@ export interface Settings {
   customPrompt?: string;
   autoEnrich: boolean;
   showNotifications: boolean;
-  // API Keys (stored securely)
-  openaiApiKey?: string;
-  openrouterApiKey?: string;
-  notionApiKey?: string;
-  openaiWhisperApiKey?: string;
+  // API keys are kept in the secret store, not in settings
   // Notion settings
   notionDefaultPageId?: string;
   notionDefaultPageName?: string;
@@ -60,6 +56,155 @@ export interface Settings {
   whisperModel?: string;
   // Setup
   setupComplete?: boolean;
+  // Secret vault (only used when no OS keyring is available)
+  vaultAutoLockMinutes?: number;
+  // Proxy, CA bundle and timeouts for outgoing requests
+  network?: NetworkSettings;
+  // Silence trimming and auto-stop for recordings
+  vad?: VadSettings;
+  // Background transcription queue
+  queue?: QueueSettings;
+  whisper?: WhisperOptions;
+  vocabulary?: VocabularySettings;
+  models?: ModelSettings;
+}
+
+export interface NetworkSettings {
+  proxyUrl?: string | null;
+  noProxy?: string | null;
+  caBundlePath?: string | null;
+  connectTimeoutSecs: number;
+  readTimeoutSecs: number;
+  userAgent?: string | null;
+}
+
+export interface VadSettings {
+  trimSilence: boolean;
+  thresholdDb: number;
+  paddingMs: number;
+  // 0 disables auto-stop
+  autoStopSilenceSecs: number;
+}
+
+// whisper.cpp decoding parameters; unset fields keep whisper's defaults
+export interface WhisperOptions {
+  threads?: number | null;
+  processors?: number | null;
+  beamSize?: number | null;
+  bestOf?: number | null;
+  temperature?: number | null;
+  // 0 disables temperature fallback
+  temperatureIncrement?: number | null;
+  maxSegmentLength?: number | null;
+  entropyThreshold?: number | null;
+  logprobThreshold?: number | null;
+}
+
+// A whole word or phrase replaced in transcripts. Lowercase `from` matches any
+// capitalisation; lowercase `to` follows the capitalisation of the match.
+export interface VocabularyReplacement {
+  from: string;
+  to: string;
+}
+
+export interface VocabularySettings {
+  // Passed to whisper as its initial prompt, most important first
+  terms: string[];
+  replacements: VocabularyReplacement[];
+}
+
+export interface ModelSettings {
+  // URL or local path of a model manifest that replaces the bundled catalog
+  manifestSource?: string | null;
+  // Absolute directory catalog models are downloaded to; defaults to
+  // ~/.voiceintelligence/whisper
+  directory?: string | null;
+  // Model files registered by the user and used in place
+  custom?: CustomModel[];
+}
+
+export interface CustomModel {
+  // 'custom:<file name without .bin>'
+  id: string;
+  name: string;
+  path: string;
+  multilingual: boolean;
+}
+
+export interface QueueSettings {
+  // Jobs transcribed at the same time, 1 – 4
+  parallelJobs: number;
+}
+
+export interface SilenceTrim {
+  leadingSecs: number;
+  trailingSecs: number;
+}
+
+export interface TranscriptionToken {
+  text: string;
+  probability: number;
+  // Times relative to the original audio, if whisper reported them
+  startMs: number | null;
+  endMs: number | null;
+}
+
+// Timed stretch of a local whisper transcript, times relative to the original audio
+export interface TranscriptionSegment {
+  startMs: number;
+  endMs: number;
+  text: string;
+  tokens: TranscriptionToken[];
+  noSpeechProb: number | null;
+}
+
+// Emitted while local whisper works through a transcription job
+export interface TranscriptionProgress {
+  jobId: string;
+  percent: number;
+}
+
+export type TranscriptionJobState = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export interface TranscriptionJobRequest {
+  audioPath: string;
+  language: Language;
+  model: string;
+  whisperPath?: string | null;
+  // Overrides the whisper options in settings for this job
+  whisperOptions?: WhisperOptions | null;
+  // Applied once the transcript is ready
+  enrichmentMode: EnrichmentMode;
+  outputTarget: OutputTarget;
+  // Remove the audio after a successful transcription (app-owned recordings)
+  deleteAudio?: boolean;
+}
+
+// Job in the persistent backend queue; survives app restarts
+export interface TranscriptionJob extends TranscriptionJobRequest {
+  id: string;
+  state: TranscriptionJobState;
+  createdAt: number;
+  updatedAt: number;
+  attempts: number;
+  result: TranscriptionResult | null;
+  error: AppError | null;
+}
+
+export type SubtitleFormat = 'srt' | 'vtt';
+
+// Rules subtitle cues are re-flowed by; omitted fields use the backend defaults
+export interface SubtitleOptions {
+  format?: SubtitleFormat;
+  maxLineChars?: number; // default 42
+  maxLines?: number; // default 2
+  maxCueMs?: number; // default 7000
+  offsetMs?: number; // may be negative
+}
+
+export interface SubtitleExport {
+  path: string;
+  cueCount: number;
 }
 
 export interface HistoryEntry {
@@ -77,6 +222,10 @@ export interface TranscriptionResult {
   language: Language;
   duration: number;
   confidence?: number;
+  // Confidence in the detected language when transcribing with "auto"
+  languageProbability?: number;
+  trimmedSilence?: SilenceTrim;
+  segments?: TranscriptionSegment[];
 }
 
 export interface EnrichmentResult {
@@ -126,10 +275,21 @@ export interface WhisperModel {
   name: string;
   size: string;
   sizeBytes: number;
-  downloadUrl: string;
+  fileName: string;
+  // null for custom models, which aren't downloaded
+  downloadUrl: string | null;
   installed: boolean;
   installedPath?: string;
+  // Size of the installed file
+  sizeOnDisk?: number | null;
   isMultilingual: boolean;
+  // Expected SHA-256 of the model file, checked after downloading
+  sha256?: string | null;
+  recommendedRamMb: number;
+  // Quantization type such as 'q5_0', for quantized files
+  quantization?: string | null;
+  // Registered by the user from a file outside the models directory
+  custom: boolean;
 }
 
 export interface DownloadProgress {
@@ -137,15 +297,141 @@ export interface DownloadProgress {
   downloaded: number;
   total: number;
   percentage: number;
-  status: 'starting' | 'downloading' | 'completed' | 'error';
+  // 'verifying' while a resumed download's partial file is checksummed
+  status: 'starting' | 'verifying' | 'downloading' | 'completed' | 'cancelled' | 'error';
+}
+
+// A model download in flight, as listed by list_downloads
+export interface DownloadInfo {
+  // 'whisper:<model id>' or 'ollama:<model name>', accepted by cancel_download
+  id: string;
+  kind: 'whisperModel' | 'ollamaModel';
+  modelId: string;
+  downloaded: number;
+  total: number | null;
+  startedAt: number;
+  cancelling: boolean;
+}
+
+// Disk usage in bytes, as reported by get_storage_usage
+export interface StorageUsage {
+  whisperModels: number;
+  // null when OLLAMA isn't reachable
+  ollamaModels: number | null;
+  history: number;
+  cachedAudio: number;
+  logs: number;
+  // .tmp files left by interrupted downloads, removed by clean_temp_files
+  tempFiles: TempFile[];
+  modelsDirectory: string;
+  modelsDirectoryFree: number | null;
+}
+
+export interface TempFile {
+  path: string;
+  size: number;
+}
+
+export interface TempFileCleanup {
+  removed: string[];
+  freedBytes: number;
 }
 
 export interface DownloadResult {
-  success: boolean;
-  message: string;
   modelPath?: string;
 }
 
+export interface InstallResult {
+  path: string;
+}
+
+export interface OllamaPullResult {
+  model: string;
+  status: string;
+}
+
+// Native audio capture
+export interface InputDevice {
+  id: string;
+  name: string;
+  isDefault: boolean;
+}
+
+export interface AudioLevel {
+  rms: number;
+  peak: number;
+  elapsedMs: number;
+}
+
+export interface RecordingResult {
+  path: string;
+  durationSecs: number;
+  device: string;
+}
+
+export interface RecordingStatus {
+  recording: boolean;
+  device: string | null;
+  elapsedMs: number;
+  path: string | null;
+}
+
+// Audio files queued for transcription with import_audio_files
+export interface ImportedAudioFile {
+  id: string;
+  path: string;
+  fileName: string;
+  sizeBytes: number;
+}
+
+export interface AudioImportResult {
+  queued: ImportedAudioFile[];
+  rejected: { path: string; error: AppError }[];
+}
+
+// Errors returned by backend commands
+export type AppErrorCode =
+  | 'WHISPER_NOT_FOUND'
+  | 'WHISPER_FAILED'
+  | 'EMPTY_TRANSCRIPTION'
+  | 'WHISPER_INSTALL_FAILED'
+  | 'AUDIO_NOT_FOUND'
+  | 'UNSUPPORTED_AUDIO_FORMAT'
+  | 'AUDIO_DEVICE_UNAVAILABLE'
+  | 'RECORDING_IN_PROGRESS'
+  | 'NOT_RECORDING'
+  | 'AUDIO_ERROR'
+  | 'MODEL_MISSING'
+  | 'UNKNOWN_MODEL'
+  | 'MODEL_CHECKSUM_MISMATCH'
+  | 'DOWNLOAD_IN_PROGRESS'
+  | 'INSUFFICIENT_DISK_SPACE'
+  | 'NETWORK_UNAVAILABLE'
+  | 'HTTP_ERROR'
+  | 'HTTP_REQUEST_FAILED'
+  | 'NETWORK_CONFIG_INVALID'
+  | 'NOTION_UNAUTHORIZED'
+  | 'NOTION_FORBIDDEN'
+  | 'NOTION_NOT_FOUND'
+  | 'OLLAMA_ERROR'
+  | 'SECRET_MISSING'
+  | 'VAULT_LOCKED'
+  | 'INCORRECT_PASSPHRASE'
+  | 'VAULT_KDF_PARAMS_INVALID'
+  | 'SECRET_STORE_ERROR'
+  | 'INVALID_SETTING'
+  | 'SETTINGS_ERROR'
+  | 'INVALID_INPUT'
+  | 'IO_ERROR'
+  | 'CANCELLED'
+  | 'INTERNAL_ERROR';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details: Record<string, unknown> | null;
+}
+
 // Default settings
 export const DEFAULT_SETTINGS: Settings = {
   hotkey: 'CommandOrControl+Shift+Space',